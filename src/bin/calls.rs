@@ -0,0 +1,85 @@
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+struct Rect {
+    top_left: Point,
+    bottom_right: Point,
+}
+
+fn sum(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+fn half(x: f64) -> f64 {
+    x / 2.0
+}
+
+fn is_even(x: u64) -> bool {
+    x.is_multiple_of(2)
+}
+
+fn find(v: u32) -> Option<u32> {
+    if v > 3 {
+        Some(v)
+    } else {
+        None
+    }
+}
+
+fn make_point(x: f64, y: f64) -> Point {
+    Point { x, y }
+}
+
+fn make_rect(width: f64, height: f64) -> Rect {
+    Rect {
+        top_left: make_point(0.0, height),
+        bottom_right: make_point(width, 0.0),
+    }
+}
+
+fn greeting(name: &str) -> String {
+    format!("hello, {name}")
+}
+
+fn nothing() {
+    println!("nothing");
+}
+
+fn main() {
+    let s = sum(1, 2);
+    let h = half(5.0);
+    let e = is_even(4);
+    let f = find(5);
+    let p = make_point(1.5, 2.5);
+    let r = make_rect(3.0, 4.0);
+    let g = greeting("bs");
+    nothing();
+    let area = (r.bottom_right.x - r.top_left.x) * (r.top_left.y - r.bottom_right.y);
+    println!("{s} {h} {e} {f:?} {} {} {area} {g}", p.x, p.y);
+
+    let d = p.dist(&r.top_left);
+    let q = p.scaled(2.0);
+    #[allow(clippy::useless_vec)] // debugger calls `Vec::len` on it
+    let v = vec![1, 2, 3];
+    let n = read_ptr(&v[2]);
+    println!("{d} {} {} {} {n}", q.x, q.y, v.len());
+}
+
+impl Point {
+    fn dist(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+
+    fn scaled(&self, k: f64) -> Point {
+        Point {
+            x: self.x * k,
+            y: self.y * k,
+        }
+    }
+}
+
+fn read_ptr(ptr: *const i32) -> i32 {
+    unsafe { *ptr }
+}
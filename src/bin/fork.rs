@@ -0,0 +1,27 @@
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{execv, fork, ForkResult};
+use std::env;
+use std::ffi::CString;
+
+fn main() {
+    match unsafe { fork() }.unwrap() {
+        ForkResult::Child => {
+            let result = worker(2);
+            println!("child result: {result}");
+
+            let calc = env::current_exe().unwrap().with_file_name("calc");
+            let calc = CString::new(calc.to_str().unwrap()).unwrap();
+            let Err(e) = execv(&calc, &[&calc]);
+            panic!("exec failed: {e}");
+        }
+        ForkResult::Parent { child } => {
+            let status = waitpid(child, None).unwrap();
+            assert_eq!(status, WaitStatus::Exited(child, 0));
+            println!("parent done");
+        }
+    }
+}
+
+fn worker(n: u32) -> u32 {
+    n * 2
+}
@@ -0,0 +1,25 @@
+// without `result` binding `finish` from inlined `square` lands on a line 0 instruction
+#[allow(clippy::let_and_return)]
+#[inline(always)]
+fn square(x: u64) -> u64 {
+    let result = x * x;
+    result
+}
+
+#[inline(always)]
+fn sum_of_squares(a: u64, b: u64) -> u64 {
+    let sa = square(a);
+    let sb = square(b);
+    sa + sb
+}
+
+#[inline(never)]
+fn calculate(a: u64, b: u64) -> u64 {
+    let total = sum_of_squares(a, b);
+    total + 1
+}
+
+fn main() {
+    let result = calculate(3, 4);
+    println!("{result}");
+}
@@ -0,0 +1,11 @@
+use std::thread;
+
+fn main() {
+    let handle = thread::spawn(|| {
+        panic!("panic in thread");
+    });
+    assert!(handle.join().is_err());
+
+    let code = 42;
+    panic!("panic in main, code: {code}");
+}
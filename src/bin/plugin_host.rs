@@ -0,0 +1,24 @@
+use nix::libc;
+use std::ffi::CString;
+
+/// Load `libplugin.so` at runtime (after program start) and call its function.
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./target/debug/libplugin.so".to_string());
+    let path = CString::new(path).unwrap();
+    let symbol = CString::new("print_sum").unwrap();
+
+    unsafe {
+        let handle = libc::dlopen(path.as_ptr(), libc::RTLD_NOW);
+        assert!(!handle.is_null(), "plugin not found");
+
+        let print_sum: extern "C" fn(i64, i64) =
+            std::mem::transmute(libc::dlsym(handle, symbol.as_ptr()));
+        print_sum(1, 2);
+
+        libc::dlclose(handle);
+    }
+
+    println!("plugin unloaded");
+}
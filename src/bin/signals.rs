@@ -0,0 +1,19 @@
+use nix::sys::signal::{raise, signal, SigHandler, Signal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HANDLED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_usr1(_: i32) {
+    HANDLED.store(true, Ordering::SeqCst);
+}
+
+fn main() {
+    unsafe { signal(Signal::SIGUSR1, SigHandler::Handler(handle_usr1)) }.unwrap();
+    raise(Signal::SIGUSR1).unwrap();
+
+    if HANDLED.load(Ordering::SeqCst) {
+        println!("signal handled");
+    } else {
+        println!("signal ignored");
+    }
+}
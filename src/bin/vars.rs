@@ -409,6 +409,18 @@ fn ptr_to_array() {
     let nop: Option<u8> = None;
 }
 
+fn generic<T: Copy>(val: T) -> T {
+    let local = [val; 2];
+    local[1]
+}
+
+#[allow(unused)]
+fn generics() {
+    let a = generic(1_u8);
+    let b = generic(2_i64);
+    let c = generic('c');
+}
+
 pub fn main() {
     scalar_types();
     compound_types();
@@ -435,4 +447,5 @@ pub fn main() {
     atomics();
     shared_ptrs();
     ptr_to_array();
+    generics();
 }
@@ -1,8 +1,12 @@
+use crate::console::variable::render_variable_ir;
 use crate::console::view::FileView;
 use crate::debugger::address::RelocatedAddress;
+use crate::debugger::uw::Backtrace;
+use crate::debugger::variable::VariableIR;
 use crate::debugger::EventHook;
-use crate::debugger::Place;
+use crate::debugger::{PanicInfo, Place, Watchpoint};
 use nix::libc::c_int;
+use nix::unistd::Pid;
 
 pub(super) struct TerminalHook {
     file_view: FileView,
@@ -31,4 +35,72 @@ impl EventHook for TerminalHook {
     fn on_exit(&self, code: i32) {
         println!("Program exit with code: {code}");
     }
+
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        mb_place: Option<Place>,
+        watchpoint: &Watchpoint,
+        old: Option<&VariableIR>,
+        new: Option<&VariableIR>,
+    ) -> anyhow::Result<()> {
+        let Some(new) = new else {
+            println!(
+                "Watchpoint {} ({}) deleted, variable goes out of scope",
+                watchpoint.number, watchpoint.expr
+            );
+            return Ok(());
+        };
+
+        println!(
+            "Hit watchpoint {} ({}) at address {}",
+            watchpoint.number, watchpoint.expr, pc
+        );
+        match old {
+            Some(old) => {
+                println!("old value: {}", render_variable_ir(old, 0));
+                println!("new value: {}", render_variable_ir(new, 0));
+            }
+            None => println!("value: {}", render_variable_ir(new, 0)),
+        }
+        if let Some(place) = mb_place {
+            println!("{}:{}", place.file.display(), place.line_number);
+            println!("{}", self.file_view.render_source(&place, 1)?);
+        }
+        Ok(())
+    }
+
+    fn on_panic(
+        &self,
+        tid: Pid,
+        panic: &PanicInfo,
+        backtrace: Option<&Backtrace>,
+    ) -> anyhow::Result<()> {
+        match panic.location {
+            Some(ref location) => println!(
+                "Thread {tid} panicked at {}:{}:{}:",
+                location.file, location.line, location.column
+            ),
+            None => println!("Thread {tid} panicked:"),
+        }
+        println!(
+            "{}",
+            panic.message.as_deref().unwrap_or("unknown panic message")
+        );
+
+        if let Some(backtrace) = backtrace {
+            backtrace.iter().for_each(|part| match part.place.as_ref() {
+                Some(place) => {
+                    println!(
+                        "{:#016X} - {} ({:#016X}) + {:#X}",
+                        part.ip, place.func_name, place.start_ip, place.offset,
+                    );
+                }
+                None => {
+                    println!("{:#016X} - ????", part.ip)
+                }
+            })
+        }
+        Ok(())
+    }
 }
@@ -1,13 +1,15 @@
 use super::debugger::command::Continue;
 use crate::console::hook::TerminalHook;
-use crate::console::variable::render_variable_ir;
+use crate::console::variable::{render_variable_ir, render_variable_ir_with_options};
 use crate::console::view::FileView;
+use crate::debugger::address::{PCValue, RelocatedAddress};
 use crate::debugger::command::{
-    Arguments, Backtrace, Break, Frame, Run, StepI, StepInto, StepOut, StepOver, Symbol, Trace,
-    Variables,
+    Advance, Arguments, Backtrace, Break, Breakpoints, Call, Catch, CatchEvent, Checkpoint,
+    ContinueWithSignal, Examine, Frame, Handle, Inferiors, Interrupt, Print, Run, Set, StepI,
+    StepInto, StepOut, StepOver, Symbol, Thread, Trace, Until, Variables, Watch,
 };
 use crate::debugger::variable::render::RenderRepr;
-use crate::debugger::{command, Debugger};
+use crate::debugger::{command, uw, Debugger, WatchpointKind};
 use command::{Memory, Register};
 use nix::unistd::Pid;
 use rustyline::Editor;
@@ -15,7 +17,7 @@ use std::sync::mpsc;
 use std::thread;
 
 pub mod hook;
-mod variable;
+pub mod variable;
 pub mod view;
 
 pub struct AppBuilder {
@@ -39,6 +41,12 @@ impl AppBuilder {
         let debugger = Debugger::new(program, pid, hook)?;
         Ok(TerminalApplication { debugger })
     }
+
+    pub fn attach(self, pid: Pid) -> anyhow::Result<TerminalApplication> {
+        let hook = TerminalHook::new(self.file_view);
+        let debugger = Debugger::attach(pid, hook)?;
+        Ok(TerminalApplication { debugger })
+    }
 }
 
 enum ControlAction {
@@ -112,8 +120,147 @@ impl TerminalApplication {
 
         match command.to_lowercase().as_str() {
             "r" | "run" => Run::new(&mut self.debugger).run()?,
-            "c" | "continue" => Continue::new(&mut self.debugger).run()?,
-            "b" | "break" => Break::new(&mut self.debugger, args)?.run()?,
+            "c" | "continue" => Continue::new(&mut self.debugger, args)?.run()?,
+            "interrupt" => Interrupt::new(&mut self.debugger).run()?,
+            "signal" => ContinueWithSignal::new(&mut self.debugger, args)?.run()?,
+            "handle" => {
+                let cmd = Handle::new(&mut self.debugger, args)?;
+                let signal = cmd.signal;
+                let action = cmd.run()?;
+                let yes_no = |flag: bool| if flag { "Yes" } else { "No" };
+                println!("{:<10}{:<8}{:<8}Pass", "Signal", "Stop", "Print");
+                println!(
+                    "{:<10}{:<8}{:<8}{}",
+                    signal.as_str(),
+                    yes_no(action.stop),
+                    yes_no(action.print),
+                    yes_no(action.pass)
+                );
+            }
+            "set" => Set::new(&mut self.debugger, args)?.run()?,
+            "inferior" => {
+                let pids = Inferiors::new(&mut self.debugger, args)?.run()?;
+                for (i, pid) in pids.iter().enumerate() {
+                    let focus = if i == 0 { "*" } else { " " };
+                    println!("{focus} process {pid}");
+                }
+            }
+            "checkpoint" | "restart" => match Checkpoint::new(&mut self.debugger, args)?.run()? {
+                command::checkpoint::Response::Created(num) => println!("Checkpoint {num} created"),
+                command::checkpoint::Response::List(checkpoints) => {
+                    checkpoints.iter().for_each(|checkpoint| {
+                        let source = match (checkpoint.file.as_ref(), checkpoint.line) {
+                            (Some(file), Some(line)) => format!(" at {}:{line}", file.display()),
+                            _ => String::new(),
+                        };
+                        println!(
+                            "{:<4}process {} {}{source}",
+                            checkpoint.number, checkpoint.pid, checkpoint.pc
+                        );
+                    })
+                }
+                command::checkpoint::Response::Done => {}
+            },
+            "b" | "break" => {
+                let mut cmd = Break::new(&mut self.debugger, args)?;
+                let number = cmd.run()?;
+                let spec = cmd.r#type.clone();
+                let locations = self
+                    .debugger
+                    .breakpoints()
+                    .into_iter()
+                    .find(|brkpt| brkpt.number == number)
+                    .map(|brkpt| brkpt.locations.len())
+                    .unwrap_or_default();
+                if locations == 0 {
+                    println!("Breakpoint {number} at {spec} (pending)");
+                } else if locations > 1 {
+                    println!("Breakpoint {number} at {spec} ({locations} locations)");
+                } else {
+                    println!("Breakpoint {number} at {spec}");
+                }
+            }
+            "info" | "delete" | "enable" | "disable" | "ignore" => {
+                let mut cmd = Breakpoints::new(&mut self.debugger, args)?;
+                if let Some(list) = cmd.run()? {
+                    println!(
+                        "{:<8}{:<16}{:<8}{:<20}What",
+                        "Num", "Type", "Enb", "Address"
+                    );
+                    let render_addr = |addr: &PCValue| match addr {
+                        PCValue::Relocated(addr) => addr.to_string(),
+                        PCValue::Global(addr) => addr.to_string(),
+                    };
+                    list.breakpoints.iter().for_each(|brkpt| {
+                        let addr = match brkpt.locations.as_slice() {
+                            [] => "<PENDING>".to_string(),
+                            [addr] => render_addr(addr),
+                            _ => "<MULTIPLE>".to_string(),
+                        };
+                        let mut what = brkpt.spec.to_string();
+                        if let Some(ref condition) = brkpt.condition {
+                            what = format!("{what} if {}", condition.source);
+                        }
+                        println!(
+                            "{:<8}{:<16}{:<8}{:<20}{what}",
+                            brkpt.number,
+                            "breakpoint",
+                            if brkpt.enabled { "y" } else { "n" },
+                            addr,
+                        );
+                        if brkpt.hit_count > 0 {
+                            println!("\tbreakpoint already hit {} time(s)", brkpt.hit_count);
+                        }
+                        if brkpt.ignore_count > 0 {
+                            println!("\tignore next {} hit(s)", brkpt.ignore_count);
+                        }
+                        if brkpt.locations.len() > 1 {
+                            brkpt.locations.iter().enumerate().for_each(|(i, addr)| {
+                                println!(
+                                    "{:<8}{:<16}{:<8}{}",
+                                    format!("{}.{}", brkpt.number, i + 1),
+                                    "",
+                                    "",
+                                    render_addr(addr)
+                                );
+                            });
+                        }
+                    });
+                    list.watchpoints.iter().for_each(|wp| {
+                        let r#type = match wp.kind {
+                            WatchpointKind::Write => "hw watchpoint",
+                            WatchpointKind::Read => "read watchpoint",
+                            WatchpointKind::Access => "acc watchpoint",
+                        };
+                        println!(
+                            "{:<8}{:<16}{:<8}{:<20}{}",
+                            wp.number,
+                            r#type,
+                            if wp.is_enabled() { "y" } else { "n" },
+                            wp.address.to_string(),
+                            wp.expr
+                        );
+                    });
+                }
+            }
+            "catch" => {
+                let cmd = Catch::new(&mut self.debugger, args)?;
+                let event = match cmd.event {
+                    CatchEvent::Panic => "panic",
+                };
+                cmd.run()?;
+                println!("Catchpoint: {event}");
+            }
+            "watch" | "rwatch" | "awatch" => {
+                let kind = match command.to_lowercase().as_str() {
+                    "rwatch" => WatchpointKind::Read,
+                    "awatch" => WatchpointKind::Access,
+                    _ => WatchpointKind::Write,
+                };
+                let expr = args[1..].join(" ");
+                let number = Watch::new(&mut self.debugger, args, kind)?.run()?;
+                println!("Watchpoint {number}: {expr}");
+            }
             "reg" | "register" => {
                 let cmd = Register::new(&self.debugger, args)?;
                 let response = cmd.run()?;
@@ -121,23 +268,19 @@ impl TerminalApplication {
                     println!("{:10} {:#016X}", register.register_name, register.value);
                 });
             }
+            cmd if matches!(cmd.split('/').next(), Some("x")) => {
+                for line in Examine::new(&self.debugger, args)?.run()? {
+                    let symbol = line.symbol.map(|s| format!(" <{s}>")).unwrap_or_default();
+                    println!("{}{symbol}:\t{}", line.addr, line.items.join(" "));
+                }
+            }
             "mem" | "memory" => {
                 let read = Memory::new(&self.debugger, args)?.run()?;
                 println!("read at address: {:#016X}", read);
             }
             "bt" | "backtrace" => {
                 let bt = Backtrace::new(&self.debugger).run()?;
-                bt.iter().for_each(|part| match part.place.as_ref() {
-                    Some(place) => {
-                        println!(
-                            "{:#016X} - {} ({:#016X}) + {:#X}",
-                            part.ip, place.func_name, place.start_ip, place.offset,
-                        );
-                    }
-                    None => {
-                        println!("{:#016X} - ????", part.ip)
-                    }
-                })
+                print_backtrace(&bt);
             }
             "trace" => {
                 let bt = Trace::new(&self.debugger).run()?;
@@ -148,39 +291,84 @@ impl TerminalApplication {
                         thread.pc.unwrap_or(0_usize.into())
                     );
                     if let Some(ref bt) = thread.bt {
-                        bt.iter().for_each(|part| match part.place.as_ref() {
-                            Some(place) => {
-                                println!(
-                                    "{:#016X} - {} ({:#016X}) + {:#X}",
-                                    part.ip, place.func_name, place.start_ip, place.offset,
-                                );
-                            }
-                            None => {
-                                println!("{:#016X} - ????", part.ip)
-                            }
-                        })
+                        print_backtrace(bt);
                     }
                 });
             }
-            "stepi" => StepI::new(&self.debugger).run()?,
-            "step" | "stepinto" => StepInto::new(&self.debugger).run()?,
+            "thread" => match Thread::new(&mut self.debugger, args)?.run()? {
+                command::thread::Response::List(threads) => {
+                    for dump in threads {
+                        let focus = if dump.in_focus { "*" } else { " " };
+                        let func = dump
+                            .bt
+                            .as_ref()
+                            .and_then(|bt| bt.first())
+                            .and_then(|part| part.place.as_ref())
+                            .map_or("??", |place| place.func_name.as_str());
+                        println!(
+                            "{focus} {:<4}{:<10}{:<16}{:<12}{func}",
+                            dump.thread.number,
+                            dump.thread.pid,
+                            dump.name.as_deref().unwrap_or("??"),
+                            format!("{:?}", dump.thread.status),
+                        );
+                    }
+                }
+                command::thread::Response::Switched(num, tid) => {
+                    println!("[switched to thread {num} ({tid})]");
+                }
+                command::thread::Response::Backtraces(threads) => {
+                    for dump in threads {
+                        println!("\nthread {} ({}):", dump.thread.number, dump.thread.pid);
+                        if let Some(ref bt) = dump.bt {
+                            print_backtrace(bt);
+                        }
+                    }
+                }
+            },
+            "stepi" => StepI::new(&mut self.debugger).run()?,
+            "step" | "stepinto" => StepInto::new(&mut self.debugger).run()?,
             "next" | "stepover" => StepOver::new(&mut self.debugger).run()?,
-            "finish" | "stepout" => StepOut::new(&mut self.debugger).run()?,
+            "finish" | "stepout" => {
+                if let Some(value) = StepOut::new(&mut self.debugger).run()? {
+                    println!("Value returned: {}", render_variable_ir(&value, 0));
+                }
+            }
+            "u" | "until" => Until::new(&mut self.debugger).run()?,
+            "advance" => Advance::new(&mut self.debugger, args)?.run()?,
             "vars" => Variables::new(&self.debugger, args)?
                 .run()?
                 .into_iter()
                 .for_each(|var| {
                     println!("{} = {}", var.name(), render_variable_ir(&var, 0),);
                 }),
+            cmd if matches!(cmd.split('/').next(), Some("print" | "p")) => {
+                let print = Print::new(&self.debugger, args)?;
+                let options = print.options;
+                let symbolize = |addr: usize| {
+                    self.debugger
+                        .symbolize(RelocatedAddress::from(addr))
+                        .map(|(name, offset)| format!("{name}+{offset}"))
+                };
+                print.run()?.into_iter().for_each(|var| {
+                    let render = render_variable_ir_with_options(&var, 0, &options, &symbolize);
+                    println!("{} = {render}", var.name());
+                });
+            }
+            "call" => {
+                if let Some(value) = Call::new(&self.debugger, args)?.run()? {
+                    println!("Value returned: {}", render_variable_ir(&value, 0));
+                }
+            }
             "args" => Arguments::new(&self.debugger)?
                 .run()?
                 .into_iter()
                 .for_each(|arg| {
                     println!("{} = {}", arg.name(), render_variable_ir(&arg, 0),);
                 }),
-            "frame" => {
-                let frame = Frame::new(&self.debugger).run()?;
-                println!("current frame: {}", frame.base_addr);
+            "frame" | "up" | "down" => {
+                let frame = Frame::new(&self.debugger, args)?.run()?;
+                println!("frame #{}: {}", frame.num, frame.base_addr);
                 println!(
                     "return address: {}",
                     frame
@@ -200,3 +388,22 @@ impl TerminalApplication {
         Ok(())
     }
 }
+
+fn print_backtrace(bt: &uw::Backtrace) {
+    bt.iter().for_each(|part| match part.place.as_ref() {
+        Some(place) => {
+            let source = match (place.file.as_ref(), place.line) {
+                (Some(file), Some(line)) => format!(" at {}:{line}", file.display()),
+                _ => String::new(),
+            };
+            let inlined = if place.inlined { " [inlined]" } else { "" };
+            println!(
+                "{:#016X} - {}{inlined} ({:#016X}) + {:#X}{source}",
+                part.ip, place.func_name, place.start_ip, place.offset,
+            );
+        }
+        None => {
+            println!("{:#016X} - ????", part.ip)
+        }
+    })
+}
@@ -1,58 +1,347 @@
-use crate::debugger::variable::render::{RenderRepr, ValueLayout};
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::render::{Format, RenderOptions, RenderRepr, ValueLayout};
+use crate::debugger::variable::{
+    ScalarVariable, SpecializedVariableIR, SupportedScalar, VariableIR,
+};
+use std::borrow::Cow;
 
 const TAB: &str = "\t";
 
 pub fn render_variable_ir(view: &VariableIR, depth: usize) -> String {
+    render_variable_ir_with_options(view, depth, &RenderOptions::default(), &|_| None)
+}
+
+/// Render variable according to `options`, `symbolize` used by [`Format::Address`]
+/// and return a symbol for address (like `main+16`).
+pub fn render_variable_ir_with_options(
+    view: &VariableIR,
+    depth: usize,
+    options: &RenderOptions,
+    symbolize: &dyn Fn(usize) -> Option<String>,
+) -> String {
+    let render_nested =
+        |v: &VariableIR| render_variable_ir_with_options(v, depth + 1, options, symbolize);
+
+    if options.format == Format::String {
+        if let Some(string) = bytes_as_string(view) {
+            return format!("{} {string:?}", view.r#type());
+        }
+    }
+
     match view.value() {
         Some(value) => match value {
-            ValueLayout::PreRendered(rendered_value) => match view {
-                VariableIR::CEnum(_) => format!("{}::{}", view.r#type(), rendered_value),
-                _ => format!("{}({})", view.r#type(), rendered_value),
-            },
+            ValueLayout::PreRendered(rendered_value) => {
+                let rendered_value = match view {
+                    VariableIR::Scalar(ScalarVariable {
+                        value: Some(scalar),
+                        ..
+                    }) => format_scalar(scalar, options.format, symbolize)
+                        .map(Cow::Owned)
+                        .unwrap_or(rendered_value),
+                    _ => rendered_value,
+                };
+                match view {
+                    VariableIR::CEnum(_) => format!("{}::{}", view.r#type(), rendered_value),
+                    _ => format!("{}({})", view.r#type(), rendered_value),
+                }
+            }
             ValueLayout::Referential { addr } => {
-                format!("{} [{addr:p}]", view.r#type())
+                let addr = format_scalar(
+                    &SupportedScalar::Usize(addr as usize),
+                    options.format,
+                    symbolize,
+                )
+                .unwrap_or_else(|| format!("{addr:p}"));
+                format!("{} [{addr}]", view.r#type())
             }
             ValueLayout::Wrapped(val) => {
-                format!("{}::{}", view.r#type(), render_variable_ir(val, depth))
+                format!(
+                    "{}::{}",
+                    view.r#type(),
+                    render_variable_ir_with_options(val, depth, options, symbolize)
+                )
             }
             ValueLayout::Nested { members, named } => {
-                let mut render = format!("{} {{", view.r#type());
-
-                let tabs = TAB.repeat(depth + 1);
-
-                for v in members {
-                    render = format!("{render}\n");
+                let limited = !named || matches!(view, VariableIR::Array(_));
+                let members = members.iter().map(|v| {
                     if named {
-                        render = format!(
-                            "{render}{tabs}{}: {}",
-                            v.name(),
-                            render_variable_ir(v, depth + 1)
-                        );
+                        format!("{}: {}", v.name(), render_nested(v))
                     } else {
-                        render = format!("{render}{tabs}{}", render_variable_ir(v, depth + 1));
+                        render_nested(v)
                     }
-                }
-
-                format!("{render}\n{}}}", TAB.repeat(depth))
+                });
+                render_members(view, depth, options, limited, members)
             }
             ValueLayout::Map(kv_children) => {
-                let mut render = format!("{} {{", view.r#type());
+                let members = kv_children
+                    .iter()
+                    .map(|kv| format!("{}: {}", render_nested(&kv.0), render_nested(&kv.1)));
+                render_members(view, depth, options, true, members)
+            }
+        },
+        None => format!("{}(unknown)", view.r#type()),
+    }
+}
 
-                let tabs = TAB.repeat(depth + 1);
+/// Render members of structure or collection, number of rendered members
+/// is limited by [`RenderOptions::max_elements`] if `limited` is true.
+fn render_members(
+    view: &VariableIR,
+    depth: usize,
+    options: &RenderOptions,
+    limited: bool,
+    members: impl ExactSizeIterator<Item = String>,
+) -> String {
+    if options
+        .max_depth
+        .is_some_and(|max_depth| depth >= max_depth)
+    {
+        return format!("{} {{...}}", view.r#type());
+    }
 
-                for kv in kv_children {
-                    render = format!("{render}\n");
-                    render = format!(
-                        "{render}{tabs}{}: {}",
-                        render_variable_ir(&kv.0, depth + 1),
-                        render_variable_ir(&kv.1, depth + 1)
-                    );
-                }
+    let total = members.len();
+    let limit = options
+        .max_elements
+        .filter(|_| limited)
+        .unwrap_or(total)
+        .min(total);
+    let mut members = members.take(limit).collect::<Vec<_>>();
+    if limit < total {
+        members.push("...".to_string());
+    }
+
+    if !options.pretty {
+        return match members.is_empty() {
+            true => format!("{} {{}}", view.r#type()),
+            false => format!("{} {{ {} }}", view.r#type(), members.join(", ")),
+        };
+    }
+
+    let mut render = format!("{} {{", view.r#type());
+    let tabs = TAB.repeat(depth + 1);
+    for member in members {
+        render = format!("{render}\n{tabs}{member}");
+    }
+    format!("{render}\n{}}}", TAB.repeat(depth))
+}
+
+/// Render scalar in `format`, `None` returned if format is not applicable to the scalar.
+fn format_scalar(
+    scalar: &SupportedScalar,
+    format: Format,
+    symbolize: &dyn Fn(usize) -> Option<String>,
+) -> Option<String> {
+    // integer value and bits of value
+    let (int, bits) = match *scalar {
+        SupportedScalar::I8(v) => (v as i128, v as u8 as u128),
+        SupportedScalar::I16(v) => (v as i128, v as u16 as u128),
+        SupportedScalar::I32(v) => (v as i128, v as u32 as u128),
+        SupportedScalar::I64(v) => (v as i128, v as u64 as u128),
+        SupportedScalar::I128(v) => (v, v as u128),
+        SupportedScalar::Isize(v) => (v as i128, v as usize as u128),
+        SupportedScalar::U8(v) => (v as i128, v as u128),
+        SupportedScalar::U16(v) => (v as i128, v as u128),
+        SupportedScalar::U32(v) => (v as i128, v as u128),
+        SupportedScalar::U64(v) => (v as i128, v as u128),
+        SupportedScalar::U128(v) => (v as i128, v),
+        SupportedScalar::Usize(v) => (v as i128, v as u128),
+        SupportedScalar::Bool(v) => (v as i128, v as u128),
+        SupportedScalar::Char(v) => (v as i128, v as u128),
+        SupportedScalar::F32(v) => (v as i128, v.to_bits() as u128),
+        SupportedScalar::F64(v) => (v as i128, v.to_bits() as u128),
+        SupportedScalar::Empty() => return None,
+    };
+    let is_float = matches!(scalar, SupportedScalar::F32(_) | SupportedScalar::F64(_));
 
-                format!("{render}\n{}}}", TAB.repeat(depth))
+    let rendered = match format {
+        Format::Natural | Format::String => return None,
+        Format::Hex => format!("{bits:#x}"),
+        Format::Octal => format!("{bits:#o}"),
+        Format::Binary => format!("{bits:#b}"),
+        Format::Decimal if is_float => return None,
+        Format::Decimal => match scalar {
+            SupportedScalar::U128(v) => v.to_string(),
+            _ => int.to_string(),
+        },
+        Format::Char if is_float => return None,
+        Format::Char => match *scalar {
+            // bytes above ASCII are not characters, so rendered as octal escapes
+            SupportedScalar::I8(_) | SupportedScalar::U8(_) if bits >= 0x80 => {
+                format!("'\\{bits:03o}'")
+            }
+            _ => {
+                let char = u32::try_from(int).ok().and_then(char::from_u32)?;
+                format!("'{}'", char.escape_debug())
             }
         },
-        None => format!("{}(unknown)", view.r#type()),
+        Format::Float if is_float => return None,
+        Format::Float => format!("{:?}", int as f64),
+        Format::Address => match symbolize(bits as usize) {
+            Some(symbol) => format!("{bits:#x} <{symbol}>"),
+            None => format!("{bits:#x}"),
+        },
+    };
+    Some(rendered)
+}
+
+/// Return content of array (or vector) of bytes as a string.
+fn bytes_as_string(view: &VariableIR) -> Option<String> {
+    let items = match view {
+        VariableIR::Array(array) => array.items.as_deref()?,
+        VariableIR::Specialized(SpecializedVariableIR::Vector { vec: Some(vec), .. }) => {
+            return vec.structure.members.iter().find_map(bytes_as_string);
+        }
+        _ => return None,
+    };
+    let bytes = items
+        .iter()
+        .map(|item| match item {
+            VariableIR::Scalar(ScalarVariable {
+                value: Some(SupportedScalar::U8(byte)),
+                ..
+            }) => Some(*byte),
+            VariableIR::Scalar(ScalarVariable {
+                value: Some(SupportedScalar::I8(byte)),
+                ..
+            }) => Some(*byte as u8),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::variable::{ArrayVariable, StructVariable, VariableIdentity};
+
+    fn identity(name: &str) -> VariableIdentity {
+        VariableIdentity::new(Default::default(), Some(name.to_string()))
+    }
+
+    fn scalar(name: &str, type_name: &str, value: SupportedScalar) -> VariableIR {
+        VariableIR::Scalar(ScalarVariable {
+            identity: identity(name),
+            raw_address: None,
+            type_name: Some(type_name.to_string()),
+            value: Some(value),
+        })
+    }
+
+    fn array(items: Vec<VariableIR>) -> VariableIR {
+        VariableIR::Array(ArrayVariable {
+            identity: identity("arr"),
+            raw_address: None,
+            type_name: Some("[u8]".to_string()),
+            items: Some(items),
+        })
+    }
+
+    fn render(view: &VariableIR, options: RenderOptions) -> String {
+        render_variable_ir_with_options(view, 0, &options, &|addr| {
+            (addr == 0x10).then(|| "main+4".to_string())
+        })
+    }
+
+    #[test]
+    fn test_render_with_options() {
+        let bytes = array(
+            "hi!"
+                .bytes()
+                .enumerate()
+                .map(|(i, b)| scalar(&i.to_string(), "u8", SupportedScalar::U8(b)))
+                .collect(),
+        );
+        let structure = VariableIR::Struct(StructVariable {
+            identity: identity("s"),
+            raw_address: None,
+            type_name: Some("S".to_string()),
+            members: vec![
+                scalar("a", "i8", SupportedScalar::I8(-1)),
+                scalar("b", "usize", SupportedScalar::Usize(0x10)),
+                bytes.clone(),
+            ],
+            type_params: Default::default(),
+        });
+        let with_format = |format| RenderOptions {
+            format,
+            pretty: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render(&structure, with_format(Format::Natural)),
+            "S { a: i8(-1), b: usize(16), arr: [u8] { 0: u8(104), 1: u8(105), 2: u8(33) } }"
+        );
+        assert_eq!(
+            render(&structure, with_format(Format::Hex)),
+            "S { a: i8(0xff), b: usize(0x10), arr: [u8] { 0: u8(0x68), 1: u8(0x69), 2: u8(0x21) } }"
+        );
+        assert_eq!(
+            render(&structure, with_format(Format::Char)),
+            "S { a: i8('\\377'), b: usize('\\u{10}'), arr: [u8] { 0: u8('h'), 1: u8('i'), 2: u8('!') } }"
+        );
+        assert_eq!(
+            render(&structure, with_format(Format::Address)),
+            "S { a: i8(0xff), b: usize(0x10 <main+4>), arr: [u8] { 0: u8(0x68), 1: u8(0x69), 2: u8(0x21) } }"
+        );
+        assert_eq!(
+            render(&structure, with_format(Format::String)),
+            "S { a: i8(-1), b: usize(16), arr: [u8] \"hi!\" }"
+        );
+        assert_eq!(
+            render(
+                &scalar("f", "f64", SupportedScalar::F64(1.5)),
+                with_format(Format::Binary)
+            ),
+            "f64(0b11111111111000000000000000000000000000000000000000000000000000)"
+        );
+        assert_eq!(
+            render(
+                &scalar("i", "i32", SupportedScalar::I32(7)),
+                with_format(Format::Float)
+            ),
+            "i32(7.0)"
+        );
+        assert_eq!(
+            render(
+                &scalar("i", "i32", SupportedScalar::I32(-1)),
+                with_format(Format::Char)
+            ),
+            "i32(-1)"
+        );
+        assert_eq!(
+            render(
+                &scalar("c", "u32", SupportedScalar::U32(0xe9)),
+                with_format(Format::Char)
+            ),
+            "u32('é')"
+        );
+
+        let limited = RenderOptions {
+            max_elements: Some(2),
+            pretty: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&bytes, limited),
+            "[u8] { 0: u8(104), 1: u8(105), ... }"
+        );
+        let shallow = RenderOptions {
+            max_depth: Some(1),
+            pretty: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&structure, shallow),
+            "S { a: i8(-1), b: usize(16), arr: [u8] {...} }"
+        );
+        assert_eq!(
+            render(&bytes, RenderOptions::default()),
+            render_variable_ir(&bytes, 0)
+        );
+        assert_eq!(
+            render_variable_ir(&bytes, 0),
+            "[u8] {\n\t0: u8(104)\n\t1: u8(105)\n\t2: u8(33)\n}"
+        );
     }
 }
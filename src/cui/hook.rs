@@ -1,7 +1,11 @@
 use crate::cui::{context, AppState};
 use crate::debugger::address::RelocatedAddress;
-use crate::debugger::{EventHook, Place};
+use crate::debugger::uw::Backtrace;
+use crate::debugger::variable::render::{RenderRepr, ValueLayout};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{EventHook, PanicInfo, Place, Watchpoint};
 use nix::libc::c_int;
+use nix::unistd::Pid;
 use tui::style::{Color, Style};
 use tui::text::{Span, Spans};
 
@@ -39,4 +43,52 @@ impl EventHook for CuiHook {
     fn on_exit(&self, _code: i32) {
         context::Context::current().change_state(AppState::Finish)
     }
+
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        place: Option<Place>,
+        watchpoint: &Watchpoint,
+        old: Option<&VariableIR>,
+        new: Option<&VariableIR>,
+    ) -> anyhow::Result<()> {
+        let title = format!("Watchpoint {} ({}): ", watchpoint.number, watchpoint.expr);
+        let description = match (old, new) {
+            (_, None) => "deleted, variable goes out of scope".to_string(),
+            (Some(old), Some(new)) => format!("{} -> {}", render_value(old), render_value(new)),
+            (None, Some(new)) => render_value(new),
+        };
+        let alert_text = vec![Spans::from(vec![
+            Span::raw(title),
+            Span::styled(description, Style::default().fg(Color::Yellow)),
+        ])];
+        context::Context::current().set_alert(alert_text.into());
+
+        self.on_trap(pc, place)
+    }
+
+    fn on_panic(&self, tid: Pid, panic: &PanicInfo, _: Option<&Backtrace>) -> anyhow::Result<()> {
+        let message = panic.message.as_deref().unwrap_or("unknown panic message");
+        let alert_text = vec![Spans::from(vec![
+            Span::raw(format!("Thread {tid} panicked: ")),
+            Span::styled(message.to_string(), Style::default().fg(Color::Red)),
+        ])];
+        let ctx = context::Context::current();
+        ctx.set_alert(alert_text.into());
+
+        if let Some(ref location) = panic.location {
+            ctx.set_trap_file_name(location.file.clone());
+            ctx.set_trap_text_pos(location.line as u64);
+            ctx.change_state(AppState::DebugeeBreak);
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn render_value(var: &VariableIR) -> String {
+    match var.value() {
+        Some(ValueLayout::PreRendered(value)) => value.to_string(),
+        Some(ValueLayout::Referential { addr }) => format!("{addr:p}"),
+        _ => var.r#type().to_string(),
+    }
 }
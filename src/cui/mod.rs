@@ -47,6 +47,16 @@ impl AppBuilder {
             self.debugee_err,
         ))
     }
+
+    pub fn attach(self, pid: Pid) -> anyhow::Result<CuiApplication> {
+        let hook = CuiHook::new();
+        let debugger = Debugger::attach(pid, hook)?;
+        Ok(CuiApplication::new(
+            debugger,
+            self.debugee_out,
+            self.debugee_err,
+        ))
+    }
 }
 
 #[derive(Clone, Copy, PartialEq)]
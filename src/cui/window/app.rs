@@ -103,7 +103,7 @@ impl CuiComponent for AppWindow {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(0)
-            .constraints(constrains.as_ref())
+            .constraints(constrains.as_slice())
             .split(rect);
 
         self.render_work_windows(frame, chunks[0], opts);
@@ -1,3 +1,4 @@
+use crate::cui::hook::render_value;
 use crate::cui::window::app::AppWindow;
 use crate::cui::window::message::Exchanger;
 use crate::cui::{context, AppState, DebugeeStreamBuffer, Event};
@@ -69,7 +70,8 @@ pub(super) fn run(
                         ..
                     } => {
                         ctx.change_state(AppState::DebugeeRun);
-                        try_else_alert!(Continue::new(&mut debugger.borrow_mut()).run());
+                        try_else_alert!(Continue::new(&mut debugger.borrow_mut(), vec![])
+                            .and_then(|mut cmd| cmd.run()));
                     }
                     KeyEvent {
                         code: KeyCode::Char('r'),
@@ -101,14 +103,17 @@ pub(super) fn run(
                         code: KeyCode::F(7),
                         ..
                     } => {
-                        try_else_alert!(StepInto::new(&debugger.borrow()).run());
+                        try_else_alert!(StepInto::new(&mut debugger.borrow_mut()).run());
                     }
                     KeyEvent {
                         code: KeyCode::F(6),
                         ..
-                    } => {
-                        try_else_alert!(StepOut::new(&mut debugger.borrow_mut()).run());
-                    }
+                    } => match StepOut::new(&mut debugger.borrow_mut()).run() {
+                        Ok(Some(value)) => ctx
+                            .set_alert(format!("Value returned: {}", render_value(&value)).into()),
+                        Ok(None) => {}
+                        Err(e) => ctx.set_alert(format!("An error occurred: {e}").into()),
+                    },
                     _ => {
                         app_window.handle_user_event(e);
                     }
@@ -1,9 +1,8 @@
 use crate::cui::window::message::{ActionMessage, Exchanger};
 use crate::cui::window::specialized::PersistentList;
 use crate::cui::window::{CuiComponent, RenderOpts};
-use crate::debugger::command::BreakpointType;
-use crate::debugger::{command, Debugger};
-use crate::fire;
+use crate::debugger::{command, Debugger, UserBreakpoint};
+use crate::{fire, weak_error};
 use crossterm::event::{KeyCode, KeyEvent};
 use std::cell::RefCell;
 use std::io::StdoutLock;
@@ -16,7 +15,8 @@ use tui::Frame;
 
 pub struct Breakpoints {
     debugger: Rc<RefCell<Debugger>>,
-    breakpoints: RefCell<PersistentList<BreakpointType>>,
+    /// Copy of debugger breakpoints, updated before each render.
+    breakpoints: RefCell<PersistentList<UserBreakpoint>>,
 }
 
 impl Breakpoints {
@@ -28,6 +28,14 @@ impl Breakpoints {
     }
 }
 
+impl Breakpoints {
+    fn selected(&self) -> Option<UserBreakpoint> {
+        let breakpoints = self.breakpoints.borrow();
+        let idx = breakpoints.state.selected()?;
+        breakpoints.items.get(idx).cloned()
+    }
+}
+
 impl CuiComponent for Breakpoints {
     fn render(
         &self,
@@ -35,26 +43,37 @@ impl CuiComponent for Breakpoints {
         rect: Rect,
         opts: RenderOpts,
     ) {
+        let breakpoints = self
+            .debugger
+            .borrow()
+            .breakpoints()
+            .into_iter()
+            .cloned()
+            .collect();
+        self.breakpoints.borrow_mut().update_items(breakpoints);
+
         let items: Vec<ListItem> = self
             .breakpoints
             .borrow()
             .items
             .iter()
-            .enumerate()
-            .map(|(i, bp)| {
-                let bp_index = i + 1;
-                let view = match bp {
-                    BreakpointType::Address(addr) => {
-                        format!("{bp_index}. {addr:#016X}")
-                    }
-                    BreakpointType::Line(file, line) => {
-                        format!("{bp_index}. {file}:{line}")
-                    }
-                    BreakpointType::Function(function) => {
-                        format!("{bp_index}. {function}")
-                    }
+            .map(|brkpt| {
+                let mut view = format!("{}. {}", brkpt.number, brkpt.spec);
+                if let Some(ref condition) = brkpt.condition {
+                    view.push_str(&format!(" if {}", condition.source));
+                }
+                if brkpt.locations.is_empty() {
+                    view.push_str(" (pending)");
+                }
+                if brkpt.hit_count > 0 {
+                    view.push_str(&format!(" (hits: {})", brkpt.hit_count));
+                }
+                let style = if brkpt.enabled {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::DarkGray)
                 };
-                ListItem::new(view)
+                ListItem::new(view).style(style)
             })
             .collect();
 
@@ -88,7 +107,20 @@ impl CuiComponent for Breakpoints {
                 fire!(ActionMessage::ActivateUserInput {sender: self.name()} => "app_window")
             }
             KeyCode::Char('r') => {
-                self.breakpoints.borrow_mut().remove_selected();
+                if let Some(brkpt) = self.selected() {
+                    weak_error!(self.debugger.borrow_mut().remove_breakpoint(brkpt.number));
+                    self.breakpoints.borrow_mut().remove_selected();
+                }
+            }
+            KeyCode::Char('t') => {
+                if let Some(brkpt) = self.selected() {
+                    let mut debugger = self.debugger.borrow_mut();
+                    if brkpt.enabled {
+                        weak_error!(debugger.disable_breakpoint(brkpt.number));
+                    } else {
+                        weak_error!(debugger.enable_breakpoint(brkpt.number));
+                    }
+                }
             }
             KeyCode::Up => {
                 self.breakpoints.borrow_mut().previous();
@@ -104,9 +136,7 @@ impl CuiComponent for Breakpoints {
         for action in Exchanger::current().pop(self.name()) {
             if let ActionMessage::HandleUserInput { input } = action {
                 let dbg = &mut (*self.debugger).borrow_mut();
-                let mut b = command::Break::new(dbg, vec!["", &input])?;
-                b.run()?;
-                self.breakpoints.borrow_mut().items().push(b.r#type);
+                command::Break::new(dbg, vec!["", &input])?.run()?;
             }
         }
         Ok(())
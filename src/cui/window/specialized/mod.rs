@@ -26,10 +26,6 @@ impl<T> PersistentList<T> {
         self.items = new_items;
     }
 
-    fn items(&mut self) -> &mut Vec<T> {
-        &mut self.items
-    }
-
     fn next(&mut self) {
         if self.items.is_empty() {
             self.state.select(None);
@@ -1,6 +1,7 @@
 use crate::cui::window::specialized::PersistentList;
 use crate::cui::window::{CuiComponent, RenderOpts};
 use crate::debugger::{command, Debugger, ThreadDump};
+use crate::weak_error;
 use crossterm::event::{KeyCode, KeyEvent};
 use std::cell::RefCell;
 use std::io::StdoutLock;
@@ -43,7 +44,7 @@ impl CuiComponent for ThreadTrace {
             .items
             .iter()
             .map(|t_dump| {
-                let as_text = format!("thread {}", t_dump.thread.pid);
+                let as_text = format!("thread {} ({})", t_dump.thread.number, t_dump.thread.pid);
                 let mut list_item = ListItem::new(as_text);
                 if t_dump.in_focus {
                     list_item = list_item.style(Style::default().fg(Color::Cyan))
@@ -84,6 +85,19 @@ impl CuiComponent for ThreadTrace {
             KeyCode::Down => {
                 self.thread_list.borrow_mut().next();
             }
+            KeyCode::Enter => {
+                let selected = {
+                    let thread_list = self.thread_list.borrow();
+                    thread_list
+                        .state
+                        .selected()
+                        .and_then(|idx| thread_list.items.get(idx))
+                        .map(|t_dump| t_dump.thread.number)
+                };
+                if let Some(num) = selected {
+                    weak_error!(self.debugger.borrow_mut().set_thread_into_focus(num));
+                }
+            }
             _ => {}
         }
     }
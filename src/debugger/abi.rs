@@ -0,0 +1,228 @@
+use crate::debugger::debugee::dwarf::r#type::{
+    ComplexType, EvaluationContext, MemberLocation, StructureMember, TypeDeclaration, TypeIdentity,
+};
+use crate::debugger::read_memory_by_pid;
+use crate::debugger::register::{get_register_value, get_xmm_register_value, Register};
+use crate::debugger::variable::ObjectBinaryRepr;
+use anyhow::anyhow;
+use bytes::Bytes;
+use gimli::DW_ATE_float;
+use std::mem;
+
+/// Registers for integer class values in order of use.
+const INTEGER_RETURN_REGISTERS: [Register; 2] = [Register::Rax, Register::Rdx];
+/// Count of XMM registers for floating point class values (xmm0 and xmm1).
+const SSE_RETURN_REGISTERS: usize = 2;
+/// Values larger than two eightbytes always returned in memory.
+const MAX_REGISTER_VALUE_SIZE: usize = 16;
+
+/// Register class of scalar value (System V AMD64 ABI).
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum RegisterClass {
+    Integer,
+    Sse,
+}
+
+/// Scalar part of value: primitive, pointer or c-style enum at some offset.
+#[derive(Clone, Copy)]
+pub(super) struct ScalarPart {
+    pub(super) offset: usize,
+    pub(super) size: usize,
+    pub(super) class: RegisterClass,
+}
+
+/// How a value is passed into function or returned from it, rustc follows
+/// System V AMD64 ABI in this way:
+/// - a scalar or a pair of scalars not larger than two eightbytes passed in registers,
+///   each scalar takes the next free register of its class
+/// - other values not larger than eightbyte passed in a single integer register
+/// - larger values passed in memory, by pointer to them.
+pub(super) enum PassMode {
+    /// Zero-sized value, nothing is passed.
+    Ignore,
+    Scalars(Vec<ScalarPart>),
+    Integer,
+    Indirect,
+}
+
+/// Return pass mode and size of value of type `r#type`.
+pub(super) fn pass_mode(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+) -> anyhow::Result<(PassMode, usize)> {
+    let size = r#type
+        .type_size_in_bytes(eval_ctx, r#type.root)
+        .ok_or_else(|| anyhow!("unknown size of type"))? as usize;
+    if size == 0 {
+        return Ok((PassMode::Ignore, size));
+    }
+
+    let mut parts = vec![];
+    let in_registers = size <= MAX_REGISTER_VALUE_SIZE
+        && scalar_parts(eval_ctx, r#type, r#type.root, 0, &mut parts).is_some()
+        && {
+            parts.sort_by_key(|part| part.offset);
+            // parts at the same place (like a niche enum discriminant and its value)
+            // are the same scalar
+            parts.dedup_by_key(|part| (part.offset, part.size));
+            match parts.as_slice() {
+                [_] => true,
+                [first, second] => first.offset + first.size <= second.offset,
+                _ => false,
+            }
+        };
+
+    let mode = if in_registers {
+        PassMode::Scalars(parts)
+    } else if size <= mem::size_of::<u64>() {
+        PassMode::Integer
+    } else {
+        PassMode::Indirect
+    };
+    Ok((mode, size))
+}
+
+/// Read value of type `r#type` returned by function which just returned into thread
+/// `eval_ctx.pid`. Value location follows [`PassMode`] of return type: scalars return
+/// in RAX, RDX (integers and pointers) and XMM0, XMM1 (floating point numbers).
+/// Larger values are written into memory provided by caller (sret pointer),
+/// address of this memory returns in RAX.
+pub(super) fn read_return_value(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+) -> anyhow::Result<ObjectBinaryRepr> {
+    let pid = eval_ctx.pid;
+    let (mode, size) = pass_mode(eval_ctx, r#type)?;
+
+    match mode {
+        PassMode::Ignore => Ok(ObjectBinaryRepr::new(Bytes::new(), None)),
+        PassMode::Scalars(parts) => {
+            let mut data = vec![0; size];
+            let mut integer_registers = INTEGER_RETURN_REGISTERS.iter();
+            let mut sse_registers = 0..SSE_RETURN_REGISTERS;
+            for part in parts {
+                let value = match part.class {
+                    RegisterClass::Integer => {
+                        let eightbytes = part.size.div_ceil(mem::size_of::<u64>());
+                        integer_registers
+                            .by_ref()
+                            .take(eightbytes)
+                            .map(|&register| get_register_value(pid, register))
+                            .collect::<nix::Result<Vec<_>>>()?
+                            .into_iter()
+                            .flat_map(u64::to_ne_bytes)
+                            .collect::<Vec<_>>()
+                    }
+                    RegisterClass::Sse => {
+                        let num = sse_registers
+                            .next()
+                            .ok_or_else(|| anyhow!("not enough xmm registers for return value"))?;
+                        get_xmm_register_value(pid, num)?.to_ne_bytes().to_vec()
+                    }
+                };
+                let value = value
+                    .get(..part.size)
+                    .ok_or_else(|| anyhow!("not enough registers for return value"))?;
+                data[part.offset..part.offset + part.size].copy_from_slice(value);
+            }
+            Ok(ObjectBinaryRepr::new(Bytes::from(data), None))
+        }
+        PassMode::Integer => {
+            let rax = get_register_value(pid, Register::Rax)?;
+            let data = rax.to_ne_bytes()[..size].to_vec();
+            Ok(ObjectBinaryRepr::new(Bytes::from(data), None))
+        }
+        PassMode::Indirect => {
+            let rax = get_register_value(pid, Register::Rax)?;
+            let data = read_memory_by_pid(pid, rax as usize, size)?;
+            Ok(ObjectBinaryRepr::new(Bytes::from(data), Some(rax as usize)))
+        }
+    }
+}
+
+/// Collect scalar parts of type `typ` located at `offset`, zero-sized parts are skipped.
+/// Parts of all variants of enums and unions are collected.
+/// Return `None` if type layout is unknown.
+fn scalar_parts(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+    typ: TypeIdentity,
+    offset: usize,
+    parts: &mut Vec<ScalarPart>,
+) -> Option<()> {
+    let mut push = |size: usize, class: RegisterClass| {
+        if size > 0 {
+            parts.push(ScalarPart {
+                offset,
+                size,
+                class,
+            });
+        }
+    };
+
+    match r#type.types.get(&typ)? {
+        TypeDeclaration::Scalar(scalar) => {
+            let class = if scalar.encoding == Some(DW_ATE_float) {
+                RegisterClass::Sse
+            } else {
+                RegisterClass::Integer
+            };
+            push(scalar.byte_size? as usize, class);
+        }
+        TypeDeclaration::Pointer { .. } => {
+            push(mem::size_of::<usize>(), RegisterClass::Integer);
+        }
+        TypeDeclaration::CStyleEnum { byte_size, .. } => {
+            push((*byte_size)? as usize, RegisterClass::Integer);
+        }
+        TypeDeclaration::Array(array) => {
+            let element = array.element_type?;
+            let element_size = r#type.type_size_in_bytes(eval_ctx, element)? as usize;
+            if element_size == 0 {
+                return Some(());
+            }
+            let count = array.size_in_bytes(eval_ctx, r#type)? as usize / element_size;
+            for i in 0..count {
+                scalar_parts(eval_ctx, r#type, element, offset + i * element_size, parts)?;
+            }
+        }
+        TypeDeclaration::Structure { members, .. } | TypeDeclaration::Union { members, .. } => {
+            for member in members {
+                member_scalar_parts(eval_ctx, r#type, member, offset, parts)?;
+            }
+        }
+        TypeDeclaration::RustEnum {
+            discr_type,
+            enumerators,
+            ..
+        } => {
+            for member in discr_type.iter().map(|discr| discr.as_ref()) {
+                member_scalar_parts(eval_ctx, r#type, member, offset, parts)?;
+            }
+            for member in enumerators.values() {
+                member_scalar_parts(eval_ctx, r#type, member, offset, parts)?;
+            }
+        }
+    }
+    Some(())
+}
+
+fn member_scalar_parts(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+    member: &StructureMember,
+    offset: usize,
+    parts: &mut Vec<ScalarPart>,
+) -> Option<()> {
+    let member_offset = match member.in_struct_location.as_ref()? {
+        MemberLocation::Offset(member_offset) => usize::try_from(*member_offset).ok()?,
+        MemberLocation::Expr(_) => return None,
+    };
+    scalar_parts(
+        eval_ctx,
+        r#type,
+        member.type_ref?,
+        offset + member_offset,
+        parts,
+    )
+}
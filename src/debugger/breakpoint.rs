@@ -1,8 +1,12 @@
 use crate::debugger::address::PCValue;
+use crate::debugger::command::expression::Condition;
+use crate::debugger::{into_errno, proc_memory};
 use nix::libc::c_void;
-use nix::sys;
 use nix::unistd::Pid;
 use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::os::unix::fs::FileExt;
 
 impl PCValue {
     fn as_ptr(&self) -> *mut c_void {
@@ -26,6 +30,11 @@ impl Breakpoint {
     pub(crate) fn is_enabled(&self) -> bool {
         self.enabled.get()
     }
+
+    /// Return original byte replaced by trap instruction, `None` if breakpoint is disabled.
+    pub(crate) fn saved_data(&self) -> Option<u8> {
+        self.is_enabled().then(|| self.saved_data.get())
+    }
 }
 
 impl Breakpoint {
@@ -38,27 +47,159 @@ impl Breakpoint {
         }
     }
 
-    pub fn enable(&self) -> nix::Result<()> {
-        let data = sys::ptrace::read(self.pid, self.addr.as_ptr())?;
-        self.saved_data.set((data & 0xff) as u8);
-        let int3 = 0xCC as u64;
-        let data_with_pb = (data & !0xff) as u64 | int3;
-        unsafe {
-            sys::ptrace::write(self.pid, self.addr.as_ptr(), data_with_pb as *mut c_void)?;
+    /// Return copy of breakpoint for process `pid` forked by breakpoint process,
+    /// forked process memory already contains a copy of trap.
+    pub(super) fn fork(&self, pid: Pid) -> Self {
+        Self {
+            addr: self.addr,
+            pid,
+            enabled: Cell::new(self.enabled.get()),
+            saved_data: Cell::new(self.saved_data.get()),
         }
+    }
+
+    pub fn enable(&self) -> nix::Result<()> {
+        let mem = proc_memory(self.pid)?;
+        let mut data = [0u8];
+        mem.read_exact_at(&mut data, self.addr.as_ptr() as u64)
+            .map_err(into_errno)?;
+        self.saved_data.set(data[0]);
+        let int3 = 0xCC_u8;
+        mem.write_all_at(&[int3], self.addr.as_ptr() as u64)
+            .map_err(into_errno)?;
         self.enabled.set(true);
 
         Ok(())
     }
 
     pub fn disable(&self) -> nix::Result<()> {
-        let data = sys::ptrace::read(self.pid, self.addr.as_ptr())? as u64;
-        let restored: u64 = (data & !0xff) | self.saved_data.get() as u64;
-        unsafe {
-            sys::ptrace::write(self.pid, self.addr.as_ptr(), restored as *mut c_void)?;
-        }
+        let mem = proc_memory(self.pid)?;
+        mem.write_all_at(&[self.saved_data.get()], self.addr.as_ptr() as u64)
+            .map_err(into_errno)?;
         self.enabled.set(false);
 
         Ok(())
     }
 }
+
+/// Place where user sets a breakpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BreakpointSpec {
+    Address(usize),
+    Line(String, u64),
+    Function(String),
+}
+
+impl BreakpointSpec {
+    /// Return shared library name and function name if breakpoint set at function qualified
+    /// with a library name, like `libfoo.so::handler`.
+    pub(super) fn library_function(&self) -> Option<(&str, &str)> {
+        let BreakpointSpec::Function(name) = self else {
+            return None;
+        };
+        name.split_once("::")
+            .filter(|(library, _)| library.contains(".so"))
+    }
+}
+
+impl Display for BreakpointSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BreakpointSpec::Address(addr) => f.write_str(&format!("{addr:#016X}")),
+            BreakpointSpec::Line(file, line) => f.write_str(&format!("{file}:{line}")),
+            BreakpointSpec::Function(function) => f.write_str(function),
+        }
+    }
+}
+
+/// Breakpoint created by user, unlike debugger internal breakpoints it has a number
+/// and may be disabled or deleted by this number.
+#[derive(Clone)]
+pub struct UserBreakpoint {
+    pub number: u32,
+    pub spec: BreakpointSpec,
+    /// Breakpoint addresses, one for each code instance of breakpoint place
+    /// (function monomorphizations, inlined copies, etc.).
+    /// Addresses are global before debugee started and relocated after.
+    /// Empty for pending breakpoints, which place is in not yet loaded shared library.
+    pub locations: Vec<PCValue>,
+    pub enabled: bool,
+    /// How many times breakpoint hit (with satisfied condition).
+    pub hit_count: u32,
+    /// How many next hits will be ignored.
+    pub ignore_count: u32,
+    /// If set, debugee stops at breakpoint only when condition is true.
+    pub condition: Option<Condition>,
+}
+
+/// Storage of user breakpoints, also a source of numbers for watchpoints.
+#[derive(Clone, Default)]
+pub struct BreakpointRegistry {
+    breakpoints: BTreeMap<u32, UserBreakpoint>,
+    last_number: u32,
+}
+
+impl BreakpointRegistry {
+    /// Return next unused breakpoint (or watchpoint) number.
+    pub fn next_number(&mut self) -> u32 {
+        self.last_number += 1;
+        self.last_number
+    }
+
+    /// Add enabled breakpoint, return its number.
+    pub fn add(&mut self, spec: BreakpointSpec, locations: Vec<PCValue>) -> u32 {
+        let number = self.next_number();
+        self.breakpoints.insert(
+            number,
+            UserBreakpoint {
+                number,
+                spec,
+                locations,
+                enabled: true,
+                hit_count: 0,
+                ignore_count: 0,
+                condition: None,
+            },
+        );
+        number
+    }
+
+    pub fn get_mut(&mut self, number: u32) -> Option<&mut UserBreakpoint> {
+        self.breakpoints.get_mut(&number)
+    }
+
+    pub fn remove(&mut self, number: u32) -> Option<UserBreakpoint> {
+        self.breakpoints.remove(&number)
+    }
+
+    /// Return all breakpoints ordered by number.
+    pub fn iter(&self) -> impl Iterator<Item = &UserBreakpoint> {
+        self.breakpoints.values()
+    }
+
+    /// Return enabled breakpoints at address.
+    pub fn enabled_at(&self, addr: PCValue) -> impl Iterator<Item = &UserBreakpoint> {
+        self.breakpoints
+            .values()
+            .filter(move |brkpt| brkpt.enabled && brkpt.locations.contains(&addr))
+    }
+
+    /// Remove breakpoint locations matched by predicate.
+    pub fn remove_locations(&mut self, predicate: impl Fn(&PCValue) -> bool) {
+        self.breakpoints
+            .values_mut()
+            .for_each(|brkpt| brkpt.locations.retain(|location| !predicate(location)));
+    }
+
+    /// Relocate global breakpoint addresses with mapping offset.
+    pub fn relocate(&mut self, mapping_offset: usize) {
+        self.breakpoints
+            .values_mut()
+            .flat_map(|brkpt| brkpt.locations.iter_mut())
+            .for_each(|location| {
+                if let PCValue::Global(addr) = *location {
+                    *location = PCValue::Relocated(addr.relocate(mapping_offset));
+                }
+            });
+    }
+}
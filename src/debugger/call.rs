@@ -0,0 +1,368 @@
+use crate::debugger::abi::{self, PassMode, RegisterClass};
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::dwarf::r#type::{
+    ComplexType, EvaluationContext, TypeDeclaration, TypeIdentity,
+};
+use crate::debugger::debugee::thread::ThreadCtl;
+use crate::debugger::register::{get_fp_registers, set_fp_registers, set_xmm_register_value};
+use crate::debugger::variable::assign;
+use crate::debugger::write_memory_by_pid;
+use anyhow::{anyhow, bail};
+use nix::libc;
+use nix::sys;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::mem;
+
+/// Registers for integer class arguments in order of use.
+const INTEGER_ARGUMENT_REGISTERS: usize = 6;
+/// Count of XMM registers for floating point class arguments (xmm0 - xmm7).
+const SSE_ARGUMENT_REGISTERS: usize = 8;
+/// Area below stack pointer which may be used by function without stack pointer adjusting.
+const RED_ZONE_SIZE: u64 = 128;
+/// Stack pointer alignment at call instruction.
+const STACK_ALIGN: u64 = 16;
+
+/// Call of debugee function by a stopped thread. Thread registers are saved at creation,
+/// arguments are placed into registers and thread stack (below red zone of interrupted
+/// function), thread registers restored after call completes or fails.
+pub(super) struct InferiorCall {
+    tid: Pid,
+    saved_regs: libc::user_regs_struct,
+    saved_fpregs: libc::user_fpregs_struct,
+    /// Stack pointer, stack grows down while argument data pushed.
+    sp: u64,
+    integer_args: Vec<u64>,
+    sse_args: Vec<u128>,
+}
+
+impl InferiorCall {
+    /// Prepare call by thread `tid`, thread must be stopped.
+    pub(super) fn new(tid: Pid) -> nix::Result<Self> {
+        let saved_regs = sys::ptrace::getregs(tid)?;
+        Ok(Self {
+            tid,
+            saved_regs,
+            saved_fpregs: get_fp_registers(tid)?,
+            sp: saved_regs.rsp - RED_ZONE_SIZE,
+            integer_args: vec![],
+            sse_args: vec![],
+        })
+    }
+
+    /// Copy `data` into thread stack, return address of copy.
+    pub(super) fn push_data(&mut self, data: &[u8]) -> anyhow::Result<usize> {
+        self.sp = (self.sp - data.len() as u64) & !(STACK_ALIGN - 1);
+        write_memory_by_pid(self.tid, self.sp as usize, data)?;
+        Ok(self.sp as usize)
+    }
+
+    /// Reserve stack memory for value returned by function if value is returned
+    /// in memory. Address of this memory is a hidden first argument of function,
+    /// so this method must be called before arguments are pushed.
+    pub(super) fn reserve_return_value(
+        &mut self,
+        eval_ctx: &EvaluationContext,
+        r#type: &ComplexType,
+    ) -> anyhow::Result<()> {
+        if let (PassMode::Indirect, size) = abi::pass_mode(eval_ctx, r#type)? {
+            let addr = self.push_data(&vec![0; size])?;
+            self.push_integer(addr as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Pass argument of type `r#type` with value `data`.
+    pub(super) fn push_argument(
+        &mut self,
+        eval_ctx: &EvaluationContext,
+        r#type: &ComplexType,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        let (mode, size) = abi::pass_mode(eval_ctx, r#type)?;
+        if data.len() != size {
+            bail!(
+                "argument size mismatch: expected {size} bytes, got {}",
+                data.len()
+            );
+        }
+
+        match mode {
+            PassMode::Ignore => {}
+            PassMode::Scalars(parts) => {
+                for part in parts {
+                    let bytes = &data[part.offset..part.offset + part.size];
+                    match part.class {
+                        RegisterClass::Integer => {
+                            for eightbyte in bytes.chunks(mem::size_of::<u64>()) {
+                                let mut word = [0; mem::size_of::<u64>()];
+                                word[..eightbyte.len()].copy_from_slice(eightbyte);
+                                self.push_integer(u64::from_ne_bytes(word))?;
+                            }
+                        }
+                        RegisterClass::Sse => {
+                            if self.sse_args.len() == SSE_ARGUMENT_REGISTERS {
+                                bail!("too many floating point arguments");
+                            }
+                            let mut value = [0; mem::size_of::<u128>()];
+                            value[..bytes.len()].copy_from_slice(bytes);
+                            self.sse_args.push(u128::from_ne_bytes(value));
+                        }
+                    }
+                }
+            }
+            PassMode::Integer => {
+                let mut value = [0; mem::size_of::<u64>()];
+                value[..size].copy_from_slice(data);
+                self.push_integer(u64::from_ne_bytes(value))?;
+            }
+            PassMode::Indirect => {
+                let addr = self.push_data(data)?;
+                self.push_integer(addr as u64)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass integer class argument (or a hidden pointer to return value) in register.
+    pub(super) fn push_integer(&mut self, value: u64) -> anyhow::Result<()> {
+        if self.integer_args.len() == INTEGER_ARGUMENT_REGISTERS {
+            bail!("too many arguments");
+        }
+        self.integer_args.push(value);
+        Ok(())
+    }
+
+    /// Execute function at `func_addr`, function returns to `trap` address
+    /// (which must contain int3 instruction). Only calling thread runs during the call.
+    /// When function returns `on_return` is called, thread registers are not restored at
+    /// this moment, so it may read returned value. Call is aborted if thread stops at other
+    /// trap (like a breakpoint) or receives a fatal signal.
+    /// Thread registers restored in all cases.
+    pub(super) fn execute<T>(
+        self,
+        func_addr: RelocatedAddress,
+        trap: RelocatedAddress,
+        on_return: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let result = self.run(func_addr, trap).and_then(|_| on_return());
+        sys::ptrace::setregs(self.tid, self.saved_regs)?;
+        set_fp_registers(self.tid, &self.saved_fpregs)?;
+        result
+    }
+
+    fn run(&self, func_addr: RelocatedAddress, trap: RelocatedAddress) -> anyhow::Result<()> {
+        let tid = self.tid;
+        // stack pointer must be aligned before call instruction pushes return address
+        let sp = (self.sp & !(STACK_ALIGN - 1)) - mem::size_of::<u64>() as u64;
+        write_memory_by_pid(tid, sp as usize, &(usize::from(trap) as u64).to_ne_bytes())?;
+
+        let mut regs = self.saved_regs;
+        let integer_registers = [
+            &mut regs.rdi,
+            &mut regs.rsi,
+            &mut regs.rdx,
+            &mut regs.rcx,
+            &mut regs.r8,
+            &mut regs.r9,
+        ];
+        for (register, value) in integer_registers.into_iter().zip(&self.integer_args) {
+            *register = *value;
+        }
+        // count of vector registers used by arguments (for variadic functions)
+        regs.rax = self.sse_args.len() as u64;
+        regs.rsp = sp;
+        regs.rip = usize::from(func_addr) as u64;
+        // function call must not be treated as an interrupted system call restart
+        regs.orig_rax = u64::MAX;
+        sys::ptrace::setregs(tid, regs)?;
+        for (num, value) in self.sse_args.iter().enumerate() {
+            set_xmm_register_value(tid, num, *value)?;
+        }
+
+        let mut delayed_signals = vec![];
+        let result = loop {
+            sys::ptrace::cont(tid, None)?;
+            match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+                WaitStatus::Stopped(_, Signal::SIGTRAP) => {
+                    let regs = sys::ptrace::getregs(tid)?;
+                    // int3 already executed, so pc is next to the trap
+                    let pc = regs.rip - 1;
+                    if pc == usize::from(trap) as u64
+                        && regs.rsp == sp + mem::size_of::<u64>() as u64
+                    {
+                        break Ok(());
+                    }
+                    break Err(anyhow!(
+                        "function call interrupted by trap at {pc:#X}, call aborted"
+                    ));
+                }
+                WaitStatus::Stopped(
+                    _,
+                    signal @ (Signal::SIGSEGV
+                    | Signal::SIGBUS
+                    | Signal::SIGILL
+                    | Signal::SIGFPE
+                    | Signal::SIGABRT),
+                ) => {
+                    break Err(anyhow!(
+                        "function call interrupted by signal {signal}, call aborted"
+                    ))
+                }
+                WaitStatus::Stopped(_, signal) => delayed_signals.push(signal),
+                status => {
+                    break Err(anyhow!(
+                        "unexpected thread {tid} status during call: {status:?}"
+                    ))
+                }
+            }
+        };
+
+        ThreadCtl::redeliver_signals(tid, &delayed_signals)?;
+        result
+    }
+}
+
+/// Value selected by select expression, used as a function argument.
+pub(super) struct SelectedValue {
+    pub(super) source: String,
+    pub(super) type_name: Option<String>,
+    pub(super) data: Vec<u8>,
+    /// Value address in debugee memory, `None` if value is not placed in memory.
+    pub(super) address: Option<usize>,
+}
+
+/// Function argument.
+pub(super) enum Argument<'a> {
+    /// Method receiver, passed by value or by reference depending on `self` type.
+    Receiver(SelectedValue),
+    /// Literal in source form (number, char, bool or enum variant).
+    Literal(&'a str),
+    /// String literal, passed as `&str`.
+    Str(&'a str),
+    Value(SelectedValue),
+    Ref(SelectedValue),
+}
+
+impl<'a> Argument<'a> {
+    /// Return argument in source form.
+    pub(super) fn source(&self) -> &str {
+        match self {
+            Argument::Receiver(value) | Argument::Value(value) | Argument::Ref(value) => {
+                &value.source
+            }
+            Argument::Literal(text) | Argument::Str(text) => text,
+        }
+    }
+}
+
+/// Return data of argument `arg` passed as parameter of type `r#type`.
+pub(super) fn argument_data(
+    call: &mut InferiorCall,
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+    arg: Argument,
+) -> anyhow::Result<Vec<u8>> {
+    match arg {
+        Argument::Receiver(value) if receiver_by_ref(r#type, &value) => {
+            reference_to(r#type, &value)
+        }
+        Argument::Receiver(value) | Argument::Value(value) => value_of(r#type, value),
+        Argument::Ref(value) => reference_to(r#type, &value),
+        Argument::Literal(text) => literal(eval_ctx, r#type, text),
+        Argument::Str(string) => str_literal(call, r#type, string),
+    }
+}
+
+/// Return true if `receiver` matches `self` parameter of type `r#type`.
+pub(super) fn receiver_matches(r#type: &ComplexType, receiver: &SelectedValue) -> bool {
+    receiver.type_name.is_some()
+        && (receiver_by_ref(r#type, receiver)
+            || type_name(r#type, r#type.root) == receiver.type_name)
+}
+
+/// Return true if reference to `receiver` passed as `self` parameter of type `r#type`
+/// (`&self` or `&mut self`).
+fn receiver_by_ref(r#type: &ComplexType, receiver: &SelectedValue) -> bool {
+    match r#type.types.get(&r#type.root) {
+        Some(TypeDeclaration::Pointer {
+            target_type: Some(target),
+            ..
+        }) => receiver.type_name.is_some() && type_name(r#type, *target) == receiver.type_name,
+        _ => false,
+    }
+}
+
+/// Return value of argument of type `r#type`.
+fn value_of(r#type: &ComplexType, value: SelectedValue) -> anyhow::Result<Vec<u8>> {
+    let expected = type_name(r#type, r#type.root);
+    if let (Some(expected), Some(actual)) = (expected, value.type_name.as_ref()) {
+        if &expected != actual {
+            bail!(
+                "type of {} mismatch: expected {expected}, got {actual}",
+                value.source
+            );
+        }
+    }
+    Ok(value.data)
+}
+
+/// Return reference to `value` as argument of type `r#type`.
+fn reference_to(r#type: &ComplexType, value: &SelectedValue) -> anyhow::Result<Vec<u8>> {
+    let Some(TypeDeclaration::Pointer { target_type, .. }) = r#type.types.get(&r#type.root) else {
+        bail!("reference to {} passed instead of value", value.source);
+    };
+    let expected = target_type.and_then(|target| type_name(r#type, target));
+    if let (Some(expected), Some(actual)) = (expected, value.type_name.as_ref()) {
+        if &expected != actual {
+            bail!(
+                "type of {} mismatch: expected {expected}, got {actual}",
+                value.source
+            );
+        }
+    }
+    let address = value
+        .address
+        .ok_or_else(|| anyhow!("{} is not placed in memory", value.source))?;
+    Ok((address as u64).to_ne_bytes().to_vec())
+}
+
+/// Return literal value (number, char, bool or enum variant) as argument of type `r#type`.
+fn literal(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+    text: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let size = r#type
+        .type_size_in_bytes(eval_ctx, r#type.root)
+        .ok_or_else(|| anyhow!("unknown size of argument {text}"))? as usize;
+    let encoded = assign::encode_value(r#type, r#type.root, text)?;
+    let mut data = vec![0; size];
+    data[encoded.offset..encoded.offset + encoded.data.len()].copy_from_slice(&encoded.data);
+    Ok(data)
+}
+
+/// Return string literal as argument of type `&str`, string data is copied into thread stack.
+fn str_literal(
+    call: &mut InferiorCall,
+    r#type: &ComplexType,
+    string: &str,
+) -> anyhow::Result<Vec<u8>> {
+    if type_name(r#type, r#type.root).as_deref() != Some("&str") {
+        bail!("string literal \"{string}\" passed instead of &str");
+    }
+    let addr = call.push_data(string.as_bytes())?;
+    Ok([addr as u64, string.len() as u64]
+        .into_iter()
+        .flat_map(u64::to_ne_bytes)
+        .collect())
+}
+
+fn type_name(r#type: &ComplexType, type_id: TypeIdentity) -> Option<String> {
+    r#type
+        .types
+        .contains_key(&type_id)
+        .then(|| r#type.type_name(type_id))
+        .flatten()
+}
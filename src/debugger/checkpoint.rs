@@ -0,0 +1,104 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::thread::ThreadCtl;
+use crate::debugger::inferior::Inferior;
+use crate::debugger::register::{get_register_value, set_register_value, Register};
+use crate::weak_error;
+use anyhow::{anyhow, bail};
+use nix::errno::Errno;
+use nix::libc::{self, c_long, c_void, pid_t};
+use nix::sys;
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::path::PathBuf;
+
+/// Code of `syscall` instruction.
+const SYSCALL_CODE: [u8; 2] = [0x0f, 0x05];
+
+/// Checkpoint description.
+pub struct CheckpointInfo {
+    pub number: u32,
+    /// Id of frozen process.
+    pub pid: Pid,
+    pub pc: RelocatedAddress,
+    pub file: Option<PathBuf>,
+    pub line: Option<u64>,
+}
+
+/// Snapshot of debugee state: a frozen copy of debugee process (forked by debugger)
+/// with debugger state of this process. Snapshot process never runs, so
+/// debugee can be restarted from snapshot many times.
+pub(super) struct Checkpoint {
+    pub(super) number: u32,
+    pub(super) inferior: Inferior,
+}
+
+impl Checkpoint {
+    pub(super) fn pid(&self) -> Pid {
+        self.inferior.debugee.threads_ctl().proc_pid()
+    }
+
+    /// Kill snapshot process.
+    pub(super) fn release(self) {
+        let pid = self.pid();
+        weak_error!(signal::kill(pid, Signal::SIGKILL));
+        weak_error!(waitpid(pid, Some(WaitPidFlag::__WALL)));
+    }
+}
+
+/// Fork process of stopped thread `tid`. Thread executes `fork` system call instead of its
+/// current instruction, then thread registers and code are restored. Forked process
+/// contains a single thread (copy of `tid`), it is stopped with the same registers as `tid`.
+pub(super) fn fork_process(tid: Pid) -> anyhow::Result<Pid> {
+    let saved_regs = sys::ptrace::getregs(tid)?;
+    let pc = saved_regs.rip as sys::ptrace::AddressType;
+    let saved_code = sys::ptrace::read(tid, pc)?;
+    let mut code = saved_code.to_ne_bytes();
+    code[..SYSCALL_CODE.len()].copy_from_slice(&SYSCALL_CODE);
+    unsafe { sys::ptrace::write(tid, pc, c_long::from_ne_bytes(code) as *mut c_void)? };
+
+    let restore = |pid: Pid| -> nix::Result<()> {
+        unsafe { sys::ptrace::write(pid, pc, saved_code as *mut c_void)? };
+        sys::ptrace::setregs(pid, saved_regs)
+    };
+
+    set_register_value(tid, Register::Rax, libc::SYS_fork as u64)?;
+    // injected system call must not be restarted like an interrupted one
+    set_register_value(tid, Register::OrigRax, u64::MAX)?;
+    let child = step_over_fork(tid);
+    restore(tid)?;
+
+    let child = child?;
+    restore(child)?;
+    Ok(child)
+}
+
+/// Execute `fork` system call by thread `tid`, return id of forked process.
+/// Signals received by thread during the call are delivered later.
+fn step_over_fork(tid: Pid) -> anyhow::Result<Pid> {
+    let mut child = None;
+    let mut delayed_signals = vec![];
+    loop {
+        sys::ptrace::step(tid, None)?;
+        match waitpid(tid, None)? {
+            WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_FORK) => {
+                let pid = Pid::from_raw(sys::ptrace::getevent(tid)? as pid_t);
+                // new process starts with PTRACE_EVENT_STOP
+                waitpid(pid, Some(WaitPidFlag::__WALL))?;
+                child = Some(pid);
+            }
+            WaitStatus::Stopped(_, Signal::SIGTRAP) => break,
+            WaitStatus::Stopped(_, signal) => delayed_signals.push(signal),
+            status => bail!("unexpected thread {tid} status during fork: {status:?}"),
+        }
+    }
+
+    ThreadCtl::redeliver_signals(tid, &delayed_signals)?;
+
+    child.ok_or_else(|| {
+        let errno = get_register_value(tid, Register::Rax)
+            .map(|rax| Errno::from_i32(-(rax as i64) as i32))
+            .unwrap_or(Errno::UnknownErrno);
+        anyhow!("fork failed: {errno}")
+    })
+}
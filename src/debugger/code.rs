@@ -2,5 +2,7 @@
 pub const TRAP_BRKPT: i32 = 0x1;
 /// Process trace trap
 pub const TRAP_TRACE: i32 = 0x2;
+/// Hardware breakpoint/watchpoint
+pub const TRAP_HWBKPT: i32 = 0x4;
 /// Sent by the kernel from somewhere
 pub const SI_KERNEL: i32 = 0x80;
@@ -0,0 +1,24 @@
+use crate::debugger::{command, BreakpointSpec, Debugger};
+
+/// Continue running the program up to the given location,
+/// execution also stops on return from the current stack frame.
+pub struct Advance<'a> {
+    dbg: &'a mut Debugger,
+    location: BreakpointSpec,
+}
+
+impl<'a> Advance<'a> {
+    /// Create command from arguments in form: `advance {location}`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+        let location = command::helper::parse_location(args[1..].join(" ").trim())?;
+        Ok(Self {
+            dbg: debugger,
+            location,
+        })
+    }
+
+    pub fn run(&mut self) -> command::Result<()> {
+        Ok(self.dbg.advance(&self.location)?)
+    }
+}
@@ -1,53 +1,99 @@
 use crate::debugger::address::PCValue;
+use crate::debugger::command::expression::{Condition, ConditionParser};
 use crate::debugger::command::CommandError;
-use crate::debugger::{command, Debugger};
-use std::str::FromStr;
-
-pub enum Breakpoint {
-    Address(usize),
-    Line(String, u64),
-    Function(String),
-}
+use crate::debugger::{command, BreakpointSpec, Debugger};
 
 pub struct Break<'a> {
     dbg: &'a mut Debugger,
-    pub r#type: Breakpoint,
+    pub r#type: BreakpointSpec,
+    pub condition: Option<Condition>,
 }
 
 impl<'a> Break<'a> {
+    /// Create breakpoint command from arguments in form: `break {place} [if {condition}]`.
     pub fn new<'s>(debugger: &'a mut Debugger, args: Vec<&'s str>) -> command::Result<Self> {
         command::helper::check_args_count(&args, 2)?;
 
-        let break_point_place = args[1];
-        let break_point_type;
-        if break_point_place.starts_with("0x") {
-            let addr = usize::from_str_radix(&break_point_place[2..], 16)
-                .map_err(|e| CommandError::InvalidArgumentsEx(e.to_string()))?;
-            break_point_type = Breakpoint::Address(addr);
-        } else if break_point_place.find(':').is_some() {
-            let args = break_point_place.split(':').collect::<Vec<_>>();
-            break_point_type = Breakpoint::Line(
-                args[0].to_string(),
-                u64::from_str(args[1])
-                    .map_err(|e| CommandError::InvalidArgumentsEx(e.to_string()))?,
-            );
-        } else {
-            break_point_type = Breakpoint::Function(break_point_place.to_string())
-        }
+        let (break_point_type, condition) = parse_breakpoint(&args[1..].join(" "))?;
 
         Ok(Self {
             dbg: debugger,
             r#type: break_point_type,
+            condition,
         })
     }
 
-    pub fn run(&mut self) -> command::Result<()> {
-        match &self.r#type {
-            Breakpoint::Address(addr) => Ok(self
+    /// Set breakpoint, return its number.
+    pub fn run(&mut self) -> command::Result<u32> {
+        let number = match &self.r#type {
+            BreakpointSpec::Address(addr) => self
                 .dbg
-                .set_breakpoint(PCValue::Relocated((*addr).into()))?),
-            Breakpoint::Line(file, line) => Ok(self.dbg.set_breakpoint_at_line(file, *line)?),
-            Breakpoint::Function(func_name) => Ok(self.dbg.set_breakpoint_at_fn(func_name)?),
+                .set_breakpoint(PCValue::Relocated((*addr).into()))?,
+            BreakpointSpec::Line(file, line) => self.dbg.set_breakpoint_at_line(file, *line)?,
+            BreakpointSpec::Function(func_name) => self.dbg.set_breakpoint_at_fn(func_name)?,
+        };
+
+        if let Some(condition) = self.condition.clone() {
+            self.dbg.set_breakpoint_condition(number, condition)?;
         }
+        Ok(number)
+    }
+}
+
+/// Parse breakpoint location and optional condition from input in form: `{place} [if {condition}]`.
+/// Location is the first word of input, rest of input must start with `if` keyword.
+fn parse_breakpoint(input: &str) -> command::Result<(BreakpointSpec, Option<Condition>)> {
+    let input = input.trim();
+    let (place, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let location = command::helper::parse_location(place)?;
+
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return Ok((location, None));
+    }
+    let condition = rest
+        .strip_prefix("if")
+        .filter(|condition| condition.starts_with(char::is_whitespace))
+        .ok_or_else(|| {
+            CommandError::InvalidArgumentsEx(format!("expect `if {{condition}}`, found `{rest}`"))
+        })?;
+    Ok((location, Some(ConditionParser::new(condition).parse()?)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::command::expression::{Expr, Literal};
+
+    #[test]
+    fn test_parse_breakpoint() {
+        let (location, condition) = parse_breakpoint("main.rs:10").unwrap();
+        assert_eq!(location, BreakpointSpec::Line("main.rs".to_string(), 10));
+        assert_eq!(condition, None);
+
+        let (location, condition) = parse_breakpoint("  main\tif\tname == \" if \"  ").unwrap();
+        assert_eq!(location, BreakpointSpec::Function("main".to_string()));
+        assert_eq!(
+            condition,
+            Some(ConditionParser::new("name == \" if \"").parse().unwrap())
+        );
+
+        let (location, condition) = parse_breakpoint("0x1000 if  x > 1").unwrap();
+        assert_eq!(location, BreakpointSpec::Address(0x1000));
+        assert_eq!(
+            condition,
+            Some(ConditionParser::new("x > 1").parse().unwrap())
+        );
+
+        // character literal is a char, not a one-letter string
+        let (_, condition) = parse_breakpoint("main.rs:10 if c == 'a'").unwrap();
+        let Expr::Binary(_, _, rhs) = condition.unwrap().expr else {
+            panic!("comparison expected");
+        };
+        assert_eq!(*rhs, Expr::Literal(Literal::Char('a')));
+
+        assert!(parse_breakpoint("main x > 1").is_err());
+        assert!(parse_breakpoint("main ifx > 1").is_err());
+        assert!(parse_breakpoint("main if").is_err());
     }
 }
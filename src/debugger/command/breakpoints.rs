@@ -0,0 +1,106 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger, UserBreakpoint, Watchpoint};
+use std::str::FromStr;
+
+enum SubCommand {
+    Info,
+    Delete(Vec<u32>),
+    Enable(Vec<u32>),
+    Disable(Vec<u32>),
+    Ignore(u32, u32),
+}
+
+/// All user breakpoints and watchpoints ordered by number.
+pub struct BreakpointList<'a> {
+    pub breakpoints: Vec<&'a UserBreakpoint>,
+    pub watchpoints: &'a [Watchpoint],
+}
+
+/// Manage existing breakpoints by their numbers.
+pub struct Breakpoints<'a> {
+    dbg: &'a mut Debugger,
+    sub_cmd: SubCommand,
+}
+
+fn parse_number(arg: &str) -> command::Result<u32> {
+    u32::from_str(arg).map_err(|e| CommandError::InvalidArgumentsEx(e.to_string()))
+}
+
+impl<'a> Breakpoints<'a> {
+    /// Create command from arguments in form: `info break`, `delete {N}..`, `enable {N}..`,
+    /// `disable {N}..` or `ignore {N} {count}`.
+    pub fn new<'s>(debugger: &'a mut Debugger, args: Vec<&'s str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+
+        let numbers = || -> command::Result<Vec<u32>> {
+            args[1..].iter().map(|arg| parse_number(arg)).collect()
+        };
+        let sub_cmd = match args[0].to_lowercase().as_str() {
+            "info" => match args[1] {
+                "b" | "break" | "breakpoints" => SubCommand::Info,
+                _ => return Err(CommandError::InvalidArguments),
+            },
+            "delete" => SubCommand::Delete(numbers()?),
+            "enable" => SubCommand::Enable(numbers()?),
+            "disable" => SubCommand::Disable(numbers()?),
+            "ignore" => {
+                command::helper::check_args_count(&args, 3)?;
+                SubCommand::Ignore(parse_number(args[1])?, parse_number(args[2])?)
+            }
+            _ => return Err(CommandError::InvalidArguments),
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            sub_cmd,
+        })
+    }
+
+    /// Run command, breakpoint list returns for `info break`.
+    pub fn run(&mut self) -> command::Result<Option<BreakpointList<'_>>> {
+        match &self.sub_cmd {
+            SubCommand::Info => {
+                return Ok(Some(BreakpointList {
+                    breakpoints: self.dbg.breakpoints(),
+                    watchpoints: self.dbg.watchpoints(),
+                }))
+            }
+            SubCommand::Delete(numbers) => {
+                for &number in numbers {
+                    if self.is_watchpoint(number) {
+                        self.dbg.remove_watchpoint(number)?;
+                    } else {
+                        self.dbg.remove_breakpoint(number)?;
+                    }
+                }
+            }
+            SubCommand::Enable(numbers) => {
+                for &number in numbers {
+                    if self.is_watchpoint(number) {
+                        self.dbg.enable_watchpoint(number)?;
+                    } else {
+                        self.dbg.enable_breakpoint(number)?;
+                    }
+                }
+            }
+            SubCommand::Disable(numbers) => {
+                for &number in numbers {
+                    if self.is_watchpoint(number) {
+                        self.dbg.disable_watchpoint(number)?;
+                    } else {
+                        self.dbg.disable_breakpoint(number)?;
+                    }
+                }
+            }
+            SubCommand::Ignore(number, count) => {
+                self.dbg.set_breakpoint_ignore_count(*number, *count)?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Breakpoints and watchpoints share numbering, return true if `number` is a watchpoint.
+    fn is_watchpoint(&self, number: u32) -> bool {
+        self.dbg.watchpoints().iter().any(|wp| wp.number == number)
+    }
+}
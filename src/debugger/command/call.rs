@@ -0,0 +1,33 @@
+use crate::debugger::command::expression::{self, CallParser};
+use crate::debugger::command::CommandError;
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{command, Debugger};
+
+/// Call debugee function, like `call foo::bar(1, &x)` or `call v.len()`.
+pub struct Call<'a> {
+    dbg: &'a Debugger,
+    call: expression::Call,
+}
+
+impl<'a> Call<'a> {
+    pub fn new(debugger: &'a Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+        let string = args[1..].join(" ");
+        let parser = CallParser::new(&string);
+        if !parser.is_call() {
+            return Err(CommandError::InvalidArgumentsEx(format!(
+                "not a function call: {string}"
+            )));
+        }
+
+        Ok(Self {
+            dbg: debugger,
+            call: parser.parse()?,
+        })
+    }
+
+    /// Return value returned by function, `None` if function returns nothing.
+    pub fn run(self) -> command::Result<Option<VariableIR>> {
+        Ok(self.dbg.call_function(self.call)?)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger};
+
+/// Event on which debugee stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatchEvent {
+    /// Any thread of debugee panics.
+    Panic,
+}
+
+pub struct Catch<'a> {
+    dbg: &'a mut Debugger,
+    pub event: CatchEvent,
+}
+
+impl<'a> Catch<'a> {
+    /// Create catch command from arguments in form: `catch {event}`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+
+        let event = match args[1] {
+            "panic" => CatchEvent::Panic,
+            event => {
+                return Err(CommandError::InvalidArgumentsEx(format!(
+                    "unknown event {event}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            event,
+        })
+    }
+
+    pub fn run(self) -> command::Result<()> {
+        match self.event {
+            CatchEvent::Panic => self.dbg.catch_panic()?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, CheckpointInfo, Debugger};
+
+enum SubCommand {
+    Create,
+    List,
+    Restart(u32),
+    Delete(u32),
+}
+
+pub enum Response {
+    /// Number of created checkpoint.
+    Created(u32),
+    /// Checkpoints sorted by number.
+    List(Vec<CheckpointInfo>),
+    /// Debugee restarted or checkpoint deleted.
+    Done,
+}
+
+pub struct Checkpoint<'a> {
+    dbg: &'a mut Debugger,
+    sub_cmd: SubCommand,
+}
+
+impl<'a> Checkpoint<'a> {
+    /// Create command from arguments in form:
+    /// `checkpoint`, `checkpoint list`, `checkpoint delete {number}` or `restart {number}`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        let parse_num = |arg: Option<&&str>| -> command::Result<u32> {
+            let arg = arg.ok_or(CommandError::InvalidArguments)?;
+            arg.parse().map_err(|_| {
+                CommandError::InvalidArgumentsEx(format!("invalid checkpoint number {arg}"))
+            })
+        };
+
+        let sub_cmd = if args[0].to_lowercase() == "restart" {
+            SubCommand::Restart(parse_num(args.get(1))?)
+        } else {
+            match args.get(1).map(|arg| arg.to_lowercase()).as_deref() {
+                None | Some("") => SubCommand::Create,
+                Some("list") | Some("info") => SubCommand::List,
+                Some("delete") => SubCommand::Delete(parse_num(args.get(2))?),
+                Some(arg) => {
+                    return Err(CommandError::InvalidArgumentsEx(format!(
+                        "unknown subcommand {arg}"
+                    )))
+                }
+            }
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            sub_cmd,
+        })
+    }
+
+    pub fn run(self) -> command::Result<Response> {
+        match self.sub_cmd {
+            SubCommand::Create => Ok(Response::Created(self.dbg.checkpoint()?)),
+            SubCommand::List => Ok(Response::List(self.dbg.checkpoints()?)),
+            SubCommand::Restart(num) => {
+                self.dbg.restart_checkpoint(num)?;
+                Ok(Response::Done)
+            }
+            SubCommand::Delete(num) => {
+                self.dbg.delete_checkpoint(num)?;
+                Ok(Response::Done)
+            }
+        }
+    }
+}
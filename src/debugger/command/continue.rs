@@ -1,19 +1,34 @@
+use crate::debugger::command::CommandError;
 use crate::debugger::{command, Debugger};
 use anyhow::Context;
 
 pub struct Continue<'a> {
     dbg: &'a mut Debugger,
+    all: bool,
 }
 
 impl<'a> Continue<'a> {
-    pub fn new(debugger: &'a mut Debugger) -> Self {
-        Self { dbg: debugger }
+    /// Create command from arguments in form: `continue [-a]`,
+    /// with `-a` all stopped threads continue (in non-stop mode or with scheduler locking).
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        let all = match args.get(1).copied() {
+            None | Some("") => false,
+            Some("-a") => true,
+            Some(arg) => {
+                return Err(CommandError::InvalidArgumentsEx(format!(
+                    "unknown argument {arg}"
+                )))
+            }
+        };
+        Ok(Self { dbg: debugger, all })
     }
 
     pub fn run(&mut self) -> command::Result<()> {
-        Ok(self
-            .dbg
-            .continue_debugee()
-            .context("Failed to continue execution")?)
+        let result = if self.all {
+            self.dbg.continue_all()
+        } else {
+            self.dbg.continue_debugee()
+        };
+        Ok(result.context("Failed to continue execution")?)
     }
 }
@@ -0,0 +1,322 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::command::expression::{Expr, ExprParser};
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger};
+use iced_x86::{Decoder, DecoderError, DecoderOptions, Formatter, GasFormatter, Instruction};
+
+/// Maximum length of string read with [`ExamineFormat::String`].
+const MAX_STRING_LEN: usize = 4096;
+/// Maximum length of x86-64 instruction.
+const MAX_INSTRUCTION_LEN: usize = 15;
+/// Code for disassembling read in chunks of this size, chunk never crosses a page boundary.
+const CODE_CHUNK_LEN: usize = 256;
+/// Maximum number of examined units (bytes, words, characters or instructions) in bytes.
+const MAX_EXAMINE_LEN: usize = 1 << 20;
+/// Number of bytes in a line of hexdump.
+const HEXDUMP_LINE_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExamineFormat {
+    Hex,
+    Decimal,
+    Char,
+    String,
+    Instruction,
+    Float,
+    /// Bytes in hex with ASCII column.
+    HexDump,
+}
+
+impl ExamineFormat {
+    fn from_letter(letter: char) -> Option<Self> {
+        let format = match letter {
+            'x' => ExamineFormat::Hex,
+            'd' => ExamineFormat::Decimal,
+            'c' => ExamineFormat::Char,
+            's' => ExamineFormat::String,
+            'i' => ExamineFormat::Instruction,
+            'f' => ExamineFormat::Float,
+            'y' => ExamineFormat::HexDump,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Unit size used if it is not set explicitly.
+    fn default_unit(self) -> usize {
+        match self {
+            ExamineFormat::Hex | ExamineFormat::Decimal => 4,
+            ExamineFormat::Float => 8,
+            _ => 1,
+        }
+    }
+}
+
+/// Line of examined memory.
+#[derive(Debug, PartialEq)]
+pub struct ExamineLine {
+    pub addr: RelocatedAddress,
+    /// Function symbol and offset of line address, like `main+4`.
+    pub symbol: Option<String>,
+    pub items: Vec<String>,
+}
+
+/// Examine debugee memory, command in the form of "x[/{count}{format}{unit}] {address}".
+/// Format is one of `x` (hex), `d` (decimal), `c` (char), `s` (string), `i` (instruction),
+/// `f` (float) or `y` (hexdump with ASCII column), unit is one of `b` (1 byte),
+/// `h` (2 bytes), `w` (4 bytes) or `g` (8 bytes). Address is an expression,
+/// like `&var`, `ptr + 8`, `0x5555555551a0` or a symbol name.
+pub struct Examine<'a> {
+    dbg: &'a Debugger,
+    count: usize,
+    format: ExamineFormat,
+    unit: usize,
+    /// Number of examined bytes for formats that read memory at once.
+    len: usize,
+    addr: Expr,
+}
+
+impl<'a> Examine<'a> {
+    pub fn new(debugger: &'a Debugger, args: Vec<&'a str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+
+        let spec = args[0].split_once('/').map(|(_, spec)| spec).unwrap_or("");
+        let digits = spec.chars().take_while(char::is_ascii_digit).count();
+        let count: usize = match &spec[..digits] {
+            "" => 1,
+            count => count
+                .parse()
+                .map_err(|_| CommandError::InvalidArgumentsEx(format!("invalid count {count}")))?,
+        };
+
+        let mut format = ExamineFormat::Hex;
+        let mut unit = None;
+        for letter in spec[digits..].chars() {
+            match letter {
+                'b' => unit = Some(1),
+                'h' => unit = Some(2),
+                'w' => unit = Some(4),
+                'g' => unit = Some(8),
+                _ => {
+                    format = ExamineFormat::from_letter(letter).ok_or_else(|| {
+                        CommandError::InvalidArgumentsEx(format!("unknown format {letter}"))
+                    })?
+                }
+            }
+        }
+        let unit = unit.unwrap_or(format.default_unit());
+        if format == ExamineFormat::Float && unit < 4 {
+            return Err(CommandError::InvalidArgumentsEx(
+                "float format requires unit of 4 or 8 bytes".to_string(),
+            ));
+        }
+
+        let len = count
+            .checked_mul(unit)
+            .filter(|&len| len <= MAX_EXAMINE_LEN)
+            .ok_or_else(|| {
+                CommandError::InvalidArgumentsEx(format!(
+                    "count must not exceed {}",
+                    MAX_EXAMINE_LEN / unit
+                ))
+            })?;
+
+        let addr = ExprParser::new(&args[1..].join(" ")).parse()?;
+
+        Ok(Self {
+            dbg: debugger,
+            count,
+            format,
+            unit,
+            len,
+            addr,
+        })
+    }
+
+    pub fn run(&self) -> command::Result<Vec<ExamineLine>> {
+        let addr = usize::from(self.dbg.evaluate_address(&self.addr)?);
+
+        let lines = match self.format {
+            ExamineFormat::String => self.strings(addr)?,
+            ExamineFormat::Instruction => self.instructions(addr)?,
+            ExamineFormat::HexDump => {
+                let bytes = self.dbg.read_memory_unpatched(addr, self.len)?;
+                bytes
+                    .chunks(HEXDUMP_LINE_LEN)
+                    .enumerate()
+                    .map(|(i, chunk)| self.line(addr + i * HEXDUMP_LINE_LEN, hexdump(chunk)))
+                    .collect()
+            }
+            _ => {
+                let bytes = self.dbg.read_memory_unpatched(addr, self.len)?;
+                let per_line = match self.unit {
+                    1 | 2 => 8,
+                    4 => 4,
+                    _ => 2,
+                };
+                bytes
+                    .chunks(self.unit * per_line)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        let items = chunk
+                            .chunks(self.unit)
+                            .map(|unit| render_unit(unit, self.format))
+                            .collect();
+                        self.line(addr + i * self.unit * per_line, items)
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(lines)
+    }
+
+    fn line(&self, addr: usize, items: Vec<String>) -> ExamineLine {
+        let addr = RelocatedAddress::from(addr);
+        ExamineLine {
+            addr,
+            symbol: self
+                .dbg
+                .symbolize(addr)
+                .map(|(name, offset)| format!("{name}+{offset}")),
+            items,
+        }
+    }
+
+    /// Read `count` null-terminated strings starting at `addr`.
+    fn strings(&self, mut addr: usize) -> anyhow::Result<Vec<ExamineLine>> {
+        let mut lines = Vec::with_capacity(self.count);
+        for _ in 0..self.count {
+            let mut string = vec![];
+            let terminated = loop {
+                // chunk ends at 64 byte boundary, so never crosses a page boundary
+                let chunk_addr = addr + string.len();
+                let chunk_len = 64 - chunk_addr % 64;
+                let chunk = self.dbg.read_memory_unpatched(chunk_addr, chunk_len)?;
+                match chunk.iter().position(|&b| b == 0) {
+                    Some(pos) => {
+                        string.extend_from_slice(&chunk[..pos]);
+                        break true;
+                    }
+                    None => string.extend_from_slice(&chunk),
+                }
+                if string.len() >= MAX_STRING_LEN {
+                    string.truncate(MAX_STRING_LEN);
+                    break false;
+                }
+            };
+
+            let mut rendered = format!("{:?}", String::from_utf8_lossy(&string));
+            if !terminated {
+                rendered.push_str("...");
+            }
+            lines.push(self.line(addr, vec![rendered]));
+            addr += string.len() + 1;
+        }
+        Ok(lines)
+    }
+
+    /// Disassemble `count` instructions starting at `addr`, disassembling stops
+    /// at the end of readable memory.
+    fn instructions(&self, addr: usize) -> anyhow::Result<Vec<ExamineLine>> {
+        let mut formatter = GasFormatter::new();
+        let mut instruction = Instruction::default();
+
+        // not decoded code and its address
+        let mut code = vec![];
+        let mut code_addr = addr;
+        let mut end_of_memory = false;
+        let mut lines = Vec::with_capacity(self.count);
+        while lines.len() < self.count {
+            if code.len() < MAX_INSTRUCTION_LEN && !end_of_memory {
+                let chunk_addr = code_addr + code.len();
+                let chunk_len = CODE_CHUNK_LEN - chunk_addr % CODE_CHUNK_LEN;
+                match self.dbg.read_memory_unpatched(chunk_addr, chunk_len) {
+                    Ok(chunk) => code.extend_from_slice(&chunk),
+                    Err(_) if chunk_addr != addr => end_of_memory = true,
+                    Err(e) => return Err(e),
+                }
+                continue;
+            }
+
+            let mut decoder = Decoder::with_ip(64, &code, code_addr as u64, DecoderOptions::NONE);
+            decoder.decode_out(&mut instruction);
+            if decoder.last_error() == DecoderError::NoMoreBytes {
+                break;
+            }
+            let mut rendered = String::new();
+            formatter.format(&instruction, &mut rendered);
+            lines.push(self.line(code_addr, vec![rendered]));
+
+            code.drain(..instruction.len());
+            code_addr += instruction.len();
+        }
+        Ok(lines)
+    }
+}
+
+/// Render unit of memory (1, 2, 4 or 8 bytes in native byte order) in `format`.
+fn render_unit(unit: &[u8], format: ExamineFormat) -> String {
+    let mut bytes = [0; 8];
+    bytes[..unit.len()].copy_from_slice(unit);
+    let value = u64::from_ne_bytes(bytes);
+    let bits = unit.len() as u32 * 8;
+
+    match format {
+        ExamineFormat::Decimal => {
+            // sign extension of value
+            let shift = 64 - bits;
+            (((value << shift) as i64) >> shift).to_string()
+        }
+        ExamineFormat::Char => match char::from_u32(value as u32) {
+            Some(char) => format!("{value} '{}'", char.escape_default()),
+            None => value.to_string(),
+        },
+        ExamineFormat::Float if unit.len() == 4 => format!("{:?}", f32::from_bits(value as u32)),
+        ExamineFormat::Float => format!("{:?}", f64::from_bits(value)),
+        _ => format!("{value:#0width$x}", width = unit.len() * 2 + 2),
+    }
+}
+
+/// Render bytes as hex and as ASCII column, non-printable characters rendered as dots.
+fn hexdump(bytes: &[u8]) -> Vec<String> {
+    let mut items: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    items.resize(HEXDUMP_LINE_LEN, "  ".to_string());
+    let ascii: String = bytes
+        .iter()
+        .map(|&b| match b.is_ascii_graphic() || b == b' ' {
+            true => b as char,
+            false => '.',
+        })
+        .collect();
+    items.push(format!("|{ascii}|"));
+    items
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_unit() {
+        assert_eq!(render_unit(&[0xff], ExamineFormat::Hex), "0xff");
+        assert_eq!(render_unit(&[0x1, 0], ExamineFormat::Hex), "0x0001");
+        assert_eq!(render_unit(&[0xff, 0xff], ExamineFormat::Decimal), "-1");
+        assert_eq!(render_unit(&[0xff, 0], ExamineFormat::Decimal), "255");
+        assert_eq!(render_unit(&[b'a'], ExamineFormat::Char), "97 'a'");
+        assert_eq!(render_unit(&[b'\n'], ExamineFormat::Char), "10 '\\n'");
+        assert_eq!(
+            render_unit(&1.5f32.to_ne_bytes(), ExamineFormat::Float),
+            "1.5"
+        );
+        assert_eq!(
+            render_unit(&(-2.25f64).to_ne_bytes(), ExamineFormat::Float),
+            "-2.25"
+        );
+
+        let dump = hexdump(b"hi\x00");
+        assert_eq!(dump.len(), HEXDUMP_LINE_LEN + 1);
+        assert_eq!(&dump[..4], ["68", "69", "00", "  "]);
+        assert_eq!(dump[HEXDUMP_LINE_LEN], "|hi.|");
+    }
+}
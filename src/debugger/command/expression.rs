@@ -1,3 +1,7 @@
+use crate::debugger::variable::arithmetic::Value;
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::{SupportedScalar, VariableIR};
+use anyhow::{anyhow, bail};
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::mem;
@@ -11,7 +15,27 @@ pub enum Token {
     DoubleDot,
     OpenSquareBracket,
     ClosedSquareBracket,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Not,
+    Comma,
+    Ref,
+    Plus,
+    Minus,
+    Slash,
+    Percent,
+    Shl,
+    Shr,
+    Pipe,
+    Caret,
     Text(String),
+    Str(String),
     End,
 }
 
@@ -47,9 +71,74 @@ impl Token {
                 )
             },
             Token::DoubleDot => |tok| matches!(tok, Token::Text(_)),
+            Token::Eq
+            | Token::Ne
+            | Token::Lt
+            | Token::Le
+            | Token::Gt
+            | Token::Ge
+            | Token::And
+            | Token::Or
+            | Token::Not
+            | Token::Plus
+            | Token::Minus
+            | Token::Slash
+            | Token::Percent
+            | Token::Shl
+            | Token::Shr
+            | Token::Pipe
+            | Token::Caret => |tok| {
+                matches!(
+                    tok,
+                    Token::Deref
+                        | Token::OpenBracket
+                        | Token::Not
+                        | Token::Minus
+                        | Token::Ref
+                        | Token::Text(_)
+                        | Token::Str(_)
+                )
+            },
+            Token::Str(_) => |tok| matches!(tok, Token::ClosedBracket | Token::End),
+            Token::Comma => |_| true,
+            Token::Ref => |tok| matches!(tok, Token::Deref | Token::OpenBracket | Token::Text(_)),
             Token::End => |_| true,
         }
     }
+
+    fn as_source(&self) -> String {
+        match self {
+            Token::Deref => "*".to_string(),
+            Token::OpenBracket => "(".to_string(),
+            Token::ClosedBracket => ")".to_string(),
+            Token::Dot => ".".to_string(),
+            Token::DoubleDot => "..".to_string(),
+            Token::OpenSquareBracket => "[".to_string(),
+            Token::ClosedSquareBracket => "]".to_string(),
+            Token::Eq => " == ".to_string(),
+            Token::Ne => " != ".to_string(),
+            Token::Lt => " < ".to_string(),
+            Token::Le => " <= ".to_string(),
+            Token::Gt => " > ".to_string(),
+            Token::Ge => " >= ".to_string(),
+            Token::And => " && ".to_string(),
+            Token::Or => " || ".to_string(),
+            Token::Not => "!".to_string(),
+            Token::Comma => ", ".to_string(),
+            Token::Ref => "&".to_string(),
+            Token::Plus => " + ".to_string(),
+            Token::Minus => " - ".to_string(),
+            Token::Slash => " / ".to_string(),
+            Token::Percent => " % ".to_string(),
+            Token::Shl => " << ".to_string(),
+            Token::Shr => " >> ".to_string(),
+            Token::Pipe => " | ".to_string(),
+            Token::Caret => " ^ ".to_string(),
+            Token::Text(text) => text.clone(),
+            Token::Str(string) => format!("\"{string}\""),
+            Token::End => String::new(),
+        }
+    }
 }
 
 struct Tokenizer<'a> {
@@ -68,28 +157,94 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn tokenize(mut self) -> Vec<Token> {
-        let mut skip = false;
-        for (i, char) in self.string.chars().enumerate() {
-            if skip {
-                skip = false;
-                continue;
-            }
-            match char {
-                '*' => self.push_text_and_token(Token::Deref),
-                '(' => self.push_text_and_token(Token::OpenBracket),
-                ')' => self.push_text_and_token(Token::ClosedBracket),
-                '.' => {
-                    if self.string.chars().nth(i + 1) == Some('.') {
-                        self.push_text_and_token(Token::DoubleDot);
-                        skip = true;
-                    } else {
-                        self.push_text_and_token(Token::Dot)
-                    }
+        let chars = self.string.chars().collect::<Vec<_>>();
+        let mut i = 0;
+        while i < chars.len() {
+            let next = chars.get(i + 1).copied();
+            match (chars[i], next) {
+                ('*', _) => self.push_text_and_token(Token::Deref),
+                ('(', _) => self.push_text_and_token(Token::OpenBracket),
+                (')', _) => self.push_text_and_token(Token::ClosedBracket),
+                ('.', Some('.')) => {
+                    self.push_text_and_token(Token::DoubleDot);
+                    i += 1;
+                }
+                // fractional part of a float literal, but not a tuple field like `var.0.1`
+                ('.', Some(n)) if n.is_ascii_digit() && self.accum_is_number() => {
+                    self.accum.push('.')
+                }
+                ('.', _) => self.push_text_and_token(Token::Dot),
+                ('[', _) => self.push_text_and_token(Token::OpenSquareBracket),
+                (']', _) => self.push_text_and_token(Token::ClosedSquareBracket),
+                ('=', Some('=')) => {
+                    self.push_text_and_token(Token::Eq);
+                    i += 1;
+                }
+                ('!', Some('=')) => {
+                    self.push_text_and_token(Token::Ne);
+                    i += 1;
+                }
+                ('!', _) => self.push_text_and_token(Token::Not),
+                ('<', Some('=')) => {
+                    self.push_text_and_token(Token::Le);
+                    i += 1;
+                }
+                ('<', Some('<')) => {
+                    self.push_text_and_token(Token::Shl);
+                    i += 1;
+                }
+                ('<', _) => self.push_text_and_token(Token::Lt),
+                ('>', Some('=')) => {
+                    self.push_text_and_token(Token::Ge);
+                    i += 1;
+                }
+                ('>', Some('>')) => {
+                    self.push_text_and_token(Token::Shr);
+                    i += 1;
                 }
-                '[' => self.push_text_and_token(Token::OpenSquareBracket),
-                ']' => self.push_text_and_token(Token::ClosedSquareBracket),
-                _ => self.accum.push(char),
+                ('>', _) => self.push_text_and_token(Token::Gt),
+                ('&', Some('&')) => {
+                    self.push_text_and_token(Token::And);
+                    i += 1;
+                }
+                ('|', Some('|')) => {
+                    self.push_text_and_token(Token::Or);
+                    i += 1;
+                }
+                ('&', _) => self.push_text_and_token(Token::Ref),
+                ('|', _) => self.push_text_and_token(Token::Pipe),
+                ('^', _) => self.push_text_and_token(Token::Caret),
+                ('+', _) => self.push_text_and_token(Token::Plus),
+                // sign of a number literal, but not a subtraction like `a - 1`
+                ('-', Some(n))
+                    if n.is_ascii_digit() && self.accum.is_empty() && !self.after_operand() =>
+                {
+                    self.accum.push('-')
+                }
+                ('-', _) => self.push_text_and_token(Token::Minus),
+                ('/', _) => self.push_text_and_token(Token::Slash),
+                ('%', _) => self.push_text_and_token(Token::Percent),
+                (',', _) => self.push_text_and_token(Token::Comma),
+                ('"', _) => {
+                    let literal = chars[i + 1..]
+                        .iter()
+                        .take_while(|&&c| c != '"')
+                        .collect::<String>();
+                    i += literal.chars().count() + 1;
+                    self.push_text_and_token(Token::Str(literal));
+                }
+                ('\'', _) if self.accum.is_empty() => {
+                    let literal = chars[i + 1..]
+                        .iter()
+                        .take_while(|&&c| c != '\'')
+                        .collect::<String>();
+                    i += literal.chars().count() + 1;
+                    self.push_text_and_token(Token::Text(format!("'{literal}'")));
+                }
+                (c, _) if c.is_whitespace() => self.push_text(),
+                (c, _) => self.accum.push(c),
             }
+            i += 1;
         }
         self.push_text();
         self.tokens.push(Token::End);
@@ -97,6 +252,22 @@ impl<'a> Tokenizer<'a> {
         self.tokens
     }
 
+    fn accum_is_number(&self) -> bool {
+        !self.accum.is_empty()
+            && self.accum.chars().all(|c| c.is_ascii_digit() || c == '-')
+            && self.tokens.last() != Some(&Token::Dot)
+    }
+
+    /// Return true if last token ends an operand, so next operator is a binary one.
+    fn after_operand(&self) -> bool {
+        matches!(
+            self.tokens.last(),
+            Some(
+                Token::Text(_) | Token::Str(_) | Token::ClosedBracket | Token::ClosedSquareBracket
+            )
+        )
+    }
+
     fn push_text(&mut self) {
         let text = mem::take(&mut self.accum);
         if !text.is_empty() {
@@ -175,7 +346,7 @@ pub enum ParseError {
 }
 
 /// `SelectPlan` item.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Operation {
     Deref,
     FindVariable(String),
@@ -187,7 +358,7 @@ pub enum Operation {
 /// List of operations for further execution.
 /// `SelectPlan` can be generated from an input string of the form "{operator}{open bracket}variable{operator}{field}{index}{closed bracket}"
 /// Supported operators are: dereference, get element by index, get field by name.
-#[derive(Debug, PartialEq, Default)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct SelectPlan {
     pub source: String,
     pub plan: VecDeque<Operation>,
@@ -227,7 +398,11 @@ impl<'a> SelectPlanParser<'a> {
     /// Parse `SelectPlan` from input string.
     pub fn parse(&self) -> Result<SelectPlan, ParseError> {
         let tokenizer = Tokenizer::new(self.string);
+        Self::parse_tokens(self.string.to_string(), tokenizer.tokenize())
+    }
 
+    /// Parse `SelectPlan` from tokens, `tokens` must end with [`Token::End`].
+    fn parse_tokens(source: String, mut tokens: Vec<Token>) -> Result<SelectPlan, ParseError> {
         enum OperatorOrOperand {
             Operator(Operator),
             Operand(String),
@@ -257,7 +432,6 @@ impl<'a> SelectPlanParser<'a> {
         }
 
         let mut next_valid: fn(&Token) -> bool = |_: &Token| true;
-        let mut tokens = tokenizer.tokenize();
 
         for i in 0..tokens.len() {
             if !next_valid(&tokens[i]) {
@@ -307,6 +481,7 @@ impl<'a> SelectPlanParser<'a> {
                 Token::ClosedSquareBracket => {}
                 Token::DoubleDot => {}
                 Token::End => {}
+                tok => return Err(ParseError::UnexpectedToken(tok.clone())),
             }
         }
 
@@ -364,13 +539,660 @@ impl<'a> SelectPlanParser<'a> {
             }
         }
 
-        Ok(SelectPlan {
-            source: self.string.to_string(),
-            plan,
+        Ok(SelectPlan { source, plan })
+    }
+}
+
+/// Literal value in condition expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Literal {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Char(char),
+    /// Enum variant path like `State::Ready`, compared by variant name only.
+    EnumVariant(String),
+}
+
+impl Literal {
+    /// Interpret a text token as literal, return `None` if text is not a literal.
+    pub(crate) fn from_text(text: &str) -> Option<Self> {
+        if let Ok(int) = text.parse::<i128>() {
+            return Some(Literal::Int(int));
+        }
+        if let Some(hex) = text.strip_prefix("0x") {
+            return i128::from_str_radix(hex, 16).ok().map(Literal::Int);
+        }
+        if let Some(char) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+            let mut chars = char.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(char), None) => Some(Literal::Char(char)),
+                _ => None,
+            };
+        }
+        if text.contains('.') {
+            if let Ok(float) = text.parse::<f64>() {
+                return Some(Literal::Float(float));
+            }
+        }
+        match text {
+            "true" => Some(Literal::Bool(true)),
+            "false" => Some(Literal::Bool(false)),
+            _ if text.contains("::") => Some(Literal::EnumVariant(text.to_string())),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn compare(&self, op: CompareOperator, other: &Literal) -> anyhow::Result<bool> {
+        let ordering = match (self, other) {
+            (Literal::Int(a), Literal::Int(b)) => a.partial_cmp(b),
+            (Literal::Int(a), Literal::Float(b)) => (*a as f64).partial_cmp(b),
+            (Literal::Float(a), Literal::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (Literal::Float(a), Literal::Float(b)) => a.partial_cmp(b),
+            (Literal::Bool(a), Literal::Bool(b)) => a.partial_cmp(b),
+            (Literal::String(a), Literal::String(b)) => a.partial_cmp(b),
+            (Literal::EnumVariant(a), Literal::EnumVariant(b)) => {
+                let variant_a = a.rsplit("::").next();
+                let variant_b = b.rsplit("::").next();
+                return match op {
+                    CompareOperator::Eq => Ok(variant_a == variant_b),
+                    CompareOperator::Ne => Ok(variant_a != variant_b),
+                    _ => bail!("enum variants can only be checked for equality"),
+                };
+            }
+            _ => bail!("incompatible operands: {self:?} and {other:?}"),
+        };
+
+        Ok(match op {
+            CompareOperator::Eq => ordering == Some(Ordering::Equal),
+            CompareOperator::Ne => ordering != Some(Ordering::Equal),
+            CompareOperator::Lt => ordering == Some(Ordering::Less),
+            CompareOperator::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            CompareOperator::Gt => ordering == Some(Ordering::Greater),
+            CompareOperator::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CompareOperator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOperator {
+    fn from_token(tok: &Token) -> Option<Self> {
+        match tok {
+            Token::Eq => Some(CompareOperator::Eq),
+            Token::Ne => Some(CompareOperator::Ne),
+            Token::Lt => Some(CompareOperator::Lt),
+            Token::Le => Some(CompareOperator::Le),
+            Token::Gt => Some(CompareOperator::Gt),
+            Token::Ge => Some(CompareOperator::Ge),
+            _ => None,
+        }
+    }
+}
+
+/// Target type of `as` cast.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ScalarType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    F32,
+    F64,
+    Bool,
+    Char,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> Option<Self> {
+        let r#type = match name {
+            "i8" => ScalarType::I8,
+            "i16" => ScalarType::I16,
+            "i32" => ScalarType::I32,
+            "i64" => ScalarType::I64,
+            "i128" => ScalarType::I128,
+            "isize" => ScalarType::Isize,
+            "u8" => ScalarType::U8,
+            "u16" => ScalarType::U16,
+            "u32" => ScalarType::U32,
+            "u64" => ScalarType::U64,
+            "u128" => ScalarType::U128,
+            "usize" => ScalarType::Usize,
+            "f32" => ScalarType::F32,
+            "f64" => ScalarType::F64,
+            "bool" => ScalarType::Bool,
+            "char" => ScalarType::Char,
+            _ => return None,
+        };
+        Some(r#type)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScalarType::I8 => "i8",
+            ScalarType::I16 => "i16",
+            ScalarType::I32 => "i32",
+            ScalarType::I64 => "i64",
+            ScalarType::I128 => "i128",
+            ScalarType::Isize => "isize",
+            ScalarType::U8 => "u8",
+            ScalarType::U16 => "u16",
+            ScalarType::U32 => "u32",
+            ScalarType::U64 => "u64",
+            ScalarType::U128 => "u128",
+            ScalarType::Usize => "usize",
+            ScalarType::F32 => "f32",
+            ScalarType::F64 => "f64",
+            ScalarType::Bool => "bool",
+            ScalarType::Char => "char",
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        !matches!(
+            self,
+            ScalarType::F32 | ScalarType::F64 | ScalarType::Bool | ScalarType::Char
+        )
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, ScalarType::F32 | ScalarType::F64)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Compare(CompareOperator),
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    /// Return operator for a token in binary position and operator precedence,
+    /// higher precedence binds tighter.
+    fn from_token(tok: &Token) -> Option<(Self, u8)> {
+        let op = match tok {
+            Token::Deref => (BinaryOperator::Mul, 9),
+            Token::Slash => (BinaryOperator::Div, 9),
+            Token::Percent => (BinaryOperator::Rem, 9),
+            Token::Plus => (BinaryOperator::Add, 8),
+            Token::Minus => (BinaryOperator::Sub, 8),
+            Token::Shl => (BinaryOperator::Shl, 7),
+            Token::Shr => (BinaryOperator::Shr, 7),
+            Token::Ref => (BinaryOperator::BitAnd, 6),
+            Token::Caret => (BinaryOperator::BitXor, 5),
+            Token::Pipe => (BinaryOperator::BitOr, 4),
+            Token::And => (BinaryOperator::And, 2),
+            Token::Or => (BinaryOperator::Or, 1),
+            tok => (
+                BinaryOperator::Compare(CompareOperator::from_token(tok)?),
+                3,
+            ),
+        };
+        Some(op)
+    }
+}
+
+/// Expression tree.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr {
+    Literal(Literal),
+    /// Data selected from debugee (see [`SelectPlan`]).
+    Select(SelectPlan),
+    /// Address of data selected from debugee (`&var`).
+    AddressOf(SelectPlan),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Cast(Box<Expr>, ScalarType),
+    Binary(Box<Expr>, BinaryOperator, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate expression into a new variable with name `name`,
+    /// `read_variable` used for read a variable value by it select plan.
+    pub fn evaluate<F>(&self, name: &str, read_variable: F) -> anyhow::Result<VariableIR>
+    where
+        F: Fn(&SelectPlan) -> anyhow::Result<VariableIR>,
+    {
+        self.value(&read_variable)?.into_variable(name)
+    }
+
+    fn value<F>(&self, read_variable: &F) -> anyhow::Result<Value>
+    where
+        F: Fn(&SelectPlan) -> anyhow::Result<VariableIR>,
+    {
+        Ok(match self {
+            Expr::Literal(literal) => Value::from(literal.clone()),
+            Expr::Select(plan) => match read_variable(plan) {
+                Ok(var) => Value::from_variable(&var)?,
+                // path that is not a static variable may be an enum variant
+                Err(_) if plan.plan.len() == 1 && plan.source.contains("::") => {
+                    Value::EnumVariant(plan.source.clone())
+                }
+                Err(e) => return Err(e),
+            },
+            Expr::AddressOf(plan) => {
+                let var = read_variable(plan)?;
+                let addr = var
+                    .raw_address()
+                    .ok_or_else(|| anyhow!("{} is not located in memory", plan.source))?;
+                Value::Pointer {
+                    addr,
+                    type_name: Some(format!("&{}", var.r#type())),
+                }
+            }
+            Expr::Neg(expr) => expr.value(read_variable)?.neg()?,
+            Expr::Not(expr) => expr.value(read_variable)?.not()?,
+            Expr::Cast(expr, r#type) => expr.value(read_variable)?.cast(*r#type)?,
+            Expr::Binary(lhs, BinaryOperator::And, rhs) => {
+                let value =
+                    lhs.value(read_variable)?.as_bool()? && rhs.value(read_variable)?.as_bool()?;
+                Value::Scalar(SupportedScalar::Bool(value))
+            }
+            Expr::Binary(lhs, BinaryOperator::Or, rhs) => {
+                let value =
+                    lhs.value(read_variable)?.as_bool()? || rhs.value(read_variable)?.as_bool()?;
+                Value::Scalar(SupportedScalar::Bool(value))
+            }
+            Expr::Binary(lhs, op, rhs) => lhs
+                .value(read_variable)?
+                .binary(*op, rhs.value(read_variable)?)?,
         })
     }
 }
 
+/// Parse `Expr` from input string. Using precedence climbing.
+/// Operand is a literal (number, char, string, bool) or a select expression (see [`SelectPlan`]),
+/// path like `crate::STATIC` is a static variable.
+/// Operator precedence from highest to lowest (like in Rust): unary `-`, `!`, `&`, then `as`,
+/// `*` `/` `%`, `+` `-`, `<<` `>>`, `&`, `^`, `|`, comparison, `&&`, `||`.
+pub struct ExprParser<'a> {
+    string: &'a str,
+}
+
+impl<'a> ExprParser<'a> {
+    /// Create new `ExprParser`.
+    pub fn new(string: &'a str) -> Self {
+        Self { string }
+    }
+
+    /// Parse `Expr` from input string.
+    pub fn parse(&self) -> Result<Expr, ParseError> {
+        let tokens = Tokenizer::new(self.string).tokenize();
+        let mut pos = 0;
+        let expr = Self::parse_binary(&tokens, &mut pos, 0)?;
+        if tokens[pos] != Token::End {
+            return Err(ParseError::UnexpectedToken(tokens[pos].clone()));
+        }
+        Ok(expr)
+    }
+
+    fn parse_binary(tokens: &[Token], pos: &mut usize, min_prec: u8) -> Result<Expr, ParseError> {
+        let mut lhs = Self::parse_cast(tokens, pos)?;
+        while let Some((op, prec)) = BinaryOperator::from_token(&tokens[*pos]) {
+            if prec < min_prec {
+                break;
+            }
+            *pos += 1;
+            let rhs = Self::parse_binary(tokens, pos, prec + 1)?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_cast(tokens: &[Token], pos: &mut usize) -> Result<Expr, ParseError> {
+        let mut expr = Self::parse_unary(tokens, pos)?;
+        while matches!(&tokens[*pos], Token::Text(text) if text == "as") {
+            *pos += 1;
+            let Token::Text(name) = &tokens[*pos] else {
+                return Err(ParseError::OperandNotFound("cast"));
+            };
+            let r#type =
+                ScalarType::from_name(name).ok_or(ParseError::InvalidOperand(name.clone()))?;
+            *pos += 1;
+            expr = Expr::Cast(Box::new(expr), r#type);
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(tokens: &[Token], pos: &mut usize) -> Result<Expr, ParseError> {
+        let expr = match &tokens[*pos] {
+            Token::Minus => {
+                *pos += 1;
+                Expr::Neg(Box::new(Self::parse_unary(tokens, pos)?))
+            }
+            Token::Not => {
+                *pos += 1;
+                Expr::Not(Box::new(Self::parse_unary(tokens, pos)?))
+            }
+            Token::Ref => {
+                *pos += 1;
+                Expr::AddressOf(Self::parse_select(tokens, pos)?)
+            }
+            Token::OpenBracket if Self::is_group(tokens, *pos) => {
+                *pos += 1;
+                let expr = Self::parse_binary(tokens, pos, 0)?;
+                if tokens[*pos] != Token::ClosedBracket {
+                    return Err(ParseError::MissClosedBracket);
+                }
+                *pos += 1;
+                expr
+            }
+            Token::Str(string) => {
+                *pos += 1;
+                Expr::Literal(Literal::String(string.clone()))
+            }
+            Token::Text(text) if Self::literal(text).is_some() => {
+                *pos += 1;
+                Expr::Literal(Self::literal(text).expect("checked above"))
+            }
+            _ => Expr::Select(Self::parse_select(tokens, pos)?),
+        };
+        Ok(expr)
+    }
+
+    /// Interpret a text token as literal, unlike [`Literal::from_text`] paths are not
+    /// enum variants (it may be a path to static variable).
+    fn literal(text: &str) -> Option<Literal> {
+        Literal::from_text(text).filter(|literal| !matches!(literal, Literal::EnumVariant(_)))
+    }
+
+    /// Return true if brackets at `pos` enclose an expression,
+    /// false if brackets are part of select expression like `*(var.field)`.
+    fn is_group(tokens: &[Token], pos: usize) -> bool {
+        let mut depth = 0;
+        let mut prev = &Token::End;
+        for tok in &tokens[pos..] {
+            match tok {
+                Token::OpenBracket => depth += 1,
+                Token::ClosedBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return false;
+                    }
+                }
+                // multiplication
+                Token::Deref
+                    if matches!(
+                        prev,
+                        Token::Text(_) | Token::ClosedBracket | Token::ClosedSquareBracket
+                    ) =>
+                {
+                    return true
+                }
+                // literal or `as` operator, but not a field name, index or slice bound
+                Token::Text(text)
+                    if !matches!(
+                        prev,
+                        Token::Dot | Token::OpenSquareBracket | Token::DoubleDot
+                    ) && (text == "as" || Self::literal(text).is_some()) =>
+                {
+                    return true
+                }
+                Token::Deref
+                | Token::Text(_)
+                | Token::Dot
+                | Token::DoubleDot
+                | Token::OpenSquareBracket
+                | Token::ClosedSquareBracket => {}
+                _ => return true,
+            }
+            prev = tok;
+        }
+        true
+    }
+
+    /// Parse select expression at `pos`, expression ends at first token that
+    /// can't continue it (like binary operator).
+    fn parse_select(tokens: &[Token], pos: &mut usize) -> Result<SelectPlan, ParseError> {
+        let start = *pos;
+        let mut depth = 0;
+        while matches!(tokens[*pos], Token::Deref | Token::OpenBracket) {
+            if tokens[*pos] == Token::OpenBracket {
+                depth += 1;
+            }
+            *pos += 1;
+        }
+        match &tokens[*pos] {
+            Token::Text(_) => *pos += 1,
+            Token::End => return Err(ParseError::OperandNotFound("expression")),
+            tok => return Err(ParseError::UnexpectedToken(tok.clone())),
+        }
+        loop {
+            match tokens[*pos] {
+                Token::Dot => {
+                    *pos += 1;
+                    if matches!(tokens[*pos], Token::Text(_)) {
+                        *pos += 1;
+                    }
+                }
+                // index or slice, like `[1]` or `[..5]`
+                Token::OpenSquareBracket => {
+                    while !matches!(tokens[*pos], Token::ClosedSquareBracket | Token::End) {
+                        *pos += 1;
+                    }
+                    if tokens[*pos] == Token::ClosedSquareBracket {
+                        *pos += 1;
+                    }
+                }
+                Token::ClosedBracket if depth > 0 => {
+                    depth -= 1;
+                    *pos += 1;
+                }
+                _ => break,
+            }
+        }
+
+        let select_tokens = &tokens[start..*pos];
+        let source = select_tokens.iter().map(Token::as_source).collect();
+        let mut plan_tokens = select_tokens.to_vec();
+        plan_tokens.push(Token::End);
+        SelectPlanParser::parse_tokens(source, plan_tokens)
+    }
+}
+
+/// Breakpoint condition, a boolean expression (see [`ExprParser`]),
+/// like `counter > 10 && self.state == State::Ready`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Condition {
+    pub source: String,
+    pub expr: Expr,
+}
+
+impl Condition {
+    /// Evaluate condition, `read_variable` used for read a variable value by it select plan.
+    pub fn evaluate<F>(&self, read_variable: F) -> anyhow::Result<bool>
+    where
+        F: Fn(&SelectPlan) -> anyhow::Result<VariableIR>,
+    {
+        self.expr.value(&read_variable)?.as_bool()
+    }
+}
+
+/// Parse `Condition` from input string with [`ExprParser`].
+pub struct ConditionParser<'a> {
+    string: &'a str,
+}
+
+impl<'a> ConditionParser<'a> {
+    /// Create new `ConditionParser`.
+    pub fn new(string: &'a str) -> Self {
+        Self { string }
+    }
+
+    /// Parse `Condition` from input string.
+    pub fn parse(&self) -> Result<Condition, ParseError> {
+        Ok(Condition {
+            source: self.string.trim().to_string(),
+            expr: ExprParser::new(self.string).parse()?,
+        })
+    }
+}
+
+/// Argument of function call expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CallArgument {
+    /// Literal in source form (number, char, bool or enum variant).
+    Literal(String),
+    /// String literal, passed as `&str`.
+    Str(String),
+    /// Value selected by select expression.
+    Variable(SelectPlan),
+    /// Reference to value selected by select expression (`&var`).
+    Ref(SelectPlan),
+}
+
+/// Function called in call expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Callee {
+    /// Function name, may be qualified with namespaces (like `foo::bar`).
+    Function(String),
+    /// Method of value selected by `receiver` expression.
+    Method { receiver: SelectPlan, name: String },
+}
+
+/// Function call expression in the form of "{function}({argument}, ...)"
+/// or "{select expression}.{method}({argument}, ...)".
+#[derive(Debug, PartialEq, Clone)]
+pub struct Call {
+    pub source: String,
+    pub callee: Callee,
+    pub args: Vec<CallArgument>,
+}
+
+/// Parse `Call` from input string.
+pub struct CallParser<'a> {
+    string: &'a str,
+}
+
+impl<'a> CallParser<'a> {
+    /// Create new `CallParser`.
+    pub fn new(string: &'a str) -> Self {
+        Self { string }
+    }
+
+    /// Return true if input string looks like a call expression (ends with argument list
+    /// which follows a function or method name).
+    pub fn is_call(&self) -> bool {
+        let tokens = Tokenizer::new(self.string).tokenize();
+        matches!(
+            Self::arguments_start(&tokens),
+            Some(open) if open > 0 && matches!(tokens[open - 1], Token::Text(_))
+        )
+    }
+
+    /// Parse `Call` from input string.
+    pub fn parse(&self) -> Result<Call, ParseError> {
+        let tokens = Tokenizer::new(self.string).tokenize();
+        let open = Self::arguments_start(&tokens).ok_or(ParseError::MissOpenBracket)?;
+        let close = tokens.len() - 2;
+
+        let callee = match &tokens[..open] {
+            [Token::Text(name)] => Callee::Function(name.clone()),
+            [receiver @ .., Token::Dot, Token::Text(name)] if !receiver.is_empty() => {
+                Callee::Method {
+                    receiver: Self::select_plan(receiver)?,
+                    name: name.clone(),
+                }
+            }
+            [.., tok] => return Err(ParseError::UnexpectedToken(tok.clone())),
+            [] => return Err(ParseError::OperandNotFound("call")),
+        };
+
+        let mut args = vec![];
+        let arg_tokens = &tokens[open + 1..close];
+        if !arg_tokens.is_empty() {
+            let mut depth = 0;
+            let mut start = 0;
+            for (i, tok) in arg_tokens.iter().enumerate() {
+                match tok {
+                    Token::OpenBracket | Token::OpenSquareBracket => depth += 1,
+                    Token::ClosedBracket | Token::ClosedSquareBracket => depth -= 1,
+                    Token::Comma if depth == 0 => {
+                        args.push(Self::parse_argument(&arg_tokens[start..i])?);
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+            args.push(Self::parse_argument(&arg_tokens[start..])?);
+        }
+
+        Ok(Call {
+            source: self.string.trim().to_string(),
+            callee,
+            args,
+        })
+    }
+
+    /// Return position of open bracket of argument list, `tokens` must end with [`Token::End`].
+    fn arguments_start(tokens: &[Token]) -> Option<usize> {
+        let [.., Token::ClosedBracket, Token::End] = tokens else {
+            return None;
+        };
+        let mut depth = 0;
+        for i in (0..tokens.len() - 1).rev() {
+            match tokens[i] {
+                Token::ClosedBracket => depth += 1,
+                Token::OpenBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn parse_argument(tokens: &[Token]) -> Result<CallArgument, ParseError> {
+        match tokens {
+            [] => Err(ParseError::OperandNotFound("call argument")),
+            [Token::Str(string)] => Ok(CallArgument::Str(string.clone())),
+            [Token::Text(text)] if Literal::from_text(text).is_some() => {
+                Ok(CallArgument::Literal(text.clone()))
+            }
+            [Token::Ref, tokens @ ..] => Ok(CallArgument::Ref(Self::select_plan(tokens)?)),
+            _ => Ok(CallArgument::Variable(Self::select_plan(tokens)?)),
+        }
+    }
+
+    fn select_plan(tokens: &[Token]) -> Result<SelectPlan, ParseError> {
+        let source = tokens.iter().map(Token::as_source).collect();
+        let mut plan_tokens = tokens.to_vec();
+        plan_tokens.push(Token::End);
+        SelectPlanParser::parse_tokens(source, plan_tokens)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -463,6 +1285,24 @@ mod test {
                     Token::End,
                 ],
             },
+            TestCase {
+                string: "var1.0 >= 1.5 && !(s != \"a b\")",
+                tokens: vec![
+                    Token::Text("var1".into()),
+                    Token::Dot,
+                    Token::Text("0".into()),
+                    Token::Ge,
+                    Token::Text("1.5".into()),
+                    Token::And,
+                    Token::Not,
+                    Token::OpenBracket,
+                    Token::Text("s".into()),
+                    Token::Ne,
+                    Token::Str("a b".into()),
+                    Token::ClosedBracket,
+                    Token::End,
+                ],
+            },
         ];
 
         for tc in test_cases {
@@ -588,4 +1428,326 @@ mod test {
             assert_eq!(result, tc.out);
         }
     }
+
+    #[test]
+    fn test_condition_parser() {
+        struct TestCase {
+            string: &'static str,
+            out: Result<Expr, ParseError>,
+        }
+        let var = |name: &str| Expr::Select(SelectPlan::select_variable(name));
+        let binary = |lhs: Expr, op: BinaryOperator, rhs: Expr| {
+            Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+        };
+        let compare = |lhs: Expr, op: CompareOperator, rhs: Expr| {
+            binary(lhs, BinaryOperator::Compare(op), rhs)
+        };
+        let test_cases = vec![
+            TestCase {
+                string: "counter > 10",
+                out: Ok(compare(
+                    var("counter"),
+                    CompareOperator::Gt,
+                    Expr::Literal(Literal::Int(10)),
+                )),
+            },
+            TestCase {
+                string: "counter > 10 && self.state == State::Ready",
+                out: Ok(binary(
+                    compare(
+                        var("counter"),
+                        CompareOperator::Gt,
+                        Expr::Literal(Literal::Int(10)),
+                    ),
+                    BinaryOperator::And,
+                    compare(
+                        Expr::Select(SelectPlan {
+                            source: "self.state".to_string(),
+                            plan: VecDeque::from(vec![
+                                Operation::FindVariable("self".to_string()),
+                                Operation::GetField("state".to_string()),
+                            ]),
+                        }),
+                        CompareOperator::Eq,
+                        var("State::Ready"),
+                    ),
+                )),
+            },
+            TestCase {
+                string: "a || b && !c",
+                out: Ok(binary(
+                    var("a"),
+                    BinaryOperator::Or,
+                    binary(var("b"), BinaryOperator::And, Expr::Not(Box::new(var("c")))),
+                )),
+            },
+            TestCase {
+                string: "(a || b) && *(ptr) <= 1.5",
+                out: Ok(binary(
+                    binary(var("a"), BinaryOperator::Or, var("b")),
+                    BinaryOperator::And,
+                    compare(
+                        Expr::Select(SelectPlan {
+                            source: "*(ptr)".to_string(),
+                            plan: VecDeque::from(vec![
+                                Operation::FindVariable("ptr".to_string()),
+                                Operation::Deref,
+                            ]),
+                        }),
+                        CompareOperator::Le,
+                        Expr::Literal(Literal::Float(1.5)),
+                    ),
+                )),
+            },
+            TestCase {
+                string: "name == \"bob\"",
+                out: Ok(compare(
+                    var("name"),
+                    CompareOperator::Eq,
+                    Expr::Literal(Literal::String("bob".to_string())),
+                )),
+            },
+            TestCase {
+                string: "c == 'a' || n == 0xff",
+                out: Ok(binary(
+                    compare(
+                        var("c"),
+                        CompareOperator::Eq,
+                        Expr::Literal(Literal::Char('a')),
+                    ),
+                    BinaryOperator::Or,
+                    compare(
+                        var("n"),
+                        CompareOperator::Eq,
+                        Expr::Literal(Literal::Int(255)),
+                    ),
+                )),
+            },
+            TestCase {
+                string: "a ==",
+                out: Err(ParseError::OperandNotFound("expression")),
+            },
+            TestCase {
+                string: "(a == 1",
+                out: Err(ParseError::MissClosedBracket),
+            },
+            TestCase {
+                string: "a == 1)",
+                out: Err(ParseError::UnexpectedToken(Token::ClosedBracket)),
+            },
+        ];
+
+        for tc in test_cases {
+            let parser = ConditionParser::new(tc.string);
+            let result = parser.parse().map(|cond| cond.expr);
+
+            assert_eq!(result, tc.out);
+        }
+    }
+
+    #[test]
+    fn test_call_parser() {
+        struct TestCase {
+            string: &'static str,
+            out: Result<Call, ParseError>,
+        }
+        let plan = |source: &str, plan: Vec<Operation>| SelectPlan {
+            source: source.to_string(),
+            plan: VecDeque::from(plan),
+        };
+        let test_cases = vec![
+            TestCase {
+                string: "foo::bar(1, &x)",
+                out: Ok(Call {
+                    source: "foo::bar(1, &x)".to_string(),
+                    callee: Callee::Function("foo::bar".to_string()),
+                    args: vec![
+                        CallArgument::Literal("1".to_string()),
+                        CallArgument::Ref(SelectPlan::select_variable("x")),
+                    ],
+                }),
+            },
+            TestCase {
+                string: "v.len()",
+                out: Ok(Call {
+                    source: "v.len()".to_string(),
+                    callee: Callee::Method {
+                        receiver: SelectPlan::select_variable("v"),
+                        name: "len".to_string(),
+                    },
+                    args: vec![],
+                }),
+            },
+            TestCase {
+                string: "(*a.b).get(a.c[1], \"str\", 'c', -1.5)",
+                out: Ok(Call {
+                    source: "(*a.b).get(a.c[1], \"str\", 'c', -1.5)".to_string(),
+                    callee: Callee::Method {
+                        receiver: plan(
+                            "(*a.b)",
+                            vec![
+                                Operation::FindVariable("a".to_string()),
+                                Operation::GetField("b".to_string()),
+                                Operation::Deref,
+                            ],
+                        ),
+                        name: "get".to_string(),
+                    },
+                    args: vec![
+                        CallArgument::Variable(plan(
+                            "a.c[1]",
+                            vec![
+                                Operation::FindVariable("a".to_string()),
+                                Operation::GetField("c".to_string()),
+                                Operation::GetByIndex(1),
+                            ],
+                        )),
+                        CallArgument::Str("str".to_string()),
+                        CallArgument::Literal("'c'".to_string()),
+                        CallArgument::Literal("-1.5".to_string()),
+                    ],
+                }),
+            },
+            TestCase {
+                string: "foo(1,)",
+                out: Err(ParseError::OperandNotFound("call argument")),
+            },
+            TestCase {
+                string: "foo(1",
+                out: Err(ParseError::MissOpenBracket),
+            },
+        ];
+
+        for tc in test_cases {
+            assert_eq!(CallParser::new(tc.string).parse(), tc.out);
+        }
+        assert!(CallParser::new("foo()").is_call());
+        assert!(!CallParser::new("*(foo.bar)").is_call());
+        assert!(!CallParser::new("foo.bar").is_call());
+    }
+
+    #[test]
+    fn test_expr_parser() {
+        struct TestCase {
+            string: &'static str,
+            out: Result<Expr, ParseError>,
+        }
+        let select = |source: &str, plan: Vec<Operation>| {
+            Expr::Select(SelectPlan {
+                source: source.to_string(),
+                plan: VecDeque::from(plan),
+            })
+        };
+        let binary = |lhs: Expr, op: BinaryOperator, rhs: Expr| {
+            Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+        };
+        let int = |int: i128| Expr::Literal(Literal::Int(int));
+        let a_len = || {
+            select(
+                "a.len",
+                vec![
+                    Operation::FindVariable("a".to_string()),
+                    Operation::GetField("len".to_string()),
+                ],
+            )
+        };
+        let test_cases = vec![
+            TestCase {
+                string: "a.len * 2 + 1",
+                out: Ok(binary(
+                    binary(a_len(), BinaryOperator::Mul, int(2)),
+                    BinaryOperator::Add,
+                    int(1),
+                )),
+            },
+            TestCase {
+                string: "*ptr * (a.len - -1) >> 2",
+                out: Ok(binary(
+                    binary(
+                        select(
+                            "*ptr",
+                            vec![Operation::FindVariable("ptr".to_string()), Operation::Deref],
+                        ),
+                        BinaryOperator::Mul,
+                        binary(a_len(), BinaryOperator::Sub, int(-1)),
+                    ),
+                    BinaryOperator::Shr,
+                    int(2),
+                )),
+            },
+            TestCase {
+                string: "-x as u8 == 'a' as u8 || b & 1 != 0",
+                out: Ok(binary(
+                    binary(
+                        Expr::Cast(
+                            Box::new(Expr::Neg(Box::new(select(
+                                "x",
+                                vec![Operation::FindVariable("x".to_string())],
+                            )))),
+                            ScalarType::U8,
+                        ),
+                        BinaryOperator::Compare(CompareOperator::Eq),
+                        Expr::Cast(Box::new(Expr::Literal(Literal::Char('a'))), ScalarType::U8),
+                    ),
+                    BinaryOperator::Or,
+                    binary(
+                        binary(
+                            select("b", vec![Operation::FindVariable("b".to_string())]),
+                            BinaryOperator::BitAnd,
+                            int(1),
+                        ),
+                        BinaryOperator::Compare(CompareOperator::Ne),
+                        int(0),
+                    ),
+                )),
+            },
+            TestCase {
+                string: "&(*a).b[1] - 8",
+                out: Ok(binary(
+                    Expr::AddressOf(SelectPlan {
+                        source: "(*a).b[1]".to_string(),
+                        plan: VecDeque::from(vec![
+                            Operation::FindVariable("a".to_string()),
+                            Operation::Deref,
+                            Operation::GetField("b".to_string()),
+                            Operation::GetByIndex(1),
+                        ]),
+                    }),
+                    BinaryOperator::Sub,
+                    int(8),
+                )),
+            },
+            TestCase {
+                string: "crate::STATIC % 3",
+                out: Ok(binary(
+                    select(
+                        "crate::STATIC",
+                        vec![Operation::FindVariable("crate::STATIC".to_string())],
+                    ),
+                    BinaryOperator::Rem,
+                    int(3),
+                )),
+            },
+            TestCase {
+                string: "a.len",
+                out: Ok(a_len()),
+            },
+            TestCase {
+                string: "a +",
+                out: Err(ParseError::OperandNotFound("expression")),
+            },
+            TestCase {
+                string: "(a + 1",
+                out: Err(ParseError::MissClosedBracket),
+            },
+            TestCase {
+                string: "a as string",
+                out: Err(ParseError::InvalidOperand("string".to_string())),
+            },
+        ];
+
+        for tc in test_cases {
+            assert_eq!(ExprParser::new(tc.string).parse(), tc.out);
+        }
+    }
 }
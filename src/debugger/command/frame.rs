@@ -1,15 +1,60 @@
+use crate::debugger::command::CommandError;
 use crate::debugger::{command, Debugger, FrameInfo};
 
+enum SubCommand {
+    Info,
+    Up(usize),
+    Down(usize),
+    Select(usize),
+}
+
 pub struct Frame<'a> {
     dbg: &'a Debugger,
+    sub_cmd: SubCommand,
 }
 
 impl<'a> Frame<'a> {
-    pub fn new(debugger: &'a Debugger) -> Self {
-        Self { dbg: debugger }
+    /// Create command from arguments in form:
+    /// `frame`, `frame {number}`, `up [count]` or `down [count]`.
+    pub fn new(debugger: &'a Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 1)?;
+
+        let arg = args.get(1).filter(|arg| !arg.is_empty());
+        let parse_num = |arg: &str| -> command::Result<usize> {
+            arg.parse().map_err(|_| {
+                CommandError::InvalidArgumentsEx(format!("invalid frame number {arg}"))
+            })
+        };
+        let count = arg.map(|arg| parse_num(arg)).transpose()?.unwrap_or(1);
+
+        let sub_cmd = match args[0].to_lowercase().as_str() {
+            "up" => SubCommand::Up(count),
+            "down" => SubCommand::Down(count),
+            _ => match arg {
+                None => SubCommand::Info,
+                Some(num) => SubCommand::Select(parse_num(num)?),
+            },
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            sub_cmd,
+        })
     }
 
+    /// Return information about frame in focus (after selection if frame is changed).
     pub fn run(&self) -> command::Result<FrameInfo> {
-        Ok(self.dbg.frame_info(self.dbg.debugee.thread_in_focus())?)
+        let current = self.dbg.frame_num_in_focus();
+        let num = match self.sub_cmd {
+            SubCommand::Info => return Ok(self.dbg.frame_in_focus_info()?),
+            SubCommand::Up(count) => current + count,
+            SubCommand::Down(count) => current.checked_sub(count).ok_or_else(|| {
+                CommandError::InvalidArgumentsEx(
+                    "bottom (innermost) frame selected, you cannot go down".to_string(),
+                )
+            })?,
+            SubCommand::Select(num) => num,
+        };
+        Ok(self.dbg.set_frame_into_focus(num)?)
     }
 }
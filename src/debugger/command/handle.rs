@@ -0,0 +1,69 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger, SignalAction};
+use nix::sys::signal::Signal;
+
+pub struct Handle<'a> {
+    dbg: &'a mut Debugger,
+    pub signal: Signal,
+    keywords: Vec<String>,
+}
+
+impl<'a> Handle<'a> {
+    /// Create handle command from arguments in form:
+    /// `handle {signal} [stop|nostop] [print|noprint] [pass|nopass]`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+        let signal = command::helper::parse_signal(args[1])?;
+
+        let keywords = args[2..]
+            .iter()
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| arg.to_lowercase())
+            .collect::<Vec<_>>();
+        if let Some(unknown) = keywords.iter().find(|kw| {
+            !matches!(
+                kw.as_str(),
+                "stop" | "nostop" | "print" | "noprint" | "pass" | "nopass"
+            )
+        }) {
+            return Err(CommandError::InvalidArgumentsEx(format!(
+                "unknown action {unknown}"
+            )));
+        }
+
+        Ok(Self {
+            dbg: debugger,
+            signal,
+            keywords,
+        })
+    }
+
+    /// Update signal action, return resulting action.
+    /// Stop implies print, and noprint implies nostop.
+    pub fn run(self) -> command::Result<SignalAction> {
+        let mut action = self.dbg.signal_action(self.signal);
+        if self.keywords.is_empty() {
+            return Ok(action);
+        }
+
+        for keyword in &self.keywords {
+            match keyword.as_str() {
+                "stop" => {
+                    action.stop = true;
+                    action.print = true;
+                }
+                "nostop" => action.stop = false,
+                "print" => action.print = true,
+                "noprint" => {
+                    action.print = false;
+                    action.stop = false;
+                }
+                "pass" => action.pass = true,
+                "nopass" => action.pass = false,
+                _ => unreachable!("keywords validated at command creation"),
+            }
+        }
+        self.dbg.set_signal_action(self.signal, action)?;
+        Ok(action)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger};
+use nix::unistd::Pid;
+
+pub struct Inferiors<'a> {
+    dbg: &'a mut Debugger,
+    pid: Option<Pid>,
+}
+
+impl<'a> Inferiors<'a> {
+    /// Create command from arguments in form: `inferior [pid]`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        let pid = args
+            .get(1)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| {
+                arg.parse::<i32>().map(Pid::from_raw).map_err(|_| {
+                    CommandError::InvalidArgumentsEx(format!("invalid process id {arg}"))
+                })
+            })
+            .transpose()?;
+        Ok(Self { dbg: debugger, pid })
+    }
+
+    /// Switch to process if its id given, return ids of traced processes,
+    /// process in focus goes first.
+    pub fn run(self) -> command::Result<Vec<Pid>> {
+        if let Some(pid) = self.pid {
+            self.dbg.switch_inferior(pid)?;
+        }
+        Ok(self.dbg.inferiors())
+    }
+}
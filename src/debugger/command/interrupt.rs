@@ -0,0 +1,15 @@
+use crate::debugger::{command, Debugger};
+
+pub struct Interrupt<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Interrupt<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn run(&mut self) -> command::Result<()> {
+        Ok(self.dbg.interrupt()?)
+    }
+}
@@ -1,36 +1,65 @@
+mod advance;
 mod arguments;
 mod backtrace;
 mod r#break;
+mod breakpoints;
+mod call;
+mod catch;
+pub mod checkpoint;
 mod r#continue;
+mod examine;
 pub mod expression;
 mod frame;
+mod handle;
+mod inferior;
+mod interrupt;
 mod memory;
+mod print;
 mod register;
 mod run;
+mod set;
+mod signal;
 mod step_instruction;
 mod step_into;
 mod step_out;
 mod step_over;
 mod symbol;
+pub mod thread;
 mod trace;
+mod until;
 pub mod variables;
+mod watch;
 
+pub use advance::Advance;
 pub use arguments::Arguments;
 pub use backtrace::Backtrace;
+pub use breakpoints::{BreakpointList, Breakpoints};
+pub use call::Call;
+pub use catch::{Catch, CatchEvent};
+pub use checkpoint::Checkpoint;
+pub use examine::{Examine, ExamineFormat, ExamineLine};
 pub use frame::Frame;
+pub use handle::Handle;
+pub use inferior::Inferiors;
+pub use interrupt::Interrupt;
 pub use memory::Memory;
+pub use print::Print;
 pub use r#break::Break;
-pub use r#break::Breakpoint as BreakpointType;
 pub use r#continue::Continue;
 pub use register::Register;
 pub use run::Run;
+pub use set::{Set, Setting};
+pub use signal::ContinueWithSignal;
 pub use step_instruction::StepI;
 pub use step_into::StepInto;
 pub use step_out::StepOut;
 pub use step_over::StepOver;
 pub use symbol::Symbol;
+pub use thread::Thread;
 pub use trace::Trace;
+pub use until::Until;
 pub use variables::Variables;
+pub use watch::Watch;
 
 #[derive(thiserror::Error, Debug)]
 pub enum CommandError {
@@ -49,6 +78,9 @@ pub type Result<T> = std::result::Result<T, CommandError>;
 pub mod helper {
     use crate::debugger::command;
     use crate::debugger::command::CommandError;
+    use crate::debugger::BreakpointSpec;
+    use nix::sys::signal::Signal;
+    use std::str::FromStr;
 
     pub fn check_args_count(args: &Vec<&str>, min_expected_count: usize) -> command::Result<()> {
         if args.len() < min_expected_count {
@@ -56,4 +88,34 @@ pub mod helper {
         }
         Ok(())
     }
+
+    /// Parse signal from its name (`SIGUSR1` or `USR1`) or number.
+    pub fn parse_signal(arg: &str) -> command::Result<Signal> {
+        let signal = match arg.parse::<i32>() {
+            Ok(number) => Signal::try_from(number),
+            Err(_) if arg.to_uppercase().starts_with("SIG") => {
+                Signal::from_str(&arg.to_uppercase())
+            }
+            Err(_) => Signal::from_str(&format!("SIG{}", arg.to_uppercase())),
+        };
+        signal.map_err(|_| CommandError::InvalidArgumentsEx(format!("unknown signal {arg}")))
+    }
+
+    /// Parse code location: address (`0x...`), source line (`file:line`) or function name.
+    pub fn parse_location(place: &str) -> command::Result<BreakpointSpec> {
+        if let Some(addr) = place.strip_prefix("0x") {
+            let addr = usize::from_str_radix(addr, 16)
+                .map_err(|e| CommandError::InvalidArgumentsEx(e.to_string()))?;
+            return Ok(BreakpointSpec::Address(addr));
+        }
+        if place.find(':').is_some() && !place.contains("::") {
+            let args = place.split(':').collect::<Vec<_>>();
+            return Ok(BreakpointSpec::Line(
+                args[0].to_string(),
+                u64::from_str(args[1])
+                    .map_err(|e| CommandError::InvalidArgumentsEx(e.to_string()))?,
+            ));
+        }
+        Ok(BreakpointSpec::Function(place.to_string()))
+    }
 }
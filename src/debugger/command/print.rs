@@ -0,0 +1,69 @@
+use crate::debugger::command::{CommandError, Variables};
+use crate::debugger::variable::render::{Format, RenderOptions};
+use crate::debugger::variable::VariableIR;
+use crate::debugger::{command, Debugger};
+
+/// Print expression value, command in the form of
+/// "print[/{format}] [-max-depth {N}] [-elements {N}] [-pretty on|off] [--] {expression}".
+pub struct Print<'a> {
+    variables: Variables<'a>,
+    pub options: RenderOptions,
+}
+
+impl<'a> Print<'a> {
+    pub fn new(debugger: &'a Debugger, args: Vec<&'a str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 1)?;
+
+        let mut options = RenderOptions::default();
+        if let Some((_, format)) = args[0].split_once('/') {
+            let mut letters = format.chars();
+            options.format = match (letters.next(), letters.next()) {
+                (Some(letter), None) => Format::from_letter(letter),
+                _ => None,
+            }
+            .ok_or_else(|| CommandError::InvalidArgumentsEx(format!("unknown format {format}")))?;
+        }
+
+        let mut args = args.into_iter().filter(|arg| !arg.is_empty());
+        let mut expr = vec![args.next().expect("checked above")];
+        while let Some(arg) = args.next() {
+            let mut number = || -> command::Result<usize> {
+                let value = args.next().unwrap_or_default();
+                value.parse().map_err(|_| {
+                    CommandError::InvalidArgumentsEx(format!("expect number after {arg}"))
+                })
+            };
+            match arg {
+                "-max-depth" => options.max_depth = Some(number()?),
+                "-elements" => options.max_elements = Some(number()?),
+                "-pretty" => {
+                    options.pretty = match args.next() {
+                        Some("on") => true,
+                        Some("off") => false,
+                        _ => {
+                            return Err(CommandError::InvalidArgumentsEx(
+                                "expect on or off after -pretty".to_string(),
+                            ))
+                        }
+                    }
+                }
+                "--" => {
+                    expr.extend(args.by_ref());
+                }
+                _ => {
+                    expr.push(arg);
+                    expr.extend(args.by_ref());
+                }
+            }
+        }
+
+        Ok(Self {
+            variables: Variables::new(debugger, expr)?,
+            options,
+        })
+    }
+
+    pub fn run(self) -> command::Result<Vec<VariableIR>> {
+        self.variables.run()
+    }
+}
@@ -0,0 +1,88 @@
+use crate::debugger::command::expression::{SelectPlan, SelectPlanParser};
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger, FollowForkMode, SchedulerLocking};
+
+/// Debugger setting or debugee variable changed by `set` command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Setting {
+    FollowForkMode(FollowForkMode),
+    DetachOnFork(bool),
+    SchedulerLocking(SchedulerLocking),
+    NonStop(bool),
+    /// Variable (or its part) and a new value of it.
+    Variable(SelectPlan, String),
+}
+
+pub struct Set<'a> {
+    dbg: &'a mut Debugger,
+    pub setting: Setting,
+}
+
+impl<'a> Set<'a> {
+    /// Create command from arguments in form:
+    /// `set follow-fork-mode parent|child`, `set detach-on-fork on|off`,
+    /// `set scheduler-locking on|step|off`, `set non-stop on|off`
+    /// or `set var {select expression} = {value}`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 3)?;
+        if matches!(args[1].to_lowercase().as_str(), "var" | "variable") {
+            let assignment = args[2..].join(" ");
+            let (path, value) = assignment
+                .split_once('=')
+                .map(|(path, value)| (path.trim(), value.trim()))
+                .filter(|(path, value)| !path.is_empty() && !value.is_empty())
+                .ok_or_else(|| {
+                    CommandError::InvalidArgumentsEx(format!(
+                        "expect assignment in form `{{variable}} = {{value}}`, got {assignment}"
+                    ))
+                })?;
+            let plan = SelectPlanParser::new(path).parse()?;
+            return Ok(Self {
+                dbg: debugger,
+                setting: Setting::Variable(plan, value.to_string()),
+            });
+        }
+
+        let value = args[2].to_lowercase();
+        let setting = match (args[1].to_lowercase().as_str(), value.as_str()) {
+            ("follow-fork-mode", "parent") => Setting::FollowForkMode(FollowForkMode::Parent),
+            ("follow-fork-mode", "child") => Setting::FollowForkMode(FollowForkMode::Child),
+            ("detach-on-fork", "on") => Setting::DetachOnFork(true),
+            ("detach-on-fork", "off") => Setting::DetachOnFork(false),
+            ("scheduler-locking", "on") => Setting::SchedulerLocking(SchedulerLocking::On),
+            ("scheduler-locking", "step") => Setting::SchedulerLocking(SchedulerLocking::Step),
+            ("scheduler-locking", "off") => Setting::SchedulerLocking(SchedulerLocking::Off),
+            ("non-stop", "on") => Setting::NonStop(true),
+            ("non-stop", "off") => Setting::NonStop(false),
+            (
+                name @ ("follow-fork-mode" | "detach-on-fork" | "scheduler-locking" | "non-stop"),
+                _,
+            ) => {
+                return Err(CommandError::InvalidArgumentsEx(format!(
+                    "invalid value {value} for {name}"
+                )))
+            }
+            (name, _) => {
+                return Err(CommandError::InvalidArgumentsEx(format!(
+                    "unknown setting {name}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            setting,
+        })
+    }
+
+    pub fn run(self) -> command::Result<()> {
+        match self.setting {
+            Setting::FollowForkMode(mode) => self.dbg.set_follow_fork_mode(mode),
+            Setting::DetachOnFork(detach) => self.dbg.set_detach_on_fork(detach),
+            Setting::SchedulerLocking(mode) => self.dbg.set_scheduler_locking(mode),
+            Setting::NonStop(non_stop) => self.dbg.set_non_stop(non_stop)?,
+            Setting::Variable(plan, value) => self.dbg.set_variable(plan, &value)?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::debugger::{command, Debugger};
+use nix::sys::signal::Signal;
+
+pub struct ContinueWithSignal<'a> {
+    dbg: &'a mut Debugger,
+    signal: Option<Signal>,
+}
+
+impl<'a> ContinueWithSignal<'a> {
+    /// Create command from arguments in form: `signal {signal}`,
+    /// `signal 0` continues debugee without signal.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+        let signal = match args[1] {
+            "0" => None,
+            arg => Some(command::helper::parse_signal(arg)?),
+        };
+        Ok(Self {
+            dbg: debugger,
+            signal,
+        })
+    }
+
+    pub fn run(self) -> command::Result<()> {
+        Ok(self.dbg.continue_with_signal(self.signal)?)
+    }
+}
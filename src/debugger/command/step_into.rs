@@ -2,15 +2,15 @@ use crate::debugger::{command, Debugger};
 
 /// Step program until it reaches a different source line.
 pub struct StepInto<'a> {
-    dbg: &'a Debugger,
+    dbg: &'a mut Debugger,
 }
 
 impl<'a> StepInto<'a> {
-    pub fn new(debugger: &'a Debugger) -> Self {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
         Self { dbg: debugger }
     }
 
-    pub fn run(&self) -> command::Result<()> {
+    pub fn run(&mut self) -> command::Result<()> {
         Ok(self.dbg.step_into()?)
     }
 }
@@ -1,3 +1,4 @@
+use crate::debugger::variable::VariableIR;
 use crate::debugger::{command, Debugger};
 
 // Execute until selected stack frame returns
@@ -10,7 +11,8 @@ impl<'a> StepOut<'a> {
         Self { dbg: debugger }
     }
 
-    pub fn run(&mut self) -> command::Result<()> {
+    /// Return value returned by function if it is known.
+    pub fn run(&mut self) -> command::Result<Option<VariableIR>> {
         Ok(self.dbg.step_out()?)
     }
 }
@@ -0,0 +1,74 @@
+use crate::debugger::command::CommandError;
+use crate::debugger::{command, Debugger, ThreadDump};
+use nix::unistd::Pid;
+
+enum SubCommand {
+    List,
+    Switch(u32),
+    ApplyAllBacktrace,
+}
+
+pub enum Response {
+    /// Debugee threads sorted by thread number.
+    List(Vec<ThreadDump>),
+    /// Number and id of thread in focus.
+    Switched(u32, Pid),
+    /// Debugee threads with backtraces sorted by thread number.
+    Backtraces(Vec<ThreadDump>),
+}
+
+pub struct Thread<'a> {
+    dbg: &'a mut Debugger,
+    sub_cmd: SubCommand,
+}
+
+impl<'a> Thread<'a> {
+    /// Create command from arguments in form:
+    /// `thread list`, `thread {number}` or `thread apply all bt`.
+    pub fn new(debugger: &'a mut Debugger, args: Vec<&str>) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+
+        let sub_cmd = match args[1].to_lowercase().as_str() {
+            "list" | "info" => SubCommand::List,
+            "apply" => {
+                let target = args[2..]
+                    .iter()
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| arg.to_lowercase())
+                    .collect::<Vec<_>>();
+                if !matches!(&target[..], [all, bt] if all == "all" && (bt == "bt" || bt == "backtrace"))
+                {
+                    return Err(CommandError::InvalidArgumentsEx(
+                        "only `thread apply all bt` supported".to_string(),
+                    ));
+                }
+                SubCommand::ApplyAllBacktrace
+            }
+            num => SubCommand::Switch(num.parse().map_err(|_| {
+                CommandError::InvalidArgumentsEx(format!("invalid thread number {num}"))
+            })?),
+        };
+
+        Ok(Self {
+            dbg: debugger,
+            sub_cmd,
+        })
+    }
+
+    pub fn run(self) -> command::Result<Response> {
+        match self.sub_cmd {
+            SubCommand::List => Ok(Response::List(self.sorted_threads()?)),
+            SubCommand::Switch(num) => {
+                let tid = self.dbg.set_thread_into_focus(num)?;
+                Ok(Response::Switched(num, tid))
+            }
+            SubCommand::ApplyAllBacktrace => Ok(Response::Backtraces(self.sorted_threads()?)),
+        }
+    }
+
+    fn sorted_threads(&self) -> anyhow::Result<Vec<ThreadDump>> {
+        let mut dump = self.dbg.thread_state()?;
+        dump.sort_unstable_by_key(|dump| dump.thread.number);
+        Ok(dump)
+    }
+}
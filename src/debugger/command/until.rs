@@ -0,0 +1,17 @@
+use crate::debugger::{command, Debugger};
+
+/// Continue until a source line past the current line, in the current stack frame,
+/// is reached. Useful to avoid single stepping through a loop more than once.
+pub struct Until<'a> {
+    dbg: &'a mut Debugger,
+}
+
+impl<'a> Until<'a> {
+    pub fn new(debugger: &'a mut Debugger) -> Self {
+        Self { dbg: debugger }
+    }
+
+    pub fn run(&mut self) -> command::Result<()> {
+        Ok(self.dbg.until()?)
+    }
+}
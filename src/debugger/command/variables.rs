@@ -1,39 +1,69 @@
-use crate::debugger::command::expression::{SelectPlan, SelectPlanParser};
+use crate::debugger::command::expression::{Call, CallParser, Expr, ExprParser, SelectPlan};
 use crate::debugger::command::CommandError::ParseArgument;
 use crate::debugger::variable::VariableIR;
 use crate::debugger::{command, Debugger};
 
+enum Expression {
+    Select(SelectPlan),
+    /// Method or function call, like `v.len()`.
+    Call(Call),
+    /// Expression with operators or literals, like `a.len * 2 + 1`.
+    Evaluate {
+        source: String,
+        expr: Expr,
+    },
+}
+
 pub struct Variables<'a> {
     dbg: &'a Debugger,
-    path: Option<SelectPlan>,
+    expr: Option<Expression>,
 }
 
 impl<'a> Variables<'a> {
     pub fn new(debugger: &'a Debugger, args: Vec<&'a str>) -> command::Result<Self> {
         command::helper::check_args_count(&args, 1)?;
+        let expr = (args.len() > 1)
+            .then(|| {
+                let string = args[1..].join(" ");
+                let call_parser = CallParser::new(&string);
+                if call_parser.is_call() {
+                    return call_parser.parse().map(Expression::Call);
+                }
+                ExprParser::new(&string).parse().map(|expr| match expr {
+                    Expr::Select(mut plan) => {
+                        plan.source = string.trim().to_string();
+                        Expression::Select(plan)
+                    }
+                    expr => Expression::Evaluate {
+                        source: string.trim().to_string(),
+                        expr,
+                    },
+                })
+            })
+            .transpose()
+            .map_err(ParseArgument)?;
+
         Ok(Self {
             dbg: debugger,
-            path: args
-                .get(1)
-                .map(|s| {
-                    let parser = SelectPlanParser::new(s);
-                    parser.parse().map_err(ParseArgument)
-                })
-                .transpose()?,
+            expr,
         })
     }
 
     pub fn new_locals(debugger: &'a Debugger) -> Self {
         Self {
             dbg: debugger,
-            path: None,
+            expr: None,
         }
     }
 
     pub fn run(self) -> command::Result<Vec<VariableIR>> {
-        match self.path {
+        match self.expr {
             None => Ok(self.dbg.read_local_variables()?),
-            Some(expr) => Ok(self.dbg.read_variable(expr)?),
+            Some(Expression::Select(expr)) => Ok(self.dbg.read_variable(expr)?),
+            Some(Expression::Call(call)) => Ok(self.dbg.call_function(call)?.into_iter().collect()),
+            Some(Expression::Evaluate { source, expr }) => {
+                Ok(vec![self.dbg.evaluate_expression(&expr, &source)?])
+            }
         }
     }
 }
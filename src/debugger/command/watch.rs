@@ -0,0 +1,35 @@
+use crate::debugger::command::expression::{SelectPlan, SelectPlanParser};
+use crate::debugger::command::CommandError::ParseArgument;
+use crate::debugger::{command, Debugger, WatchpointKind};
+
+pub struct Watch<'a> {
+    dbg: &'a mut Debugger,
+    expr: SelectPlan,
+    kind: WatchpointKind,
+}
+
+impl<'a> Watch<'a> {
+    /// Create watchpoint command from arguments in form: `watch|rwatch|awatch {expression}`.
+    pub fn new(
+        debugger: &'a mut Debugger,
+        args: Vec<&str>,
+        kind: WatchpointKind,
+    ) -> command::Result<Self> {
+        command::helper::check_args_count(&args, 2)?;
+        let input = args[1..].join(" ");
+        let expr = SelectPlanParser::new(input.trim())
+            .parse()
+            .map_err(ParseArgument)?;
+
+        Ok(Self {
+            dbg: debugger,
+            expr,
+            kind,
+        })
+    }
+
+    /// Set watchpoint, return its number.
+    pub fn run(self) -> command::Result<u32> {
+        Ok(self.dbg.set_watchpoint(self.expr, self.kind)?)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::debugger::address::RelocatedAddress;
+use anyhow::bail;
+use nix::libc::c_void;
+use nix::sys;
+use nix::unistd::Pid;
+
+/// Offset of `u_debugreg` field in `struct user` (see sys/user.h).
+const DEBUG_REG_OFFSET: usize = 848;
+/// Count of address registers (DR0-DR3).
+const SLOT_COUNT: usize = 4;
+/// Index of debug status register.
+const DR6: usize = 6;
+/// Index of debug control register.
+const DR7: usize = 7;
+
+/// Condition on which hardware breakpoint is triggered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakCondition {
+    /// Break on data writes.
+    Write,
+    /// Break on data reads or writes.
+    ReadWrite,
+}
+
+impl BreakCondition {
+    /// RW bits value for DR7.
+    fn bits(self) -> u64 {
+        match self {
+            BreakCondition::Write => 0b01,
+            BreakCondition::ReadWrite => 0b11,
+        }
+    }
+}
+
+/// Hardware breakpoint programmed into one of DR0-DR3 registers.
+#[derive(Clone, Copy, Debug)]
+pub struct HardwareBreakpoint {
+    pub addr: RelocatedAddress,
+    /// Watched memory size, one of 1, 2, 4 or 8 bytes.
+    pub size: u8,
+    pub condition: BreakCondition,
+}
+
+impl HardwareBreakpoint {
+    /// LEN bits value for DR7.
+    fn len_bits(&self) -> u64 {
+        match self.size {
+            1 => 0b00,
+            2 => 0b01,
+            8 => 0b10,
+            _ => 0b11,
+        }
+    }
+}
+
+/// State of x86-64 debug registers, same for all debugee threads.
+#[derive(Default)]
+pub struct DebugRegisters {
+    slots: [Option<HardwareBreakpoint>; SLOT_COUNT],
+}
+
+impl DebugRegisters {
+    /// Occupy free debug register slot, return slot index.
+    /// Memory region must be aligned to its size (1, 2, 4 or 8 bytes).
+    pub fn occupy(&mut self, brkpt: HardwareBreakpoint) -> anyhow::Result<usize> {
+        if !matches!(brkpt.size, 1 | 2 | 4 | 8) {
+            bail!("unsupported watch size: {} bytes", brkpt.size);
+        }
+        if usize::from(brkpt.addr) % brkpt.size as usize != 0 {
+            bail!("watched address {} is unaligned", brkpt.addr);
+        }
+
+        let (slot, place) = self
+            .slots
+            .iter_mut()
+            .enumerate()
+            .find(|(_, slot)| slot.is_none())
+            .ok_or_else(|| anyhow::anyhow!("no free hardware debug registers"))?;
+        *place = Some(brkpt);
+        Ok(slot)
+    }
+
+    /// Release debug register slot.
+    pub fn release(&mut self, slot: usize) {
+        if let Some(place) = self.slots.get_mut(slot) {
+            *place = None;
+        }
+    }
+
+    /// Return true if no hardware breakpoints set.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Return debug control register value for current state.
+    fn dr7(&self) -> u64 {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.map(|brkpt| (i, brkpt)))
+            .fold(0, |dr7, (i, brkpt)| {
+                dr7 | 1 << (i * 2)
+                    | brkpt.condition.bits() << (16 + i * 4)
+                    | brkpt.len_bits() << (18 + i * 4)
+            })
+    }
+
+    /// Write current state into thread debug registers.
+    pub fn apply(&self, tid: Pid) -> nix::Result<()> {
+        // disable all breakpoints before address changing
+        write_debug_reg(tid, DR7, 0)?;
+        for (i, slot) in self.slots.iter().enumerate() {
+            let addr = slot.map(|brkpt| usize::from(brkpt.addr)).unwrap_or(0);
+            write_debug_reg(tid, i, addr as u64)?;
+        }
+        write_debug_reg(tid, DR7, self.dr7())
+    }
+
+    /// Return index of slot which breakpoint was triggered in thread and reset debug status register.
+    pub fn take_triggered(tid: Pid) -> nix::Result<Option<usize>> {
+        let dr6 = read_debug_reg(tid, DR6)?;
+        write_debug_reg(tid, DR6, 0)?;
+        Ok((0..SLOT_COUNT).find(|i| dr6 & (1 << i) != 0))
+    }
+}
+
+fn read_debug_reg(tid: Pid, idx: usize) -> nix::Result<u64> {
+    let offset = DEBUG_REG_OFFSET + idx * 8;
+    sys::ptrace::read_user(tid, offset as *mut c_void).map(|v| v as u64)
+}
+
+fn write_debug_reg(tid: Pid, idx: usize, value: u64) -> nix::Result<()> {
+    let offset = DEBUG_REG_OFFSET + idx * 8;
+    unsafe { sys::ptrace::write_user(tid, offset as *mut c_void, value as *mut c_void) }
+}
@@ -0,0 +1,257 @@
+use crate::debugger::debugee::dwarf::{DebugeeContextBuilder, EndianRcSlice};
+use crate::weak_error;
+use anyhow::{anyhow, bail};
+use gimli::{
+    DW_AT_GNU_dwo_name, DW_AT_dwo_name, Dwarf, DwarfFileType, DwarfPackage, RangeLists, Reader,
+    RunTimeEndian, SectionId, Unit,
+};
+use log::{info, warn};
+use object::Object;
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+static DEBUG_DIRS: OnceCell<Vec<PathBuf>> = OnceCell::new();
+
+/// System-wide directory of separate debug information files.
+const DEFAULT_DEBUG_DIR: &str = "/usr/lib/debug";
+
+/// Set directories where separate debug information files are searched,
+/// these directories are checked before `/usr/lib/debug`.
+pub fn init_debug_dirs(dirs: Vec<PathBuf>) {
+    if DEBUG_DIRS.set(dirs).is_err() {
+        warn!("debug directories already set")
+    }
+}
+
+fn debug_dirs() -> impl Iterator<Item = &'static Path> {
+    DEBUG_DIRS
+        .get()
+        .into_iter()
+        .flatten()
+        .map(PathBuf::as_path)
+        .chain(iter::once(Path::new(DEFAULT_DEBUG_DIR)))
+}
+
+/// Return path of file that contains debug information for object file `obj` located at `obj_path`.
+/// File searched by build-id first (`<debug dir>/.build-id/xx/yyyy.debug`),
+/// then by name from `.gnu_debuglink` section, in this case file checksum must match.
+pub fn find_debug_file<'data: 'file, 'file>(
+    obj_path: &Path,
+    obj: &'file impl Object<'data, 'file>,
+) -> Option<PathBuf> {
+    if let Some(build_id) = weak_error!(obj.build_id()).flatten() {
+        let found = debug_dirs()
+            .filter_map(|dir| build_id_path(dir, build_id))
+            .find(|path| path.is_file());
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    let (link, crc) = weak_error!(obj.gnu_debuglink()).flatten()?;
+    let link = Path::new(std::str::from_utf8(link).ok()?);
+    let obj_path = obj_path.canonicalize().ok()?;
+    let obj_dir = obj_path.parent()?;
+
+    let mut candidates = vec![obj_dir.join(link), obj_dir.join(".debug").join(link)];
+    let relative_obj_dir = obj_dir.strip_prefix("/").unwrap_or(obj_dir);
+    candidates.extend(debug_dirs().map(|dir| dir.join(relative_obj_dir).join(link)));
+
+    candidates.into_iter().find(|path| {
+        path.is_file()
+            && *path != obj_path
+            && fs::read(path)
+                .map(|data| crc32fast::hash(&data) == crc)
+                .unwrap_or_default()
+    })
+}
+
+fn build_id_path(dir: &Path, build_id: &[u8]) -> Option<PathBuf> {
+    let (first, rest) = build_id.split_first()?;
+    let rest: String = rest.iter().map(|b| format!("{b:02x}")).collect();
+    Some(
+        dir.join(".build-id")
+            .join(format!("{first:02x}"))
+            .join(format!("{rest}.debug")),
+    )
+}
+
+/// Return path of DWARF package (`.dwp` file) of object located at `obj_path`.
+pub fn find_dwp(obj_path: &Path) -> Option<PathBuf> {
+    let mut dwp = obj_path.as_os_str().to_owned();
+    dwp.push(".dwp");
+    let dwp = PathBuf::from(dwp);
+    dwp.is_file().then_some(dwp)
+}
+
+/// Return path of `.dwo` file with name `dwo_name` taken from skeleton unit.
+/// Relative names resolved from unit compilation directory or from object file directory.
+pub fn find_dwo(obj_path: &Path, comp_dir: Option<&Path>, dwo_name: &Path) -> Option<PathBuf> {
+    if dwo_name.is_absolute() {
+        return dwo_name.is_file().then(|| dwo_name.to_path_buf());
+    }
+
+    comp_dir
+        .map(|dir| dir.join(dwo_name))
+        .into_iter()
+        .chain(obj_path.parent().map(|dir| dir.join(dwo_name)))
+        .find(|path| path.is_file())
+}
+
+/// Loader of split units debug information (`-C split-debuginfo=packed/unpacked` builds).
+/// Split unit searched in DWARF package first, then in `.dwo` file named in skeleton unit.
+pub(super) struct SplitDwarfLoader<'a> {
+    obj_path: &'a Path,
+    parent: &'a Dwarf<EndianRcSlice>,
+    endian: RunTimeEndian,
+    /// DWARF package of object, loaded on first use.
+    package: Option<Option<DwarfPackage<EndianRcSlice>>>,
+}
+
+impl<'a> SplitDwarfLoader<'a> {
+    pub(super) fn new(
+        obj_path: &'a Path,
+        parent: &'a Dwarf<EndianRcSlice>,
+        endian: RunTimeEndian,
+    ) -> Self {
+        Self {
+            obj_path,
+            parent,
+            endian,
+            package: None,
+        }
+    }
+
+    fn empty_section(&self) -> EndianRcSlice {
+        EndianRcSlice::new(Rc::from(&[][..]), self.endian)
+    }
+
+    fn load_dwo_section<'data: 'file, 'file>(
+        &self,
+        id: SectionId,
+        file: &'data impl Object<'data, 'file>,
+    ) -> anyhow::Result<EndianRcSlice> {
+        match id.dwo_name() {
+            Some(name) => DebugeeContextBuilder::load_section(name, file, self.endian),
+            None => Ok(self.empty_section()),
+        }
+    }
+
+    fn package(&mut self) -> anyhow::Result<Option<&DwarfPackage<EndianRcSlice>>> {
+        if self.package.is_none() {
+            let package = match find_dwp(self.obj_path) {
+                Some(path) => {
+                    info!("load DWARF package {}", path.display());
+                    let file = fs::File::open(path)?;
+                    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+                    let obj = object::File::parse(&*mmap)?;
+                    Some(DwarfPackage::load(
+                        |id| self.load_dwo_section(id, &obj),
+                        self.empty_section(),
+                    )?)
+                }
+                None => None,
+            };
+            self.package = Some(package);
+        }
+        Ok(self.package.as_ref().and_then(Option::as_ref))
+    }
+
+    fn load_dwo(&self, skeleton: &Unit<EndianRcSlice>) -> anyhow::Result<Dwarf<EndianRcSlice>> {
+        let mut entries = skeleton.entries();
+        let (_, root) = entries
+            .next_dfs()?
+            .ok_or_else(|| anyhow!("skeleton unit is empty"))?;
+        let dwo_name_attr = match root.attr_value(DW_AT_dwo_name)? {
+            Some(attr) => attr,
+            None => root
+                .attr_value(DW_AT_GNU_dwo_name)?
+                .ok_or_else(|| anyhow!("dwo name not found"))?,
+        };
+        let dwo_name = self.parent.attr_string(skeleton, dwo_name_attr)?;
+        let dwo_name = PathBuf::from(dwo_name.to_string_lossy()?.as_ref());
+        let comp_dir = skeleton.comp_dir.as_ref().and_then(|dir| {
+            dir.to_string_lossy()
+                .ok()
+                .map(|dir| PathBuf::from(dir.as_ref()))
+        });
+
+        let path = find_dwo(self.obj_path, comp_dir.as_deref(), &dwo_name)
+            .ok_or_else(|| anyhow!("{} not found", dwo_name.display()))?;
+        let file = fs::File::open(path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let obj = object::File::parse(&*mmap)?;
+
+        let mut dwarf = Dwarf::load(|id| self.load_dwo_section(id, &obj))?;
+        dwarf.file_type = DwarfFileType::Dwo;
+        // addresses and (for DWARF 4) range lists of split unit are in the skeleton object
+        dwarf.debug_addr = self.parent.debug_addr.clone();
+        dwarf.ranges = RangeLists::new(
+            self.parent.ranges.debug_ranges().clone(),
+            dwarf.ranges.debug_rnglists().clone(),
+        );
+        Ok(dwarf)
+    }
+
+    /// Return split unit of skeleton unit `skeleton` and DWARF sections which contains it.
+    pub(super) fn load(
+        &mut self,
+        skeleton: &Unit<EndianRcSlice>,
+    ) -> anyhow::Result<(Dwarf<EndianRcSlice>, Unit<EndianRcSlice>)> {
+        let dwo_id = skeleton
+            .dwo_id
+            .ok_or_else(|| anyhow!("not a skeleton unit"))?;
+
+        let parent = self.parent;
+        let dwarf = match self
+            .package()?
+            .map(|package| package.find_cu(dwo_id, parent))
+            .transpose()?
+            .flatten()
+        {
+            Some(dwarf) => dwarf,
+            None => self.load_dwo(skeleton)?,
+        };
+
+        let mut headers = dwarf.units();
+        while let Some(header) = headers.next()? {
+            let mut unit = dwarf.unit(header)?;
+            if unit.dwo_id != Some(dwo_id) {
+                continue;
+            }
+
+            unit.copy_relocated_attributes(skeleton);
+            // line table and compilation directory are stored in skeleton unit
+            if unit.line_program.is_none() {
+                unit.line_program = skeleton.line_program.clone();
+            }
+            if unit.comp_dir.is_none() {
+                unit.comp_dir = skeleton.comp_dir.clone();
+            }
+            if unit.name.is_none() {
+                unit.name = skeleton.name.clone();
+            }
+            return Ok((dwarf, unit));
+        }
+
+        bail!("split unit {:#x} not found", dwo_id.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_id_path() {
+        let path = build_id_path(Path::new("/usr/lib/debug"), &[0xab, 0x01, 0xcd]);
+        assert_eq!(
+            path,
+            Some(PathBuf::from("/usr/lib/debug/.build-id/ab/01cd.debug"))
+        );
+        assert_eq!(build_id_path(Path::new("/usr/lib/debug"), &[]), None);
+    }
+}
@@ -4,8 +4,8 @@ use crate::debugger::debugee;
 use crate::debugger::debugee::dwarf::eval::EvalError::{OptionRequired, UnsupportedRequire};
 use crate::debugger::debugee::dwarf::parser::unit::{DieVariant, Unit};
 use crate::debugger::debugee::dwarf::{EndianRcSlice, RegisterDump};
-use crate::debugger::debugee::Debugee;
-use crate::debugger::register::get_register_value_dwarf;
+use crate::debugger::debugee::{Debugee, StackFrame};
+use crate::debugger::register::{get_register_value_dwarf, set_register_value_dwarf};
 use anyhow::anyhow;
 use bytes::{BufMut, Bytes, BytesMut};
 use gimli::{
@@ -35,10 +35,13 @@ pub enum EvalError {
 
 pub type Result<T> = result::Result<T, EvalError>;
 
-/// Resolve requirements that the `ExpressionEvaluator` may need. Relevant for the current breakpoint.
+/// Resolve requirements that the `ExpressionEvaluator` may need. Relevant for the current breakpoint
+/// or for the selected stack frame.
 /// Some options are lazy to avoid overhead on recalculation.
 struct RequirementsResolver<'a> {
     debugee: &'a Debugee,
+    /// Frame in which expressions are evaluated, current thread location is used if not set.
+    frame: Option<&'a StackFrame>,
     cfa: RefCell<HashMap<Pid, RelocatedAddress>>,
     base_address: RefCell<HashMap<Pid, RelocatedAddress>>,
 }
@@ -47,23 +50,54 @@ impl<'a> RequirementsResolver<'a> {
     fn new(debugee: &'a Debugee) -> Self {
         RequirementsResolver {
             debugee,
+            frame: None,
             cfa: RefCell::default(),
             base_address: RefCell::default(),
         }
     }
 
+    /// Return evaluation frame if it is a frame of thread `pid`.
+    fn frame(&self, pid: Pid) -> Option<&'a StackFrame> {
+        self.frame.filter(|frame| frame.location.pid == pid)
+    }
+
+    /// Return location of evaluation frame or current location of thread `pid`.
+    fn location(&self, pid: Pid) -> anyhow::Result<debugee::Location> {
+        match self.frame(pid) {
+            Some(frame) => Ok(frame.location),
+            None => Ok(self.debugee.thread_stop_at(pid)?),
+        }
+    }
+
+    /// Return value of register in evaluation frame or current register value of thread `pid`.
+    fn register(&self, pid: Pid, register: gimli::Register) -> anyhow::Result<u64> {
+        match self.frame(pid) {
+            Some(frame) => frame.registers.get(register).ok_or_else(|| {
+                anyhow!(
+                    "value of register {} is unknown in frame #{}",
+                    register.0,
+                    frame.num
+                )
+            }),
+            None => get_register_value_dwarf(pid, register.0 as i32),
+        }
+    }
+
     /// Return base address of current frame.
     fn base_addr(&self, pid: Pid) -> anyhow::Result<RelocatedAddress> {
         match self.base_address.borrow_mut().entry(pid) {
             Entry::Occupied(e) => Ok(*e.get()),
             Entry::Vacant(e) => {
-                let loc = self.debugee.thread_stop_at(pid)?;
+                let frame = match self.frame(pid) {
+                    Some(frame) => frame.clone(),
+                    None => self.debugee.stack_frame(pid, 0)?,
+                };
                 let func = self
                     .debugee
-                    .dwarf
-                    .find_function_by_pc(loc.global_pc)
+                    .debug_info(frame.location.pc)
+                    .find_function_by_pc(frame.location.global_pc)
                     .ok_or_else(|| anyhow!("current function not found"))?;
-                let base_addr = func.frame_base_addr(self.debugee, pid)?;
+                let base_addr = func.frame_base_addr(self.debugee, &frame)?;
                 Ok(*e.insert(base_addr))
             }
         }
@@ -71,36 +105,42 @@ impl<'a> RequirementsResolver<'a> {
 
     /// Return canonical frame address of current frame.
     fn cfa(&self, pid: Pid) -> anyhow::Result<RelocatedAddress> {
+        if let Some(frame) = self.frame(pid) {
+            return Ok(frame.cfa);
+        }
         match self.cfa.borrow_mut().entry(pid) {
             Entry::Occupied(e) => Ok(*e.get()),
             Entry::Vacant(e) => {
                 let loc = self.debugee.thread_stop_at(pid)?;
-                let cfa = self.debugee.dwarf.get_cfa(self.debugee, loc)?;
+                let cfa = self.debugee.debug_info(loc.pc).get_cfa(self.debugee, loc)?;
                 Ok(*e.insert(cfa))
             }
         }
     }
 
-    fn relocation_addr(&self) -> usize {
-        self.debugee.mapping_offset()
+    /// Return mapping offset of object which code executed by thread `pid`.
+    fn relocation_addr(&self, pid: Pid) -> anyhow::Result<usize> {
+        let loc = self.location(pid)?;
+        Ok(self.debugee.mapping_offset_for_pc(loc.pc))
     }
 
     fn resolve_tls(&self, pid: Pid, offset: u64) -> anyhow::Result<RelocatedAddress> {
-        let lm_addr = self.debugee.rendezvous().link_map_main();
+        let loc = self.location(pid)?;
+        let lm_addr = self.debugee.link_map_for_pc(loc.pc);
         self.debugee
             .threads_ctl()
             .tls_addr(pid, lm_addr, offset as usize)
     }
 
-    fn debug_addr_section(&self) -> &DebugAddr<EndianRcSlice> {
-        self.debugee.dwarf.debug_addr()
+    fn debug_addr_section(&self, pid: Pid) -> anyhow::Result<&DebugAddr<EndianRcSlice>> {
+        let loc = self.location(pid)?;
+        Ok(self.debugee.debug_info(loc.pc).debug_addr())
     }
 
     fn resolve_registers(&self, pid: Pid) -> anyhow::Result<RegisterDump> {
-        let current_loc = self.debugee.thread_stop_at(pid)?;
-        let current_fn = self
-            .debugee
-            .dwarf
+        let current_loc = self.location(pid)?;
+        let dwarf = self.debugee.debug_info(current_loc.pc);
+        let current_fn = dwarf
             .find_function_by_pc(current_loc.global_pc)
             .ok_or_else(|| anyhow!("not in function"))?;
         let entry_pc: GlobalAddress = current_fn
@@ -113,14 +153,18 @@ impl<'a> RequirementsResolver<'a> {
             .ok_or_else(|| anyhow!("entry point pc not found"))?
             .into();
 
-        self.debugee.dwarf.registers(
+        let registers = match self.frame(pid) {
+            Some(frame) => frame.registers.clone(),
+            None => RegisterDump::current(pid)?,
+        };
+        dwarf.registers(
             self.debugee,
             debugee::Location {
                 pid,
-                pc: entry_pc.relocate(self.debugee.mapping_offset()),
+                pc: entry_pc.relocate(self.debugee.mapping_offset_for_pc(current_loc.pc)),
                 global_pc: entry_pc,
             },
-            current_loc,
+            &registers,
         )
     }
 }
@@ -130,14 +174,15 @@ impl<'a> RequirementsResolver<'a> {
 #[derive(Default)]
 pub struct ExternalRequirementsResolver {
     at_location: Option<Vec<u8>>,
-    entry_registers: HashMap<Pid, RegisterDump>,
+    /// Register values used instead of thread registers (like registers at function entry).
+    registers: HashMap<Pid, RegisterDump>,
 }
 
 impl ExternalRequirementsResolver {
     pub fn new() -> Self {
         Self {
             at_location: None,
-            entry_registers: HashMap::default(),
+            registers: HashMap::default(),
         }
     }
 
@@ -148,11 +193,11 @@ impl ExternalRequirementsResolver {
         }
     }
 
-    pub fn with_entry_registers(self, pid: Pid, registers: RegisterDump) -> Self {
-        let mut regs = self.entry_registers;
+    pub fn with_registers(self, pid: Pid, registers: RegisterDump) -> Self {
+        let mut regs = self.registers;
         regs.insert(pid, registers);
         Self {
-            entry_registers: regs,
+            registers: regs,
             ..self
         }
     }
@@ -173,6 +218,13 @@ impl<'a> ExpressionEvaluator<'a> {
         }
     }
 
+    /// Evaluate expressions in stack `frame`: frame registers, CFA and frame base are used
+    /// for expressions of frame thread instead of current thread state.
+    pub fn with_frame(mut self, frame: &'a StackFrame) -> Self {
+        self.resolver.frame = Some(frame);
+        self
+    }
+
     pub fn evaluate(&self, pid: Pid, expr: Expression<EndianRcSlice>) -> Result<CompletedResult> {
         self.evaluate_with_resolver(ExternalRequirementsResolver::default(), pid, expr)
     }
@@ -194,13 +246,12 @@ impl<'a> ExpressionEvaluator<'a> {
                 } => {
                     let value_type = self.value_type_from_offset(base_type);
 
-                    // if there is registers dump for functions entry - use it
-                    let bytes = if let Some(regs) = resolver.entry_registers.remove(&pid) {
-                        regs.get(register).ok_or_else(|| {
-                            anyhow!("entry registers exists, but target not found")
-                        })?
+                    // if there is registers dump (like registers at functions entry) - use it
+                    let bytes = if let Some(regs) = resolver.registers.get(&pid) {
+                        regs.get(register)
+                            .ok_or_else(|| anyhow!("registers dump exists, but target not found"))?
                     } else {
-                        get_register_value_dwarf(pid, register.0 as i32)?
+                        self.resolver.register(pid, register)?
                     };
                     result = eval.resume_with_register(Value::from_u64(value_type, bytes)?)?;
                 }
@@ -248,7 +299,7 @@ impl<'a> ExpressionEvaluator<'a> {
                     result = eval.resume_with_memory(value)?;
                 }
                 EvaluationResult::RequiresRelocatedAddress(addr) => {
-                    let relocation_addr = self.resolver.relocation_addr();
+                    let relocation_addr = self.resolver.relocation_addr(pid)?;
                     result = eval.resume_with_relocated_address(addr + relocation_addr as u64)?;
                 }
                 EvaluationResult::RequiresTls(offset) => {
@@ -256,14 +307,14 @@ impl<'a> ExpressionEvaluator<'a> {
                     result = eval.resume_with_tls(addr.into())?;
                 }
                 EvaluationResult::RequiresIndexedAddress { index, relocate } => {
-                    let debug_addr = self.resolver.debug_addr_section();
+                    let debug_addr = self.resolver.debug_addr_section(pid)?;
                     let mut addr = debug_addr.get_address(
                         self.unit.address_size(),
                         self.unit.addr_base(),
                         index,
                     )?;
                     if relocate {
-                        addr += self.resolver.relocation_addr() as u64;
+                        addr += self.resolver.relocation_addr(pid)? as u64;
                     }
                     result = eval.resume_with_indexed_address(addr)?;
                 }
@@ -274,7 +325,7 @@ impl<'a> ExpressionEvaluator<'a> {
                 EvaluationResult::RequiresEntryValue(expr) => {
                     let regs = self.resolver.resolve_registers(pid)?;
                     let ctx_resolver =
-                        ExternalRequirementsResolver::default().with_entry_registers(pid, regs);
+                        ExternalRequirementsResolver::default().with_registers(pid, regs);
                     let eval_res = self.evaluate_with_resolver(ctx_resolver, pid, expr)?;
                     let u = eval_res.into_scalar::<u64>()?;
                     result = eval.resume_with_entry_value(Value::Generic(u))?;
@@ -291,6 +342,10 @@ impl<'a> ExpressionEvaluator<'a> {
         Ok(CompletedResult {
             inner: eval.result(),
             pid,
+            registers: self
+                .resolver
+                .frame(pid)
+                .map(|frame| frame.registers.clone()),
         })
     }
 
@@ -314,6 +369,8 @@ impl<'a> ExpressionEvaluator<'a> {
 pub struct CompletedResult {
     inner: Vec<Piece<EndianRcSlice>>,
     pid: Pid,
+    /// Registers of evaluation frame, values located in registers are taken from it.
+    registers: Option<RegisterDump>,
 }
 
 impl CompletedResult {
@@ -322,6 +379,17 @@ impl CompletedResult {
         Ok(scalar_from_bytes(bytes))
     }
 
+    /// Return address of evaluated value if value placed entirely in debugee memory.
+    pub fn memory_address(&self) -> Option<usize> {
+        match self.inner.as_slice() {
+            [piece] if piece.size_in_bits.is_none() => match piece.location {
+                Location::Address { address } => Some(address as usize),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn into_raw_buffer(self, byte_size: usize) -> Result<Bytes> {
         let mut buf = BytesMut::with_capacity(byte_size);
         self.inner.into_iter().try_for_each(|piece| -> Result<()> {
@@ -333,12 +401,13 @@ impl CompletedResult {
 
             match piece.location {
                 Location::Register { register } => {
-                    buf.put(read_register(
-                        self.pid,
-                        register.0 as i32,
-                        read_size,
-                        offset,
-                    )?);
+                    let register_value = match self.registers {
+                        Some(ref registers) => registers.get(register).ok_or_else(|| {
+                            anyhow!("value of register {} is unknown in frame", register.0)
+                        })?,
+                        None => get_register_value_dwarf(self.pid, register.0 as i32)?,
+                    };
+                    buf.put(read_register(register_value, read_size, offset));
                 }
                 Location::Address { address } => {
                     let memory =
@@ -375,13 +444,64 @@ impl CompletedResult {
 
         Ok(buf.freeze())
     }
+
+    /// Write `data` into evaluated value starting from byte `offset` of value.
+    /// Registers are written into thread `pid` directly, so value placed in registers
+    /// may be changed only in the innermost frame.
+    pub fn write_raw_buffer(&self, offset: usize, data: &[u8]) -> Result<()> {
+        let end = offset + data.len();
+        let mut piece_start = 0;
+        for piece in &self.inner {
+            let piece_end = piece
+                .size_in_bits
+                .map(|bits| piece_start + bits as usize / 8)
+                .unwrap_or(usize::MAX);
+            let (from, to) = (offset.max(piece_start), end.min(piece_end));
+            if from < to {
+                let part = &data[from - offset..to - offset];
+                let part_offset = from - piece_start;
+                match piece.location {
+                    Location::Register { register } => {
+                        let byte_offset = piece.bit_offset.unwrap_or(0) as usize / 8 + part_offset;
+                        let mut bytes =
+                            get_register_value_dwarf(self.pid, register.0 as i32)?.to_ne_bytes();
+                        bytes
+                            .get_mut(byte_offset..byte_offset + part.len())
+                            .ok_or_else(|| anyhow!("value exceeds register {}", register.0))?
+                            .copy_from_slice(part);
+                        set_register_value_dwarf(
+                            self.pid,
+                            register.0 as i32,
+                            u64::from_ne_bytes(bytes),
+                        )?;
+                    }
+                    Location::Address { address } => {
+                        debugger::write_memory_by_pid(
+                            self.pid,
+                            address as usize + part_offset,
+                            part,
+                        )
+                        .map_err(EvalError::Nix)?;
+                    }
+                    Location::Empty => {
+                        return Err(anyhow!("value is optimized out").into());
+                    }
+                    _ => return Err(anyhow!("value is not located in memory or registers").into()),
+                }
+            }
+            piece_start = piece_end;
+            if piece_start >= end {
+                break;
+            }
+        }
+        Ok(())
+    }
 }
 
-fn read_register(pid: Pid, reg_num: i32, size_in_bytes: usize, offset: u64) -> Result<Bytes> {
-    let register_value = get_register_value_dwarf(pid, reg_num)?;
+fn read_register(register_value: u64, size_in_bytes: usize, offset: u64) -> Bytes {
     let bytes = (register_value >> offset).to_ne_bytes();
     let write_size = min(size_in_bytes, std::mem::size_of::<u64>());
-    Ok(Bytes::copy_from_slice(&bytes[..write_size]))
+    Bytes::copy_from_slice(&bytes[..write_size])
 }
 
 fn scalar_from_bytes<T: Copy>(bytes: Bytes) -> T {
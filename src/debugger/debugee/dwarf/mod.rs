@@ -1,39 +1,45 @@
+pub mod debug_file;
 pub mod eval;
 pub mod parser;
 mod symbol;
 pub mod r#type;
 
 use crate::debugger::address::{GlobalAddress, RelocatedAddress};
-use crate::debugger::debugee::dwarf::eval::ExpressionEvaluator;
+use crate::debugger::debugee::dwarf::eval::{ExpressionEvaluator, ExternalRequirementsResolver};
 use crate::debugger::debugee::dwarf::parser::unit::{
-    DieVariant, Entry, FunctionDie, Node, ParameterDie, Unit, VariableDie,
+    DieVariant, Entry, FunctionDie, InlineSubroutineDie, Node, ParameterDie, Unit, VariableDie,
 };
 use crate::debugger::debugee::dwarf::parser::DieRef;
 use crate::debugger::debugee::dwarf::r#type::ComplexType;
 use crate::debugger::debugee::dwarf::r#type::EvaluationContext;
-use crate::debugger::debugee::dwarf::symbol::SymbolTab;
-use crate::debugger::debugee::{Debugee, Location};
+use crate::debugger::debugee::dwarf::symbol::{FunctionSymbols, SymbolTab};
+use crate::debugger::debugee::{Debugee, Location, StackFrame};
 use crate::debugger::register;
 use crate::debugger::utils::TryGetOrInsert;
+use crate::debugger::variable::ObjectBinaryRepr;
 use crate::{debugger, weak_error};
 use anyhow::anyhow;
-use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use gimli::CfaRule::RegisterAndOffset;
 use gimli::{
-    Attribute, AttributeValue, BaseAddresses, CfaRule, DebugAddr, DebugInfoOffset, Dwarf, EhFrame,
-    Expression, LocationLists, Register, RegisterRule, RunTimeEndian, Section, UnitOffset,
-    UnwindContext, UnwindSection, UnwindTableRow,
+    Attribute, AttributeValue, BaseAddresses, CfaRule, DebugAddr, DebugInfoOffset,
+    DebugFrame, DebugLocListsIndex, Dwarf, DwarfFileType, EhFrame, EhFrameHdr, Expression,
+    LocListIter, LocationListsOffset, ParsedEhFrameHdr, Register, RegisterRule, RunTimeEndian,
+    Section, UnitOffset, UnwindContext, UnwindSection, UnwindTableRow, X86_64,
 };
+use log::{info, warn};
 use nix::unistd::Pid;
-use object::{Object, ObjectSection};
+use object::{Object, ObjectSection, SymbolKind};
 use smallvec::{smallvec, SmallVec};
 use std::borrow::Cow;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::mem;
 use std::ops::Deref;
+use std::path::Path;
 use std::rc::Rc;
-pub use symbol::Symbol;
+pub use symbol::{FunctionSymbol, Symbol};
+use uuid::Uuid;
 
 pub type EndianRcSlice = gimli::EndianRcSlice<gimli::RunTimeEndian>;
 
@@ -42,7 +48,7 @@ pub struct DebugeeContextBuilder();
 
 impl DebugeeContextBuilder {
     fn load_section<'a: 'b, 'b, OBJ, Endian>(
-        id: gimli::SectionId,
+        name: &str,
         file: &'a OBJ,
         endian: Endian,
     ) -> anyhow::Result<gimli::EndianRcSlice<Endian>>
@@ -51,14 +57,19 @@ impl DebugeeContextBuilder {
         Endian: gimli::Endianity,
     {
         let data = file
-            .section_by_name(id.name())
+            .section_by_name(name)
             .and_then(|section| section.uncompressed_data().ok())
             .unwrap_or(Cow::Borrowed(&[]));
         Ok(gimli::EndianRcSlice::new(Rc::from(&*data), endian))
     }
 
+    /// Build debug information context of object file `obj_file` located at `obj_path`.
+    /// If DWARF sections stripped from object file, then they are loaded
+    /// from separate debug file (found by build-id or `.gnu_debuglink`).
+    /// Split units are loaded from `.dwp` package or `.dwo` files.
     pub fn build<'a, 'b, OBJ>(
         &self,
+        obj_path: &Path,
         obj_file: &'a OBJ,
     ) -> anyhow::Result<DebugeeContext<EndianRcSlice>>
     where
@@ -71,10 +82,50 @@ impl DebugeeContextBuilder {
             RunTimeEndian::Big
         };
 
-        let dwarf = gimli::Dwarf::load(|id| Self::load_section(id, obj_file, endian))?;
-        let symbol_table = SymbolTab::new(obj_file);
+        let debug_file = obj_file
+            .section_by_name(".debug_info")
+            .is_none()
+            .then(|| debug_file::find_debug_file(obj_path, obj_file))
+            .flatten();
+        let debug_file_data = match debug_file {
+            Some(ref path) => {
+                info!("load debug information from {}", path.display());
+                let file = fs::File::open(path)?;
+                Some(unsafe { memmap2::Mmap::map(&file)? })
+            }
+            None => None,
+        };
+        let debug_object = debug_file_data
+            .as_deref()
+            .map(object::File::parse)
+            .transpose()?;
+
+        let dwarf = match debug_object {
+            Some(ref debug_obj) => {
+                gimli::Dwarf::load(|id| Self::load_section(id.name(), debug_obj, endian))?
+            }
+            None => gimli::Dwarf::load(|id| Self::load_section(id.name(), obj_file, endian))?,
+        };
+        let symbol_table = SymbolTab::new(obj_file)
+            .or_else(|| debug_object.as_ref().and_then(SymbolTab::new));
+        let function_symbols = match debug_object {
+            Some(ref debug_obj) if obj_file.symbol_table().is_none() => {
+                FunctionSymbols::new(debug_obj)
+            }
+            _ => FunctionSymbols::new(obj_file),
+        };
 
-        let eh_frame = EhFrame::load(|id| Self::load_section(id, obj_file, endian))?;
+        let address_size = if obj_file.is_64() { 8 } else { 4 };
+        let eh_frame = EhFrame::load(|id| Self::load_section(id.name(), obj_file, endian))?;
+        let eh_frame_hdr =
+            EhFrameHdr::load(|id| Self::load_section(id.name(), obj_file, endian))?;
+        let mut debug_frame = match debug_object {
+            Some(ref debug_obj) => {
+                DebugFrame::load(|id| Self::load_section(id.name(), debug_obj, endian))?
+            }
+            None => DebugFrame::load(|id| Self::load_section(id.name(), obj_file, endian))?,
+        };
+        debug_frame.set_address_size(address_size);
 
         let section_addr = |name: &str| -> Option<u64> {
             obj_file.sections().find_map(|section| {
@@ -98,44 +149,206 @@ impl DebugeeContextBuilder {
         if let Some(eh_frame_hdr) = section_addr(".eh_frame_hdr") {
             bases = bases.set_eh_frame_hdr(eh_frame_hdr);
         }
+        // section may be absent, then FDE searched by linear scan of `.eh_frame`
+        let eh_frame_hdr = eh_frame_hdr.parse(&bases, address_size).ok();
 
         let parser = parser::DwarfUnitParser::new(&dwarf);
+        let mut split_loader = debug_file::SplitDwarfLoader::new(obj_path, &dwarf, endian);
+
+        let mut units = vec![];
+        let mut split_dwarf = HashMap::new();
+        let mut headers = dwarf.units();
+        while let Some(header) = headers.next()? {
+            let unit = dwarf.unit(header)?;
+            if unit.dwo_id.is_none() {
+                units.push(parser.parse(unit)?);
+                continue;
+            }
 
-        let mut units = dwarf
-            .units()
-            .map(|header| parser.parse(dwarf.unit(header)?))
-            .collect::<Vec<_>>()?;
+            match split_loader.load(&unit) {
+                Ok((dwo_dwarf, dwo_unit)) => {
+                    let dwo_parser = parser::DwarfUnitParser::new(&dwo_dwarf);
+                    let mut parsed_unit = dwo_parser.parse_split(&unit, dwo_unit)?;
+                    if parsed_unit.ranges.is_empty() {
+                        parsed_unit.ranges = dwarf.unit_ranges(&unit)?.collect::<Vec<_>>()?;
+                        parsed_unit.ranges.sort_by_key(|r| r.begin);
+                    }
+                    split_dwarf.insert(parsed_unit.id, dwo_dwarf);
+                    units.push(parsed_unit);
+                }
+                Err(e) => {
+                    warn!("split unit of {}: {e:#}", obj_path.display());
+                    units.push(parser.parse(unit)?);
+                }
+            }
+        }
         units.sort_by_key(|u| u.offset());
 
         Ok(DebugeeContext {
             inner: dwarf,
+            split_dwarf,
             eh_frame,
+            eh_frame_hdr,
+            debug_frame,
             bases,
             units,
             symbol_table,
+            function_symbols,
         })
     }
 }
 
+/// Code instance of a function.
+pub struct FunctionInstance {
+    /// Lowest address of instance code.
+    pub low_pc: GlobalAddress,
+    /// End of code range started at `low_pc`.
+    pub high_pc: GlobalAddress,
+    /// True if instance is an inlined call site.
+    pub inlined: bool,
+}
+
+/// Registers whose values are preserved across function calls (System V AMD64 ABI).
+const CALLEE_SAVED_REGISTERS: [Register; 7] = [
+    X86_64::RBX,
+    X86_64::RSP,
+    X86_64::RBP,
+    X86_64::R12,
+    X86_64::R13,
+    X86_64::R14,
+    X86_64::R15,
+];
+
+/// Register values indexed by DWARF register number, `None` if value is unknown.
+#[derive(Clone)]
 pub struct RegisterDump(SmallVec<[Option<u64>; 0x80]>);
 
+impl Default for RegisterDump {
+    fn default() -> Self {
+        Self(smallvec![None; 0x80])
+    }
+}
+
 impl RegisterDump {
+    /// Read registers of thread `pid`, program counter stored as
+    /// a return address register (DWARF register 16).
+    pub fn current(pid: Pid) -> nix::Result<Self> {
+        let mut dump = Self::default();
+        for descr in register::LIST.iter().filter(|descr| descr.dwarf_num >= 0) {
+            let value = register::get_register_value(pid, descr.r)?;
+            dump.set(Register(descr.dwarf_num as u16), value);
+        }
+        let pc = register::get_register_value(pid, register::Register::Rip)?;
+        dump.set(X86_64::RA, pc);
+        Ok(dump)
+    }
+
     pub fn get(&self, register: Register) -> Option<u64> {
         self.0.get(register.0 as usize).copied().and_then(|v| v)
     }
+
+    pub fn set(&mut self, register: Register, value: u64) {
+        if let Some(slot) = self.0.get_mut(register.0 as usize) {
+            *slot = Some(value);
+        }
+    }
+
+    /// Return dump with values of callee-saved registers only.
+    pub fn callee_saved(&self) -> Self {
+        let mut dump = Self::default();
+        CALLEE_SAVED_REGISTERS.iter().for_each(|&register| {
+            if let Some(value) = self.get(register) {
+                dump.set(register, value);
+            }
+        });
+        dump
+    }
+
+    fn value(&self, register: Register) -> anyhow::Result<u64> {
+        self.get(register)
+            .ok_or_else(|| anyhow!("value of register {} is unknown", register.0))
+    }
 }
 
 pub struct DebugeeContext<R: gimli::Reader = EndianRcSlice> {
     inner: Dwarf<R>,
+    /// Sections of `.dwo` files (or `.dwp` package contributions) for split units.
+    split_dwarf: HashMap<Uuid, Dwarf<R>>,
     eh_frame: EhFrame<R>,
+    /// Parsed `.eh_frame_hdr`, contains table for binary search of FDE in `.eh_frame`.
+    eh_frame_hdr: Option<ParsedEhFrameHdr<R>>,
+    debug_frame: DebugFrame<R>,
     bases: BaseAddresses,
     units: Vec<parser::unit::Unit>,
     symbol_table: Option<SymbolTab>,
+    function_symbols: FunctionSymbols,
 }
 
 impl DebugeeContext {
-    pub fn locations(&self) -> &LocationLists<EndianRcSlice> {
-        &self.inner.locations
+    /// Return DWARF sections which contain unit, these are `.dwo` sections for split units.
+    fn unit_dwarf(&self, unit: &Unit) -> &Dwarf<EndianRcSlice> {
+        self.split_dwarf.get(&unit.id).unwrap_or(&self.inner)
+    }
+
+    /// Return offset of location list by its index in `.debug_loclists` section of unit.
+    pub fn location_lists_offset(
+        &self,
+        unit: &Unit,
+        index: DebugLocListsIndex,
+    ) -> gimli::Result<LocationListsOffset> {
+        self.unit_dwarf(unit)
+            .locations
+            .get_offset(unit.encoding(), unit.loclists_base(), index)
+    }
+
+    /// Return location list of unit at `offset`.
+    pub fn locations(
+        &self,
+        unit: &Unit,
+        offset: LocationListsOffset,
+    ) -> gimli::Result<LocListIter<EndianRcSlice>> {
+        let dwarf = self.unit_dwarf(unit);
+        match dwarf.file_type {
+            DwarfFileType::Main => dwarf.locations.locations(
+                offset,
+                unit.encoding(),
+                unit.low_pc(),
+                &dwarf.debug_addr,
+                unit.addr_base(),
+            ),
+            DwarfFileType::Dwo => dwarf.locations.locations_dwo(
+                offset,
+                unit.encoding(),
+                unit.low_pc(),
+                &dwarf.debug_addr,
+                unit.addr_base(),
+            ),
+        }
+    }
+
+    /// Return CFI row for `pc`. FDE searched in `.eh_frame` (using lookup table
+    /// of `.eh_frame_hdr` if exists), then in `.debug_frame`.
+    fn unwind_row<'ctx>(
+        &self,
+        ctx: &'ctx mut UnwindContext<EndianRcSlice>,
+        pc: GlobalAddress,
+    ) -> gimli::Result<&'ctx UnwindTableRow<EndianRcSlice>> {
+        let pc = u64::from(pc);
+        let eh_fde = match self.eh_frame_hdr.as_ref().and_then(|hdr| hdr.table()) {
+            Some(table) => {
+                table.fde_for_address(&self.eh_frame, &self.bases, pc, EhFrame::cie_from_offset)
+            }
+            None => self
+                .eh_frame
+                .fde_for_address(&self.bases, pc, EhFrame::cie_from_offset),
+        };
+        match eh_fde {
+            Ok(fde) => fde.unwind_info_for_address(&self.eh_frame, &self.bases, ctx, pc),
+            Err(_) => self
+                .debug_frame
+                .fde_for_address(&self.bases, pc, DebugFrame::cie_from_offset)?
+                .unwind_info_for_address(&self.debug_frame, &self.bases, ctx, pc),
+        }
     }
 
     fn evaluate_cfa(
@@ -143,20 +356,25 @@ impl DebugeeContext {
         debugee: &Debugee,
         utr: &UnwindTableRow<EndianRcSlice>,
         location: Location,
+        registers: &RegisterDump,
     ) -> anyhow::Result<RelocatedAddress> {
         let rule = utr.cfa();
         match rule {
             RegisterAndOffset { register, offset } => {
-                let ra = register::get_register_value_dwarf(location.pid, register.0 as i32)?;
+                let ra = registers.value(*register)?;
                 Ok(RelocatedAddress::from(ra as usize).offset(*offset as isize))
             }
             CfaRule::Expression(expr) => {
                 let unit = self
                     .find_unit_by_pc(location.global_pc)
                     .ok_or_else(|| anyhow!("undefined unit"))?;
-                let expr_result = unit
-                    .evaluator(debugee)
-                    .evaluate(location.pid, expr.clone())?;
+                let resolver =
+                    ExternalRequirementsResolver::new().with_registers(location.pid, registers.clone());
+                let expr_result = unit.evaluator(debugee).evaluate_with_resolver(
+                    resolver,
+                    location.pid,
+                    expr.clone(),
+                )?;
 
                 Ok((expr_result.into_scalar::<usize>()?).into())
             }
@@ -169,31 +387,62 @@ impl DebugeeContext {
         location: Location,
     ) -> anyhow::Result<RelocatedAddress> {
         let mut ctx = Box::new(UnwindContext::new());
-        let row = self.eh_frame.unwind_info_for_address(
-            &self.bases,
-            &mut ctx,
-            location.global_pc.into(),
-            EhFrame::cie_from_offset,
-        )?;
-        self.evaluate_cfa(debugee, row, location)
+        let row = self.unwind_row(&mut ctx, location.global_pc)?;
+        self.evaluate_cfa(debugee, row, location, &RegisterDump::current(location.pid)?)
     }
 
+    /// Return caller registers restored by CFI rules at `location`, where `registers`
+    /// are registers of the frame. Registers without rules are not included in result.
     pub fn registers(
         &self,
         debugee: &Debugee,
         location: Location,
-        current_location: Location,
+        registers: &RegisterDump,
     ) -> anyhow::Result<RegisterDump> {
         let mut ctx = Box::new(UnwindContext::new());
-        let row = self.eh_frame.unwind_info_for_address(
-            &self.bases,
-            &mut ctx,
-            location.global_pc.into(),
-            EhFrame::cie_from_offset,
-        )?;
+        let row = self.unwind_row(&mut ctx, location.global_pc)?;
+        Ok(self.restore_registers(debugee, row, location, registers))
+    }
 
+    /// Unwind frame at `location` with `registers`, return CFA of the frame and
+    /// registers of its caller. Caller stack pointer is CFA, registers without CFI rules
+    /// keep their values if they are callee-saved and unknown otherwise.
+    pub fn unwind(
+        &self,
+        debugee: &Debugee,
+        location: Location,
+        registers: &RegisterDump,
+    ) -> anyhow::Result<(RelocatedAddress, RegisterDump)> {
+        let mut ctx = Box::new(UnwindContext::new());
+        let row = self.unwind_row(&mut ctx, location.global_pc)?;
+        let cfa = self.evaluate_cfa(debugee, row, location, registers)?;
+
+        let mut caller_registers = registers.callee_saved();
+        caller_registers.set(X86_64::RSP, cfa.into());
+
+        let restored = self.restore_registers(debugee, row, location, registers);
+        restored
+            .0
+            .iter()
+            .enumerate()
+            .for_each(|(register, value)| {
+                if let Some(value) = value {
+                    caller_registers.0[register] = Some(*value);
+                }
+            });
+
+        Ok((cfa, caller_registers))
+    }
+
+    fn restore_registers(
+        &self,
+        debugee: &Debugee,
+        row: &UnwindTableRow<EndianRcSlice>,
+        location: Location,
+        registers: &RegisterDump,
+    ) -> RegisterDump {
         let mut lazy_cfa = None;
-        let cfa_init_fn = || self.evaluate_cfa(debugee, row, current_location);
+        let cfa_init_fn = || self.evaluate_cfa(debugee, row, location, registers);
 
         let mut lazy_evaluator = None;
         let evaluator_init_fn = || -> anyhow::Result<ExpressionEvaluator> {
@@ -202,17 +451,17 @@ impl DebugeeContext {
                 .ok_or_else(|| anyhow!("undefined unit"))?;
             Ok(unit.evaluator(debugee))
         };
+        let resolver = || {
+            ExternalRequirementsResolver::new().with_registers(location.pid, registers.clone())
+        };
 
-        let mut registers: SmallVec<[Option<u64>; 0x80]> = smallvec![None; 0x80];
+        let mut restored = RegisterDump::default();
 
         row.registers()
             .filter_map(|(register, rule)| {
                 let value = match rule {
                     RegisterRule::Undefined => return None,
-                    RegisterRule::SameValue => weak_error!(register::get_register_value_dwarf(
-                        location.pid,
-                        register.0 as i32
-                    ))?,
+                    RegisterRule::SameValue => weak_error!(registers.value(*register))?,
                     RegisterRule::Offset(offset) => {
                         let cfa = *weak_error!(lazy_cfa.try_get_or_insert_with(cfa_init_fn))?;
                         let addr = cfa.offset(*offset as isize);
@@ -229,14 +478,15 @@ impl DebugeeContext {
                         let cfa = *weak_error!(lazy_cfa.try_get_or_insert_with(cfa_init_fn))?;
                         cfa.offset(*offset as isize).into()
                     }
-                    RegisterRule::Register(reg) => weak_error!(
-                        register::get_register_value_dwarf(location.pid, reg.0 as i32)
-                    )?,
+                    RegisterRule::Register(reg) => weak_error!(registers.value(*reg))?,
                     RegisterRule::Expression(expr) => {
                         let evaluator =
                             weak_error!(lazy_evaluator.try_get_or_insert_with(evaluator_init_fn))?;
-                        let expr_result =
-                            weak_error!(evaluator.evaluate(location.pid, expr.clone()))?;
+                        let expr_result = weak_error!(evaluator.evaluate_with_resolver(
+                            resolver(),
+                            location.pid,
+                            expr.clone()
+                        ))?;
                         let addr = weak_error!(expr_result.into_scalar::<usize>())?;
                         let bytes = weak_error!(debugger::read_memory_by_pid(
                             location.pid,
@@ -250,8 +500,11 @@ impl DebugeeContext {
                     RegisterRule::ValExpression(expr) => {
                         let evaluator =
                             weak_error!(lazy_evaluator.try_get_or_insert_with(evaluator_init_fn))?;
-                        let expr_result =
-                            weak_error!(evaluator.evaluate(location.pid, expr.clone()))?;
+                        let expr_result = weak_error!(evaluator.evaluate_with_resolver(
+                            resolver(),
+                            location.pid,
+                            expr.clone()
+                        ))?;
                         weak_error!(expr_result.into_scalar::<u64>())?
                     }
                     RegisterRule::Architectural => return None,
@@ -259,9 +512,9 @@ impl DebugeeContext {
 
                 Some((*register, value))
             })
-            .for_each(|(reg, val)| registers.insert(reg.0 as usize, Some(val)));
+            .for_each(|(reg, val)| restored.set(reg, val));
 
-        Ok(RegisterDump(registers))
+        restored
     }
 
     pub fn debug_addr(&self) -> &DebugAddr<EndianRcSlice> {
@@ -317,41 +570,222 @@ impl DebugeeContext {
         })
     }
 
-    pub fn find_function_by_name(&self, needle: &str) -> Option<ContextualDieRef<FunctionDie>> {
-        self.units.iter().find_map(|unit| {
-            unit.entries.iter().find_map(|entry| {
-                if let DieVariant::Function(func) = &entry.die {
-                    if func.base_attributes.name.as_deref() == Some(needle) {
-                        return Some(ContextualDieRef {
-                            context: self,
-                            unit,
-                            node: &entry.node,
-                            die: func,
-                        });
-                    }
+    /// Return inlined function calls which code contains `pc`, the most nested call first.
+    pub fn find_inlined_calls(
+        &self,
+        pc: GlobalAddress,
+    ) -> Vec<ContextualDieRef<'_, InlineSubroutineDie>> {
+        let Some(unit) = self.find_unit_by_pc(pc) else {
+            return vec![];
+        };
+        unit.inlined_subroutines(pc.into())
+            .into_iter()
+            .filter_map(|idx| {
+                let entry = &unit.entries[idx];
+                match entry.die {
+                    DieVariant::InlineSubroutine(ref die) => Some(ContextualDieRef {
+                        context: self,
+                        unit,
+                        node: &entry.node,
+                        die,
+                    }),
+                    _ => None,
                 }
-                None
             })
-        })
+            .collect()
+    }
+
+    /// Find all code instances of function: monomorphizations, out-of-line copies and
+    /// inlined call sites. Function name may be qualified with namespaces (like `Vec::push`),
+    /// generic arguments are ignored.
+    pub fn find_function_instances(&self, needle: &str) -> Vec<FunctionInstance> {
+        let needle = split_path(needle);
+        if needle.is_empty() {
+            return vec![];
+        }
+
+        let mut instances = vec![];
+        for unit in &self.units {
+            for entry in &unit.entries {
+                let (ranges, inlined) = match entry.die {
+                    DieVariant::Function(ref func) => (&func.base_attributes.ranges, false),
+                    DieVariant::InlineSubroutine(ref inlined) => {
+                        (&inlined.base_attributes.ranges, true)
+                    }
+                    _ => continue,
+                };
+                let Some(range) = ranges.iter().min_by_key(|r| r.begin) else {
+                    continue;
+                };
+
+                if self.function_name_matches(unit, entry, &needle) {
+                    instances.push(FunctionInstance {
+                        low_pc: GlobalAddress::from(range.begin),
+                        high_pc: GlobalAddress::from(range.end),
+                        inlined,
+                    });
+                }
+            }
+        }
+        instances
+    }
+
+    /// Find functions with code which may be called directly: monomorphizations and
+    /// out-of-line copies with their own declaration. Function name may be qualified
+    /// with namespaces, generic arguments are ignored.
+    pub fn find_functions(&self, needle: &str) -> Vec<ContextualDieRef<'_, FunctionDie>> {
+        let needle = split_path(needle);
+        if needle.is_empty() {
+            return vec![];
+        }
+
+        let mut functions = vec![];
+        for unit in &self.units {
+            for entry in &unit.entries {
+                let DieVariant::Function(ref func) = entry.die else {
+                    continue;
+                };
+                if func.base_attributes.ranges.is_empty() || func.abstract_origin.is_some() {
+                    continue;
+                }
+                if self.function_name_matches(unit, entry, &needle) {
+                    functions.push(ContextualDieRef {
+                        context: self,
+                        unit,
+                        node: &entry.node,
+                        die: func,
+                    });
+                }
+            }
+        }
+        functions
     }
 
-    pub fn find_stmt_line(&self, file: &str, line: u64) -> Option<parser::unit::Place<'_>> {
+    /// Return true if name of the function (or inlined function) die ends with `needle` path.
+    fn function_name_matches(&self, unit: &Unit, entry: &Entry, needle: &[String]) -> bool {
+        let func = match entry.die {
+            DieVariant::Function(ref func) => func,
+            DieVariant::InlineSubroutine(ref inlined) => {
+                return inlined
+                    .abstract_origin
+                    .and_then(|origin| self.resolve_die(unit, origin))
+                    .map(|(unit, origin)| self.function_name_matches(unit, origin, needle))
+                    .unwrap_or(false);
+            }
+            _ => return false,
+        };
+
+        if let Some(origin) = func.abstract_origin.or(func.specification) {
+            return self
+                .resolve_die(unit, origin)
+                .map(|(unit, origin)| self.function_name_matches(unit, origin, needle))
+                .unwrap_or(false);
+        }
+
+        let Some(name) = func.base_attributes.name.as_deref() else {
+            return false;
+        };
+        if split_path(name).last() != needle.last() {
+            return false;
+        }
+        if needle.len() == 1 {
+            return true;
+        }
+
+        let path = match func.linkage_name {
+            Some(ref linkage_name) => {
+                split_path(&format!("{:#}", rustc_demangle::demangle(linkage_name)))
+            }
+            None => {
+                let mut path = NamespaceHierarchy::for_node(&entry.node, unit)
+                    .iter()
+                    .flat_map(|ns| split_path(ns))
+                    .collect::<Vec<_>>();
+                path.extend(split_path(name));
+                path
+            }
+        };
+        path.ends_with(needle)
+    }
+
+    /// Find places of all code instances of the source line.
+    pub fn find_stmt_lines(&self, file: &str, line: u64) -> Vec<parser::unit::Place<'_>> {
         self.units
             .iter()
-            .find_map(|unit| unit.find_stmt_line(file, line))
+            .flat_map(|unit| unit.find_stmt_lines(file, line))
+            .collect()
+    }
+
+    /// Return function symbol which code contains `pc`.
+    pub fn find_function_symbol_by_pc(&self, pc: GlobalAddress) -> Option<&FunctionSymbol> {
+        self.function_symbols.find(pc.into())
     }
 
     pub fn find_symbol(&self, name: &str) -> Option<&Symbol> {
         self.symbol_table.as_ref().and_then(|table| table.get(name))
     }
 
+    /// Return addresses of function symbols which demangled path ends with `name`,
+    /// useful for functions without debug information (like functions of std library).
+    pub fn find_function_symbols(&self, name: &str) -> Vec<GlobalAddress> {
+        let needle = split_path(name);
+        if needle.is_empty() {
+            return vec![];
+        }
+
+        let mut addresses = self
+            .symbol_table
+            .iter()
+            .flat_map(|table| table.iter())
+            .filter(|(_, symbol)| symbol.kind == SymbolKind::Text)
+            .filter(|(symbol_name, _)| {
+                split_path(&format!("{:#}", rustc_demangle::demangle(symbol_name)))
+                    .ends_with(&needle)
+            })
+            .map(|(_, symbol)| symbol.addr as usize)
+            .collect::<Vec<_>>();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses.into_iter().map(GlobalAddress::from).collect()
+    }
+
     pub fn deref_die<'this>(
         &'this self,
         default_unit: &'this Unit,
         reference: DieRef,
     ) -> Option<&'this Entry> {
+        self.resolve_die(default_unit, reference)
+            .map(|(_, entry)| entry)
+    }
+
+    /// Return function die referenced by `reference` (like abstract origin of inlined call).
+    fn resolve_function<'this>(
+        &'this self,
+        default_unit: &'this Unit,
+        reference: DieRef,
+    ) -> Option<ContextualDieRef<'this, FunctionDie>> {
+        let (unit, entry) = self.resolve_die(default_unit, reference)?;
+        match entry.die {
+            DieVariant::Function(ref die) => Some(ContextualDieRef {
+                context: self,
+                unit,
+                node: &entry.node,
+                die,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Like [`DebugeeContext::deref_die`] but also return unit which contains the die.
+    fn resolve_die<'this>(
+        &'this self,
+        default_unit: &'this Unit,
+        reference: DieRef,
+    ) -> Option<(&'this Unit, &'this Entry)> {
         match reference {
-            DieRef::Unit(offset) => default_unit.find_entry(offset),
+            DieRef::Unit(offset) => default_unit
+                .find_entry(offset)
+                .map(|entry| (default_unit, entry)),
             DieRef::Global(offset) => {
                 let unit = match self
                     .units
@@ -363,19 +797,30 @@ impl DebugeeContext {
                 unit.find_entry(UnitOffset(
                     offset.0 - unit.offset().unwrap_or(DebugInfoOffset(0)).0,
                 ))
+                .map(|entry| (unit, entry))
             }
         }
     }
 
+    /// Find variables with `name` visible at `location`, name may be qualified
+    /// with a namespace path (like `crate::module::STATIC`).
     pub fn find_variables(
         &self,
         location: Location,
         name: &str,
     ) -> Vec<ContextualDieRef<'_, VariableDie>> {
+        let (path, name) = match name.rsplit_once("::") {
+            Some((path, name)) => (path.split("::").collect::<Vec<_>>(), name),
+            None => (vec![], name),
+        };
+
         let mut found = vec![];
         for unit in &self.units {
             if let Some(vars) = unit.variable_index.get(name) {
-                vars.iter().for_each(|(_, entry_idx)| {
+                vars.iter().for_each(|(namespaces, entry_idx)| {
+                    if !namespaces.matches_path(&path) {
+                        return;
+                    }
                     if let DieVariant::Variable(ref var) = unit.entries[*entry_idx].die {
                         let variable = ContextualDieRef {
                             context: self,
@@ -417,6 +862,33 @@ impl DebugeeContext {
     }
 }
 
+/// Split qualified name into path segments, generic arguments are removed,
+/// so `alloc::vec::Vec<T,A>::push` becomes `[alloc, vec, Vec, push]`.
+fn split_path(name: &str) -> Vec<String> {
+    let mut depth = 0;
+    let stripped = name
+        .chars()
+        .filter(|&c| {
+            match c {
+                '<' => depth += 1,
+                '>' if depth > 0 => {
+                    depth -= 1;
+                    return false;
+                }
+                _ => {}
+            }
+            depth == 0
+        })
+        .collect::<String>();
+
+    stripped
+        .split("::")
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
 pub trait AsAllocatedValue {
     fn name(&self) -> Option<&str>;
 
@@ -438,19 +910,13 @@ pub trait AsAllocatedValue {
 
         let offset = match location.value() {
             AttributeValue::LocationListsRef(offset) => offset,
-            AttributeValue::DebugLocListsIndex(index) => weak_error!(dwarf_ctx
-                .locations()
-                .get_offset(unit.encoding(), unit.loclists_base(), index))?,
+            AttributeValue::DebugLocListsIndex(index) => {
+                weak_error!(dwarf_ctx.location_lists_offset(unit, index))?
+            }
             _ => return None,
         };
 
-        let mut iter = weak_error!(dwarf_ctx.locations().locations(
-            offset,
-            unit.encoding(),
-            unit.low_pc(),
-            dwarf_ctx.debug_addr(),
-            unit.addr_base(),
-        ))?;
+        let mut iter = weak_error!(dwarf_ctx.locations(unit, offset))?;
 
         let pc = u64::from(pc);
         let entry = iter
@@ -524,6 +990,15 @@ impl NamespaceHierarchy {
     pub fn contains(&self, needle: &[&str]) -> bool {
         self.0.windows(needle.len()).any(|slice| slice == needle)
     }
+
+    /// Return true if namespace ends with `path`,
+    /// `crate` at the start of the path matches any crate name but only at the top level.
+    pub fn matches_path(&self, path: &[&str]) -> bool {
+        match path {
+            ["crate", path @ ..] => self.0.len() == path.len() + 1 && self.0[1..] == *path,
+            _ => self.0.len() >= path.len() && self.0[self.0.len() - path.len()..] == *path,
+        }
+    }
 }
 
 pub struct ContextualDieRef<'a, T> {
@@ -546,6 +1021,12 @@ impl<'a, T> Clone for ContextualDieRef<'a, T> {
 
 impl<'a, T> Copy for ContextualDieRef<'a, T> {}
 
+impl<'a, T> PartialEq for ContextualDieRef<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.die, other.die)
+    }
+}
+
 impl<'a, T> ContextualDieRef<'a, T> {
     pub fn namespaces(&self) -> NamespaceHierarchy {
         NamespaceHierarchy::for_node(self.node, self.unit)
@@ -553,7 +1034,36 @@ impl<'a, T> ContextualDieRef<'a, T> {
 }
 
 impl<'ctx> ContextualDieRef<'ctx, FunctionDie> {
-    pub fn frame_base_addr(&self, debugee: &Debugee, pid: Pid) -> anyhow::Result<RelocatedAddress> {
+    /// Return function name qualified with namespaces, name of concrete instance
+    /// (or method definition) taken from its abstract origin (or declaration).
+    pub fn full_name(&self) -> Option<String> {
+        if let Some(origin) = self.die.abstract_origin.or(self.die.specification) {
+            return self.context.resolve_function(self.unit, origin)?.full_name();
+        }
+        if let Some(ref linkage_name) = self.die.linkage_name {
+            return Some(format!("{:#}", rustc_demangle::demangle(linkage_name)));
+        }
+        let mut path = self.namespaces().to_vec();
+        path.push(self.die.base_attributes.name.clone()?);
+        Some(path.join("::"))
+    }
+
+    /// Return type of function, `None` if function returns nothing (unit type).
+    /// Return type of concrete instance (or method definition) taken from its abstract origin
+    /// (or declaration).
+    pub fn return_type(&self) -> Option<ComplexType> {
+        if let Some(origin) = self.die.abstract_origin.or(self.die.specification) {
+            return self.context.resolve_function(self.unit, origin)?.return_type();
+        }
+        let parser = r#type::TypeParser::new();
+        Some(parser.parse(*self, self.die.type_ref?))
+    }
+
+    pub fn frame_base_addr(
+        &self,
+        debugee: &Debugee,
+        frame: &StackFrame,
+    ) -> anyhow::Result<RelocatedAddress> {
         let attr = self
             .die
             .fb_addr
@@ -567,7 +1077,8 @@ impl<'ctx> ContextualDieRef<'ctx, FunctionDie> {
         let result = self
             .unit
             .evaluator(debugee)
-            .evaluate(pid, expr)?
+            .with_frame(frame)
+            .evaluate(frame.location.pid, expr)?
             .into_scalar::<usize>()?;
 
         Ok(result.into())
@@ -601,7 +1112,7 @@ impl<'ctx> ContextualDieRef<'ctx, FunctionDie> {
         result
     }
 
-    pub fn parameters(&self) -> Vec<ContextualDieRef<'_, ParameterDie>> {
+    pub fn parameters(&self) -> Vec<ContextualDieRef<'ctx, ParameterDie>> {
         let mut result = vec![];
         let mut queue = VecDeque::from(self.node.children.clone());
         while let Some(idx) = queue.pop_front() {
@@ -621,6 +1132,48 @@ impl<'ctx> ContextualDieRef<'ctx, FunctionDie> {
         }
         result
     }
+
+    /// Return parameters of function in declaration order, unlike [`Self::parameters`]
+    /// parameters of inlined calls are not included.
+    pub fn formal_parameters(&self) -> Vec<ContextualDieRef<'ctx, ParameterDie>> {
+        self.node
+            .children
+            .iter()
+            .filter_map(|&idx| match self.unit.entries[idx].die {
+                DieVariant::Parameter(ref param) => Some(ContextualDieRef {
+                    context: self.context,
+                    unit: self.unit,
+                    node: &self.unit.entries[idx].node,
+                    die: param,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl<'ctx> ContextualDieRef<'ctx, InlineSubroutineDie> {
+    /// Return name of inlined function qualified with namespaces.
+    pub fn function_name(&self) -> Option<String> {
+        let origin = self.die.abstract_origin?;
+        self.context.resolve_function(self.unit, origin)?.full_name()
+    }
+
+    /// Return source file and line of inlined function call.
+    pub fn call_place(&self) -> Option<(&'ctx Path, u64)> {
+        let file = self.unit.file(self.die.call_file?)?;
+        Some((file, self.die.call_line?))
+    }
+
+    /// Return true if `pc` belongs to inlined code.
+    pub fn contains(&self, pc: GlobalAddress) -> bool {
+        let pc = u64::from(pc);
+        self.die
+            .base_attributes
+            .ranges
+            .iter()
+            .any(|range| range.begin <= pc && pc < range.end)
+    }
 }
 
 impl<'ctx> ContextualDieRef<'ctx, VariableDie> {
@@ -666,24 +1219,83 @@ impl<'ctx, D: AsAllocatedValue> ContextualDieRef<'ctx, D> {
         Some(parser.parse(*self, self.die.type_ref()?))
     }
 
-    pub fn read_value_at_location(
+    /// Read variable value in stack `frame`.
+    pub fn read_value_in_frame(
         &self,
-        location: Location,
+        frame: &StackFrame,
         debugee: &Debugee,
         r#type: &ComplexType,
-    ) -> Option<Bytes> {
+    ) -> Option<ObjectBinaryRepr> {
+        let pid = frame.location.pid;
         self.die
-            .location_expr(location.global_pc, self.context, self.unit)
+            .location_expr(frame.location.global_pc, self.context, self.unit)
             .and_then(|expr| {
-                let evaluator = self.unit.evaluator(debugee);
-                let eval_result = weak_error!(evaluator.evaluate(location.pid, expr))?;
-                weak_error!(eval_result.into_raw_buffer(r#type.type_size_in_bytes(
-                    &EvaluationContext {
-                        evaluator: &evaluator,
-                        pid: location.pid,
-                    },
-                    r#type.root
-                )? as usize))
+                let evaluator = self.unit.evaluator(debugee).with_frame(frame);
+                let eval_result = weak_error!(evaluator.evaluate(pid, expr))?;
+                let address = eval_result.memory_address();
+                let raw_data =
+                    weak_error!(eval_result.into_raw_buffer(r#type.type_size_in_bytes(
+                        &EvaluationContext {
+                            evaluator: &evaluator,
+                            pid,
+                        },
+                        r#type.root
+                    )? as usize))?;
+                Some(ObjectBinaryRepr::new(raw_data, address))
             })
     }
+
+    /// Write `data` into variable value in stack `frame` starting from byte `offset` of value.
+    pub fn write_value_in_frame(
+        &self,
+        frame: &StackFrame,
+        debugee: &Debugee,
+        offset: usize,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        let expr = self
+            .die
+            .location_expr(frame.location.global_pc, self.context, self.unit)
+            .ok_or_else(|| anyhow!("variable location is unknown"))?;
+        let evaluator = self.unit.evaluator(debugee).with_frame(frame);
+        evaluator
+            .evaluate(frame.location.pid, expr)?
+            .write_raw_buffer(offset, data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_path() {
+        struct TestCase {
+            name: &'static str,
+            expected: Vec<&'static str>,
+        }
+        let test_cases = vec![
+            TestCase {
+                name: "generic<u8>",
+                expected: vec!["generic"],
+            },
+            TestCase {
+                name: "alloc::vec::Vec<T,A>::push",
+                expected: vec!["alloc", "vec", "Vec", "push"],
+            },
+            TestCase {
+                name: "vars::generic::<alloc::vec::Vec<u8>>",
+                expected: vec!["vars", "generic"],
+            },
+            TestCase {
+                name: "<i32 as core::iter::traits::accum::Sum>::sum",
+                expected: vec!["sum"],
+            },
+        ];
+
+        for tc in test_cases {
+            assert_eq!(split_path(tc.name), tc.expected);
+        }
+    }
 }
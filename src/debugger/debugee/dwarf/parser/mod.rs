@@ -2,18 +2,19 @@ pub mod unit;
 
 use crate::debugger::debugee::dwarf::parser::unit::{
     ArrayDie, ArraySubrangeDie, BaseTypeDie, DieAttributes, DieRange, DieVariant, Entry,
-    EnumTypeDie, EnumeratorDie, FunctionDie, LexicalBlockDie, LineRow, Namespace, Node,
-    ParameterDie, PointerType, StructTypeDie, TemplateTypeParameter, TypeMemberDie, UnionTypeDie,
-    Unit, UnitProperties, VariableDie, Variant, VariantPart,
+    EnumTypeDie, EnumeratorDie, FunctionDie, InlineSubroutineDie, LexicalBlockDie, LineRow,
+    Namespace, Node, ParameterDie, PointerType, StructTypeDie, TemplateTypeParameter,
+    TypeMemberDie, UnionTypeDie, Unit, UnitProperties, VariableDie, Variant, VariantPart,
 };
 use crate::debugger::debugee::dwarf::{EndianRcSlice, NamespaceHierarchy};
 use crate::debugger::rust::Environment;
 use fallible_iterator::FallibleIterator;
 use gimli::{
-    Attribute, AttributeValue, DW_AT_address_class, DW_AT_byte_size, DW_AT_const_value,
-    DW_AT_count, DW_AT_data_member_location, DW_AT_discr, DW_AT_discr_value, DW_AT_encoding,
-    DW_AT_frame_base, DW_AT_location, DW_AT_lower_bound, DW_AT_name, DW_AT_type, DW_AT_upper_bound,
-    DebugInfoOffset, Range, Reader, Unit as DwarfUnit, UnitOffset,
+    Attribute, AttributeValue, DW_AT_abstract_origin, DW_AT_address_class, DW_AT_byte_size,
+    DW_AT_call_file, DW_AT_call_line, DW_AT_const_value, DW_AT_count, DW_AT_data_member_location,
+    DW_AT_discr, DW_AT_discr_value, DW_AT_encoding, DW_AT_frame_base, DW_AT_linkage_name,
+    DW_AT_location, DW_AT_lower_bound, DW_AT_name, DW_AT_specification, DW_AT_type,
+    DW_AT_upper_bound, DebugInfoOffset, Range, Reader, Unit as DwarfUnit, UnitOffset,
 };
 use std::collections::HashMap;
 use std::num::NonZeroU64;
@@ -29,6 +30,18 @@ impl<'a> DwarfUnitParser<'a> {
         Self { dwarf }
     }
 
+    /// Parse split unit (from `.dwo` file or `.dwp` package), parsed unit takes place
+    /// of its skeleton unit in the object units list.
+    pub fn parse_split(
+        &self,
+        skeleton: &DwarfUnit<EndianRcSlice>,
+        unit: DwarfUnit<EndianRcSlice>,
+    ) -> gimli::Result<Unit> {
+        let mut parsed_unit = self.parse(unit)?;
+        parsed_unit.properties.offset = skeleton.header.offset().as_debug_info_offset();
+        Ok(parsed_unit)
+    }
+
     pub fn parse(&self, unit: DwarfUnit<EndianRcSlice>) -> gimli::Result<Unit> {
         let name = unit
             .name
@@ -129,7 +142,30 @@ impl<'a> DwarfUnitParser<'a> {
                 gimli::DW_TAG_subprogram => DieVariant::Function(FunctionDie {
                     base_attributes: base_attrs,
                     fb_addr: die.attr(DW_AT_frame_base)?,
+                    linkage_name: die
+                        .attr(DW_AT_linkage_name)?
+                        .and_then(|attr| self.dwarf.attr_string(unit, attr.value()).ok())
+                        .map(|s| s.to_string_lossy().map(|s| s.to_string()))
+                        .transpose()?,
+                    abstract_origin: die.attr(DW_AT_abstract_origin)?.and_then(DieRef::from_attr),
+                    specification: die.attr(DW_AT_specification)?.and_then(DieRef::from_attr),
+                    type_ref: die.attr(DW_AT_type)?.and_then(DieRef::from_attr),
                 }),
+                gimli::DW_TAG_inlined_subroutine => {
+                    DieVariant::InlineSubroutine(InlineSubroutineDie {
+                        base_attributes: base_attrs,
+                        abstract_origin: die
+                            .attr(DW_AT_abstract_origin)?
+                            .and_then(DieRef::from_attr),
+                        call_file: die
+                            .attr(DW_AT_call_file)?
+                            .and_then(|attr| match attr.value() {
+                                AttributeValue::FileIndex(index) => Some(index),
+                                _ => attr.udata_value(),
+                            }),
+                        call_line: die.attr(DW_AT_call_line)?.and_then(|val| val.udata_value()),
+                    })
+                }
                 gimli::DW_TAG_formal_parameter => DieVariant::Parameter(ParameterDie {
                     base_attributes: base_attrs,
                     type_ref: die.attr(DW_AT_type)?.and_then(DieRef::from_attr),
@@ -301,6 +337,7 @@ where
             line: line_row.line().map(NonZeroU64::get).unwrap_or(0) as u64,
             column,
             is_stmt: line_row.is_stmt(),
+            prologue_end: line_row.prologue_end(),
         })
     }
     Ok(lines)
@@ -18,6 +18,7 @@ pub(super) struct LineRow {
     pub(super) line: u64,
     pub(super) column: u64,
     pub(super) is_stmt: bool,
+    pub(super) prologue_end: bool,
 }
 
 #[derive(Debug)]
@@ -94,6 +95,7 @@ impl Unit {
             column_number: line.column,
             pos_in_unit: line_pos,
             is_stmt: line.is_stmt,
+            prologue_end: line.prologue_end,
             context: self,
         })
     }
@@ -108,20 +110,75 @@ impl Unit {
         self.find_place(pos)
     }
 
-    pub fn find_stmt_line(&self, file: &str, line: u64) -> Option<Place<'_>> {
-        let found = self
+    /// Return places of all code instances of the source line, one place (with the lowest
+    /// address) for each function or inlined subroutine containing this line.
+    pub fn find_stmt_lines(&self, file: &str, line: u64) -> Vec<Place<'_>> {
+        let file_indexes = self
             .files
             .iter()
             .enumerate()
-            .find(|(_, file_path)| file_path.ends_with(file))?;
+            .filter(|(_, file_path)| file_path.ends_with(file))
+            .map(|(idx, _)| idx as u64)
+            .collect::<Vec<_>>();
+        if file_indexes.is_empty() {
+            return vec![];
+        }
 
+        let mut found: Vec<(Option<usize>, usize)> = vec![];
         for (pos, line_row) in self.lines.iter().enumerate() {
-            if line_row.line == line && line_row.file_index == found.0 as u64 {
-                return self.find_place(pos);
+            if line_row.line == line
+                && line_row.is_stmt
+                && file_indexes.contains(&line_row.file_index)
+            {
+                let owner = self.innermost_subroutine(line_row.address);
+                if !found.iter().any(|(o, _)| *o == owner) {
+                    found.push((owner, pos));
+                }
+            }
+        }
+
+        found
+            .into_iter()
+            .filter_map(|(_, pos)| self.find_place(pos))
+            .collect()
+    }
+
+    /// Return source file path by its index in line program (like `DW_AT_call_file` value).
+    pub fn file(&self, index: u64) -> Option<&Path> {
+        self.files.get(index as usize).map(PathBuf::as_path)
+    }
+
+    /// Return indexes of inlined subroutine dies which code contains `pc`,
+    /// the most nested die first.
+    pub fn inlined_subroutines(&self, pc: u64) -> Vec<usize> {
+        let mut subroutines = vec![];
+        let mut mb_idx = self.innermost_subroutine(pc);
+        while let Some(idx) = mb_idx {
+            let entry = &self.entries[idx];
+            match entry.die {
+                DieVariant::InlineSubroutine(_) => subroutines.push(idx),
+                DieVariant::Function(_) => break,
+                _ => {}
             }
+            mb_idx = entry.node.parent;
         }
+        subroutines
+    }
 
-        None
+    /// Return index of the most nested function or inlined subroutine die which code contains `pc`.
+    fn innermost_subroutine(&self, pc: u64) -> Option<usize> {
+        self.die_ranges
+            .iter()
+            .filter(|dr| dr.range.begin <= pc && pc < dr.range.end)
+            .filter(|dr| {
+                matches!(
+                    self.entries[dr.die_idx].die,
+                    DieVariant::Function(_) | DieVariant::InlineSubroutine(_)
+                )
+            })
+            // nested dies always placed after its parents
+            .map(|dr| dr.die_idx)
+            .max()
     }
 
     pub fn find_entry(&self, offset: UnitOffset) -> Option<&Entry> {
@@ -136,6 +193,8 @@ pub struct Place<'a> {
     pub line_number: u64,
     pub pos_in_unit: usize,
     pub is_stmt: bool,
+    /// True if place is where function breakpoint should be set (end of function prologue).
+    pub prologue_end: bool,
     pub column_number: u64,
     context: &'a Unit,
 }
@@ -144,6 +203,33 @@ impl<'a> Place<'a> {
     pub fn next(&self) -> Option<Place<'a>> {
         self.context.find_place(self.pos_in_unit + 1)
     }
+
+    /// Return place where function prologue ends, `self` must be a first place of function
+    /// code and `high_pc` an end of this code.
+    /// Place marked with `prologue_end` flag is used if exists, otherwise the first statement
+    /// on a line other than the function entry line.
+    pub fn prologue_end(&self, high_pc: GlobalAddress) -> Option<Place<'a>> {
+        let mut body_stmt = None;
+        let mut mb_place = self.next();
+        while let Some(place) = mb_place {
+            if usize::from(place.address) >= usize::from(high_pc) {
+                break;
+            }
+            if place.prologue_end {
+                return Some(place);
+            }
+            let next = place.next();
+            if body_stmt.is_none()
+                && place.is_stmt
+                && place.address != self.address
+                && place.line_number != self.line_number
+            {
+                body_stmt = Some(place);
+            }
+            mb_place = next;
+        }
+        body_stmt
+    }
 }
 
 impl<'a> PartialEq for Place<'a> {
@@ -167,6 +253,23 @@ pub struct DieAttributes {
 pub struct FunctionDie {
     pub base_attributes: DieAttributes,
     pub fb_addr: Option<Attribute<EndianRcSlice>>,
+    pub linkage_name: Option<String>,
+    /// Set for concrete instances of inlined functions, refers to die with function name.
+    pub abstract_origin: Option<DieRef>,
+    /// Set for definitions of methods, refers to method declaration die with function name.
+    pub specification: Option<DieRef>,
+    /// Return type, `None` if function returns nothing.
+    pub type_ref: Option<DieRef>,
+}
+
+#[derive(Debug)]
+pub struct InlineSubroutineDie {
+    pub base_attributes: DieAttributes,
+    /// Refers to die of inlined function.
+    pub abstract_origin: Option<DieRef>,
+    /// Index of call site source file in unit files.
+    pub call_file: Option<u64>,
+    pub call_line: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -298,6 +401,7 @@ pub enum DieVariant {
     TemplateType(TemplateTypeParameter),
     Namespace(Namespace),
     Parameter(ParameterDie),
+    InlineSubroutine(InlineSubroutineDie),
 }
 
 #[derive(Debug)]
@@ -323,3 +427,78 @@ impl Entry {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gimli::Format;
+
+    fn unit(lines: &[(u64, u64, bool, bool)]) -> Unit {
+        Unit {
+            id: Uuid::nil(),
+            properties: UnitProperties {
+                encoding: Encoding {
+                    format: Format::Dwarf32,
+                    version: 4,
+                    address_size: 8,
+                },
+                offset: None,
+                low_pc: 0,
+                addr_base: DebugAddrBase(0),
+                loclists_base: DebugLocListsBase(0),
+                address_size: 8,
+            },
+            files: vec![PathBuf::from("/src/main.rs")],
+            lines: lines
+                .iter()
+                .map(|&(address, line, is_stmt, prologue_end)| LineRow {
+                    address,
+                    file_index: 0,
+                    line,
+                    column: 0,
+                    is_stmt,
+                    prologue_end,
+                })
+                .collect(),
+            ranges: vec![],
+            entries: vec![],
+            die_ranges: vec![],
+            name: None,
+            variable_index: HashMap::new(),
+            die_offsets_index: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_prologue_end() {
+        let high_pc = GlobalAddress::from(0x30usize);
+
+        // without `prologue_end` flag first statement on a line other than entry line is used,
+        // not a statement that repeats the entry line
+        let unit_without_flag = unit(&[
+            (0x10, 5, true, false),
+            (0x14, 5, false, false),
+            (0x18, 5, true, false),
+            (0x20, 6, true, false),
+            (0x28, 7, true, false),
+        ]);
+        let entry = unit_without_flag.find_place(0).unwrap();
+        let body = entry.prologue_end(high_pc).unwrap();
+        assert_eq!(body.address, GlobalAddress::from(0x20usize));
+        assert_eq!(body.line_number, 6);
+
+        let unit_with_flag = unit(&[
+            (0x10, 5, true, false),
+            (0x18, 5, true, true),
+            (0x20, 6, true, false),
+        ]);
+        let entry = unit_with_flag.find_place(0).unwrap();
+        let body = entry.prologue_end(high_pc).unwrap();
+        assert_eq!(body.address, GlobalAddress::from(0x18usize));
+
+        // statements after `high_pc` belongs to another function
+        let unit_single_line = unit(&[(0x10, 5, true, false), (0x30, 6, true, false)]);
+        let entry = unit_single_line.find_place(0).unwrap();
+        assert!(entry.prologue_end(high_pc).is_none());
+    }
+}
@@ -44,3 +44,48 @@ impl SymbolTab {
         })
     }
 }
+
+/// Function symbol of object file.
+#[derive(Debug)]
+pub struct FunctionSymbol {
+    pub name: String,
+    pub addr: u64,
+    pub size: u64,
+}
+
+/// Function symbols from `.symtab` and `.dynsym` sorted by address,
+/// used for symbolization of code without debug information.
+#[derive(Debug, Default)]
+pub(super) struct FunctionSymbols(Vec<FunctionSymbol>);
+
+impl FunctionSymbols {
+    pub(super) fn new<'data, 'file, OBJ>(object_file: &'data OBJ) -> Self
+    where
+        'data: 'file,
+        OBJ: Object<'data, 'file>,
+    {
+        let mut functions: Vec<_> = object_file
+            .symbols()
+            .chain(object_file.dynamic_symbols())
+            .filter(|symbol| symbol.kind() == SymbolKind::Text && symbol.address() != 0)
+            .filter_map(|symbol| {
+                Some(FunctionSymbol {
+                    name: symbol.name().ok()?.to_string(),
+                    addr: symbol.address(),
+                    size: symbol.size(),
+                })
+            })
+            .collect();
+        functions.sort_by_key(|f| f.addr);
+        functions.dedup_by_key(|f| f.addr);
+        Self(functions)
+    }
+
+    /// Return function which code contains `addr`, symbols without size
+    /// are assumed to extend to the next symbol.
+    pub(super) fn find(&self, addr: u64) -> Option<&FunctionSymbol> {
+        let pos = self.0.partition_point(|f| f.addr <= addr);
+        let func = self.0.get(pos.checked_sub(1)?)?;
+        (func.size == 0 || addr < func.addr + func.size).then_some(func)
+    }
+}
@@ -1,15 +1,18 @@
 use crate::debugger::address::{GlobalAddress, RelocatedAddress};
+use crate::debugger::debugee::debug_registers::DebugRegisters;
 use crate::debugger::debugee::thread::{ThreadCtl, TraceeStatus};
 use crate::debugger::register::Register;
+use crate::debugger::signal_policy::SignalPolicy;
 use crate::debugger::{code, register};
 use anyhow::bail;
 use log::warn;
 use nix::errno::Errno;
 use nix::libc::{pid_t, siginfo_t};
 use nix::sys::signal::Signal;
-use nix::sys::wait::{waitpid, WaitStatus};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
 use nix::{libc, sys};
+use std::collections::HashSet;
 
 #[derive(Clone, Copy, Debug)]
 pub enum DebugeeEvent {
@@ -23,10 +26,44 @@ pub enum DebugeeEvent {
     AtEntryPoint(Pid),
     /// Debugee stopped at breakpoint
     Breakpoint(Pid, RelocatedAddress),
+    /// Debugee stopped at hardware watchpoint, contains debug register index
+    Watchpoint(Pid, usize),
     /// Debugee stopped with Errno::ESRCH
     NoSuchProcess(Pid),
     /// Debugee stopped with OS signal
     OsSignal(siginfo_t, Pid),
+    /// Debugee received OS signal which must be reported without stop,
+    /// the thread that received signal continues at next tick
+    OsSignalNoStop(siginfo_t, Pid),
+    /// Debugee thread forks new process, contains thread id, new process id and true if
+    /// process created by vfork (it shares memory with debugee until exec or exit),
+    /// new process is traced and stopped
+    Fork(Pid, Pid, bool),
+    /// Already started debugee executes new program, contains process id
+    Exec(Pid),
+}
+
+/// Threads continued by debugger when debugee resumes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resume {
+    /// All stopped threads.
+    #[default]
+    All,
+    /// Only thread in focus, other stopped threads stay stopped.
+    /// If thread in focus exited all stopped threads continued.
+    Focused,
+}
+
+/// Which threads may run while user steps or continues debugee.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchedulerLocking {
+    /// All threads run.
+    #[default]
+    Off,
+    /// Only thread in focus runs while stepping (`next`, `finish`).
+    Step,
+    /// Only thread in focus runs.
+    On,
 }
 
 pub struct ControlFlow {
@@ -34,6 +71,16 @@ pub struct ControlFlow {
     program_ep: GlobalAddress,
     /// debugee process threads.
     pub(super) threads_ctl: ThreadCtl,
+    /// Actions for signals received by debugee.
+    pub signal_policy: SignalPolicy,
+    /// True if debugee program already started (first exec happened).
+    started: bool,
+    /// New threads or processes which stop reported before event of their creation.
+    unknown_stopped: HashSet<Pid>,
+    /// Threads continued on next tick.
+    pub resume: Resume,
+    /// If true, only thread that receives a trap or a signal stops, other threads keep running.
+    pub non_stop: bool,
 }
 
 impl ControlFlow {
@@ -41,12 +88,97 @@ impl ControlFlow {
         Self {
             program_ep,
             threads_ctl: ThreadCtl::new(proc_pid),
+            signal_policy: SignalPolicy::default(),
+            started: false,
+            unknown_stopped: HashSet::new(),
+            resume: Resume::All,
+            non_stop: false,
+        }
+    }
+
+    /// Create control flow for already running process, all process threads will be stopped.
+    pub fn attach(proc_pid: Pid, program_ep: GlobalAddress) -> anyhow::Result<Self> {
+        Ok(Self {
+            program_ep,
+            threads_ctl: ThreadCtl::attach(proc_pid)?,
+            signal_policy: SignalPolicy::default(),
+            started: true,
+            unknown_stopped: HashSet::new(),
+            resume: Resume::All,
+            non_stop: false,
+        })
+    }
+
+    /// Create control flow for a started process with single stopped thread
+    /// (process created by fork or process that executes new program).
+    pub fn started(proc_pid: Pid, program_ep: GlobalAddress, signal_policy: SignalPolicy) -> Self {
+        Self {
+            program_ep,
+            threads_ctl: ThreadCtl::new(proc_pid),
+            signal_policy,
+            started: true,
+            unknown_stopped: HashSet::new(),
+            resume: Resume::All,
+            non_stop: false,
+        }
+    }
+
+    /// Create control flow for process `child` forked by debugee.
+    pub fn fork(&self, child: Pid) -> Self {
+        Self {
+            non_stop: self.non_stop,
+            ..Self::started(child, self.program_ep, self.signal_policy.clone())
+        }
+    }
+
+    /// Return debugee entry point address.
+    pub fn program_ep(&self) -> GlobalAddress {
+        self.program_ep
+    }
+
+    /// Create control flow for debugee process which executes new program,
+    /// only one thread survives the exec.
+    pub fn exec(&self, program_ep: GlobalAddress) -> Self {
+        Self {
+            non_stop: self.non_stop,
+            ..Self::started(
+                self.threads_ctl.proc_pid(),
+                program_ep,
+                self.signal_policy.clone(),
+            )
         }
     }
 
     pub fn tick(&mut self, mapping_offset: Option<usize>) -> anyhow::Result<DebugeeEvent> {
         loop {
-            self.threads_ctl.cont_stopped()?;
+            let focus = self.threads_ctl.thread_in_focus();
+            let focused = self.resume == Resume::Focused
+                && self.threads_ctl.status(focus) == TraceeStatus::Stopped;
+
+            // breakpoint hit while thread was interrupted reported before debugee continues
+            if let Some(pid) = self
+                .threads_ctl
+                .take_queued_breakpoint(focused.then_some(focus))
+            {
+                return self.breakpoint_event(pid, mapping_offset);
+            }
+
+            // signal received while thread was interrupted handled before debugee continues
+            if let Some((pid, signal, info)) = self
+                .threads_ctl
+                .take_queued_signal(focused.then_some(focus))
+            {
+                match self.signal_event(pid, signal, info)? {
+                    Some(event) => return Ok(event),
+                    None => continue,
+                }
+            }
+
+            if focused {
+                self.threads_ctl.cont(focus)?;
+            } else {
+                self.threads_ctl.cont_stopped()?;
+            }
 
             let status = waitpid(Pid::from_raw(-1), None)?;
 
@@ -62,10 +194,14 @@ impl ControlFlow {
                 WaitStatus::PtraceEvent(pid, _, code) => {
                     match code {
                         libc::PTRACE_EVENT_EXEC => {
-                            // fire just before debugee start
-                            // cause currently `fork()` in debugee is unsupported we expect this code calling once
+                            // fire just before debugee start, or when started debugee executes
+                            // new program, in this case only one thread survives
                             self.threads_ctl
                                 .set_stop_status(self.threads_ctl.proc_pid());
+                            if self.started {
+                                return Ok(DebugeeEvent::Exec(self.threads_ctl.proc_pid()));
+                            }
+                            self.started = true;
                             return Ok(DebugeeEvent::DebugeeStart);
                         }
                         libc::PTRACE_EVENT_CLONE => {
@@ -73,13 +209,36 @@ impl ControlFlow {
                             let tid = Pid::from_raw(sys::ptrace::getevent(pid)? as pid_t);
                             self.threads_ctl.set_stop_status(pid);
                             self.threads_ctl.register(tid);
+                            if self.unknown_stopped.remove(&tid) {
+                                self.threads_ctl.set_stop_status(tid);
+                            }
+                        }
+                        libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK => {
+                            // fire right after new process created
+                            let child = Pid::from_raw(sys::ptrace::getevent(pid)? as pid_t);
+                            if !self.unknown_stopped.remove(&child) {
+                                // new process starts with PTRACE_EVENT_STOP
+                                waitpid(child, Some(WaitPidFlag::__WALL))?;
+                            }
+                            self.threads_ctl.set_stop_status(pid);
+                            let vfork = code == libc::PTRACE_EVENT_VFORK;
+                            return Ok(DebugeeEvent::Fork(pid, child, vfork));
                         }
                         libc::PTRACE_EVENT_STOP => {
                             // fire right after new thread started or PTRACE_INTERRUPT called.
                             // Also PTRACE_INTERRUPT handle by ::stop_threads.
                             if self.threads_ctl.status(pid) == TraceeStatus::Created {
+                                // debug registers are not inherited by new threads
+                                if let Err(e) = self.threads_ctl.apply_debug_registers_to(pid) {
+                                    warn!("set debug registers for thread {pid}: {e}");
+                                }
                                 self.threads_ctl.set_stop_status(pid);
-                                self.threads_ctl.cont_stopped()?;
+                                // new thread stays stopped if only thread in focus may run
+                                if self.resume == Resume::All || self.non_stop {
+                                    self.threads_ctl.cont(pid)?;
+                                }
+                            } else if self.threads_ctl.status(pid) == TraceeStatus::OutOfReach {
+                                self.unknown_stopped.insert(pid);
                             } else {
                                 self.threads_ctl.set_stop_status(pid);
                             }
@@ -87,7 +246,7 @@ impl ControlFlow {
                         libc::PTRACE_EVENT_EXIT => {
                             // Stop the tracee at exit
                             self.threads_ctl.set_stop_status(pid);
-                            self.threads_ctl.cont_stopped()?;
+                            self.threads_ctl.cont(pid)?;
                             self.threads_ctl.remove(pid);
                         }
                         _ => {
@@ -106,29 +265,26 @@ impl ControlFlow {
                     return match signal {
                         Signal::SIGTRAP => match info.si_code {
                             code::TRAP_TRACE => Ok(DebugeeEvent::TrapTrace),
-                            code::TRAP_BRKPT | code::SI_KERNEL => {
+                            code::TRAP_HWBKPT => {
                                 self.threads_ctl.set_thread_to_focus(pid);
                                 self.threads_ctl.set_stop_status(pid);
-                                self.threads_ctl.interrupt_running()?;
+                                self.stop_other_threads()?;
 
-                                self.set_thread_pc(pid, u64::from(self.thread_pc(pid)?) - 1)?;
-                                let current_pc = self.thread_pc(pid)?;
-                                let offset_pc = current_pc.into_global(mapping_offset.unwrap());
-                                if offset_pc == self.program_ep {
-                                    Ok(DebugeeEvent::AtEntryPoint(pid))
-                                } else {
-                                    Ok(DebugeeEvent::Breakpoint(pid, current_pc))
+                                match DebugRegisters::take_triggered(pid)? {
+                                    Some(slot) => Ok(DebugeeEvent::Watchpoint(pid, slot)),
+                                    None => bail!("hardware breakpoint triggered in unknown slot"),
                                 }
                             }
+                            code::TRAP_BRKPT | code::SI_KERNEL => {
+                                self.set_thread_pc(pid, u64::from(self.thread_pc(pid)?) - 1)?;
+                                self.breakpoint_event(pid, mapping_offset)
+                            }
                             code => bail!("unexpected SIGTRAP code {code}"),
                         },
-                        _ => {
-                            self.threads_ctl.set_thread_to_focus(pid);
-                            self.threads_ctl.set_stop_status(pid);
-                            self.threads_ctl.interrupt_running()?;
-
-                            Ok(DebugeeEvent::OsSignal(info, pid))
-                        }
+                        _ => match self.signal_event(pid, signal, info)? {
+                            Some(event) => Ok(event),
+                            None => continue,
+                        },
                     };
                 }
                 _ => {
@@ -138,6 +294,60 @@ impl ControlFlow {
         }
     }
 
+    /// Handle `signal` received by thread `pid` according to signal policy.
+    /// Return None if signal must not be reported.
+    fn signal_event(
+        &mut self,
+        pid: Pid,
+        signal: Signal,
+        info: siginfo_t,
+    ) -> anyhow::Result<Option<DebugeeEvent>> {
+        let action = self.signal_policy.action(signal);
+        self.threads_ctl.set_stop_status(pid);
+        if action.pass {
+            self.threads_ctl.set_pending_signal(pid, Some(signal));
+        }
+
+        if !action.stop {
+            if action.print {
+                return Ok(Some(DebugeeEvent::OsSignalNoStop(info, pid)));
+            }
+            return Ok(None);
+        }
+
+        self.threads_ctl.set_thread_to_focus(pid);
+        self.stop_other_threads()?;
+
+        Ok(Some(DebugeeEvent::OsSignal(info, pid)))
+    }
+
+    /// Stop debugee at breakpoint hit by thread `pid`, thread PC must be at breakpoint address.
+    fn breakpoint_event(
+        &mut self,
+        pid: Pid,
+        mapping_offset: Option<usize>,
+    ) -> anyhow::Result<DebugeeEvent> {
+        self.threads_ctl.set_thread_to_focus(pid);
+        self.threads_ctl.set_stop_status(pid);
+        self.stop_other_threads()?;
+
+        let current_pc = self.thread_pc(pid)?;
+        let offset_pc = current_pc.into_global(mapping_offset.unwrap());
+        if offset_pc == self.program_ep {
+            Ok(DebugeeEvent::AtEntryPoint(pid))
+        } else {
+            Ok(DebugeeEvent::Breakpoint(pid, current_pc))
+        }
+    }
+
+    /// Interrupt running threads, in non-stop mode other threads keep running.
+    fn stop_other_threads(&mut self) -> anyhow::Result<()> {
+        if !self.non_stop {
+            self.threads_ctl.interrupt_running()?;
+        }
+        Ok(())
+    }
+
     pub fn thread_pc(&self, tid: Pid) -> nix::Result<RelocatedAddress> {
         register::get_register_value(tid, Register::Rip).map(RelocatedAddress::from)
     }
@@ -146,17 +356,34 @@ impl ControlFlow {
         register::set_register_value(tid, Register::Rip, value)
     }
 
-    pub fn thread_step(tid: Pid) -> anyhow::Result<()> {
-        sys::ptrace::step(tid, None)?;
-        let _status = waitpid(tid, None)?;
-        debug_assert!({
-            // assert TRAP_TRACE code
-            let info = sys::ptrace::getsiginfo(tid);
-            matches!(WaitStatus::Stopped, _status)
-                && info
-                    .map(|info| info.si_code == code::TRAP_TRACE)
-                    .unwrap_or(false)
-        });
-        Ok(())
+    /// Execute single instruction in thread `tid`.
+    /// Signal received by the thread before the step is re-sent to it,
+    /// so it will be reported when debugee continues.
+    /// Return debug register index if hardware watchpoint triggered by the instruction.
+    pub fn thread_step(tid: Pid) -> anyhow::Result<Option<usize>> {
+        let mut delayed_signals = vec![];
+        loop {
+            sys::ptrace::step(tid, None)?;
+            match waitpid(tid, None)? {
+                WaitStatus::Stopped(_, signal) if signal != Signal::SIGTRAP => {
+                    delayed_signals.push(signal);
+                }
+                _status => {
+                    debug_assert!({
+                        // assert TRAP_TRACE code
+                        let info = sys::ptrace::getsiginfo(tid);
+                        matches!(WaitStatus::Stopped, _status)
+                            && info
+                                .map(|info| info.si_code == code::TRAP_TRACE)
+                                .unwrap_or(false)
+                    });
+                    break;
+                }
+            }
+        }
+
+        ThreadCtl::redeliver_signals(tid, &delayed_signals)?;
+        // watchpoint triggered by single step reported as TRAP_TRACE, only DR6 knows about it
+        Ok(DebugRegisters::take_triggered(tid)?)
     }
 }
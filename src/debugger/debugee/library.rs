@@ -0,0 +1,93 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::dwarf::{DebugeeContext, DebugeeContextBuilder, EndianRcSlice};
+use crate::debugger::debugee::rendezvous::LinkMap;
+use anyhow::anyhow;
+use nix::unistd::Pid;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Shared object loaded into debugee address space by dynamic linker.
+#[derive(Clone)]
+pub struct SharedLibrary {
+    /// Absolute path of object file.
+    pub path: PathBuf,
+    /// Object name from `link_map` structure.
+    link_map_name: String,
+    /// Address of object `link_map` structure.
+    link_map_addr: RelocatedAddress,
+    /// Difference between addresses in the object file and addresses in memory.
+    pub load_bias: usize,
+    /// Debugee memory where object is mapped.
+    pub mapped: Range<usize>,
+    /// Preparsed object dwarf.
+    pub dwarf: Rc<DebugeeContext<EndianRcSlice>>,
+}
+
+impl SharedLibrary {
+    /// Load object described by `link_map` of process `pid`.
+    pub(super) fn load(pid: Pid, link_map: &LinkMap) -> anyhow::Result<Self> {
+        // name may be relative to the debugee working directory (if `dlopen` called with it)
+        let path = PathBuf::from(format!("/proc/{pid}/cwd"))
+            .join(&link_map.name)
+            .canonicalize()?;
+
+        let mapped = proc_maps::get_process_maps(pid.as_raw())?
+            .into_iter()
+            .filter(|map| map.filename() == Some(path.as_path()))
+            .map(|map| map.start()..map.start() + map.size())
+            .reduce(|r1, r2| r1.start.min(r2.start)..r1.end.max(r2.end))
+            .ok_or_else(|| anyhow!("mapping of {} not found", path.display()))?;
+
+        let file = fs::File::open(&path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let object = object::File::parse(&*mmap)?;
+        let dwarf = DebugeeContextBuilder::default().build(&path, &object)?;
+
+        Ok(Self {
+            path,
+            link_map_name: link_map.name.clone(),
+            link_map_addr: link_map.addr,
+            load_bias: link_map.load_bias,
+            mapped,
+            dwarf: Rc::new(dwarf),
+        })
+    }
+
+    /// Return true if `link_map` describes this object.
+    pub(super) fn is_described_by(&self, link_map: &LinkMap) -> bool {
+        self.link_map_addr == link_map.addr && self.link_map_name == link_map.name
+    }
+
+    /// Return true if object file name is `name`, version suffix is optional,
+    /// so `libfoo.so` matches `libfoo.so.1`.
+    pub fn is(&self, name: &str) -> bool {
+        let Some(file_name) = self.path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        file_name
+            .strip_prefix(name)
+            .map(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+            .unwrap_or(false)
+    }
+
+    /// Return address of object `link_map` structure.
+    pub fn link_map_addr(&self) -> RelocatedAddress {
+        self.link_map_addr
+    }
+
+    /// Return true if address belongs to object memory.
+    pub fn contains(&self, addr: RelocatedAddress) -> bool {
+        self.mapped.contains(&usize::from(addr))
+    }
+}
+
+/// Result of shared objects list update.
+#[derive(Default)]
+pub struct LibrariesUpdate {
+    /// Indexes of new objects in debugee libraries list.
+    pub loaded: Range<usize>,
+    /// Objects removed from debugee address space.
+    pub unloaded: Vec<SharedLibrary>,
+}
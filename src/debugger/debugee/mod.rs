@@ -1,6 +1,8 @@
 use crate::debugger::address::{GlobalAddress, RelocatedAddress};
-use crate::debugger::debugee::dwarf::{DebugeeContext, EndianRcSlice};
+use crate::debugger::debugee::dwarf::parser::unit::Place;
+use crate::debugger::debugee::dwarf::{DebugeeContext, EndianRcSlice, RegisterDump};
 use crate::debugger::debugee::flow::{ControlFlow, DebugeeEvent};
+use crate::debugger::debugee::library::{LibrariesUpdate, SharedLibrary};
 use crate::debugger::debugee::rendezvous::Rendezvous;
 use crate::debugger::debugee::thread::{ThreadCtl, TraceeThread};
 use crate::debugger::register::Register;
@@ -13,16 +15,23 @@ use nix::unistd::Pid;
 use object::{Object, ObjectSection};
 use proc_maps::MapRange;
 use std::collections::HashMap;
+use std::fs;
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
+pub mod debug_registers;
 pub mod dwarf;
 pub mod flow;
+pub mod library;
 mod rendezvous;
 pub mod thread;
 
 /// Stack frame information.
 #[derive(Debug, Default, Clone)]
 pub struct FrameInfo {
+    /// Frame number, frame 0 is the innermost frame.
+    pub num: usize,
     pub base_addr: RelocatedAddress,
     /// CFA is defined to be the value of the stack  pointer at the call site in the previous frame
     /// (which may be different from its value on entry to the current frame).
@@ -30,8 +39,29 @@ pub struct FrameInfo {
     pub return_addr: Option<RelocatedAddress>,
 }
 
+/// Thread stack frame restored by unwinding, frame 0 is the innermost frame.
+#[derive(Clone)]
+pub struct StackFrame {
+    pub num: usize,
+    /// Instruction pointer: current pc for the innermost frame, return address
+    /// (or address of interrupted instruction) for caller frames.
+    pub ip: RelocatedAddress,
+    /// Frame location, for caller frames `pc` points into call instruction
+    /// (return address minus one), so it belongs to the calling function and line.
+    pub location: Location,
+    /// Canonical frame address.
+    pub cfa: RelocatedAddress,
+    /// Frame registers, only callee-saved registers are known for caller frames
+    /// (except frames interrupted by signal).
+    pub registers: RegisterDump,
+    /// True if frame is a signal return trampoline (like `__restore_rt`).
+    pub signal_frame: bool,
+}
+
 pub struct ThreadDump {
     pub thread: TraceeThread,
+    /// Thread name (from `/proc/<pid>/task/<tid>/comm`).
+    pub name: Option<String>,
     pub pc: Option<RelocatedAddress>,
     pub bt: Option<Backtrace>,
     pub in_focus: bool,
@@ -62,13 +92,17 @@ pub struct Debugee {
     /// debugee process map address.
     pub mapping_addr: Option<usize>,
     /// preparsed debugee dwarf.
-    pub dwarf: DebugeeContext<EndianRcSlice>,
+    pub dwarf: Rc<DebugeeContext<EndianRcSlice>>,
     /// debugee control flow
     pub control_flow: ControlFlow,
     /// elf file sections (name => address).
     object_sections: HashMap<String, u64>,
     /// rendezvous struct maintained by dyn linker.
     rendezvous: Option<Rendezvous>,
+    /// shared objects loaded by dyn linker.
+    libraries: Vec<SharedLibrary>,
+    /// true if debugee is an already running process which debugger attached to.
+    pub attached: bool,
 }
 
 impl Debugee {
@@ -86,16 +120,109 @@ impl Debugee {
             execution_status: ExecutionStatus::Unload,
             path: path.into(),
             mapping_addr: None,
-            dwarf: dwarf_builder.build(object)?,
+            dwarf: Rc::new(dwarf_builder.build(path, object)?),
             control_flow: ControlFlow::new(proc, GlobalAddress::from(object.entry() as usize)),
             object_sections: object
                 .sections()
                 .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
                 .collect(),
             rendezvous: None,
+            libraries: vec![],
+            attached: false,
         })
     }
 
+    /// Create debugee from already running process. Process must be stopped (all threads seized)
+    /// and all runtime information (mapping offset, rendezvous, libthread_db) evaluated immediately.
+    pub fn new_attached<'a, 'b, OBJ>(
+        path: &Path,
+        proc: Pid,
+        object: &'a OBJ,
+    ) -> anyhow::Result<Self>
+    where
+        'a: 'b,
+        OBJ: Object<'a, 'b>,
+    {
+        let dwarf_builder = dwarf::DebugeeContextBuilder::default();
+        let mut debugee = Self {
+            execution_status: ExecutionStatus::InProgress,
+            path: path.into(),
+            mapping_addr: None,
+            dwarf: Rc::new(dwarf_builder.build(path, object)?),
+            control_flow: ControlFlow::attach(proc, GlobalAddress::from(object.entry() as usize))?,
+            object_sections: object
+                .sections()
+                .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
+                .collect(),
+            rendezvous: None,
+            libraries: vec![],
+            attached: true,
+        };
+
+        debugee.mapping_addr = Some(debugee.define_mapping_addr()?);
+        debugee.rendezvous = Some(Rendezvous::new(
+            proc,
+            debugee.mapping_offset(),
+            &debugee.object_sections,
+        )?);
+        debugee.init_libthread_db();
+
+        Ok(debugee)
+    }
+
+    /// Create debugee from process `child` forked by this debugee. Forked process must be
+    /// stopped, it has the same memory layout, so debug information and libraries are shared.
+    pub fn fork(&self, child: Pid) -> anyhow::Result<Self> {
+        let mut debugee = Self {
+            execution_status: ExecutionStatus::InProgress,
+            path: self.path.clone(),
+            mapping_addr: self.mapping_addr,
+            dwarf: self.dwarf.clone(),
+            control_flow: self.control_flow.fork(child),
+            object_sections: self.object_sections.clone(),
+            rendezvous: None,
+            libraries: self.libraries.clone(),
+            attached: self.attached,
+        };
+        if self.rendezvous.is_some() {
+            debugee.rendezvous = Some(Rendezvous::new(
+                child,
+                debugee.mapping_offset(),
+                &debugee.object_sections,
+            )?);
+            debugee.init_libthread_db();
+        }
+        Ok(debugee)
+    }
+
+    /// Create debugee for new program (located at `path`) executed by this debugee process.
+    /// Program runtime information evaluated when execution reaches program entry point.
+    pub fn exec<'a, 'b, OBJ>(&self, path: &Path, object: &'a OBJ) -> anyhow::Result<Self>
+    where
+        'a: 'b,
+        OBJ: Object<'a, 'b>,
+    {
+        let dwarf_builder = dwarf::DebugeeContextBuilder::default();
+        let mut debugee = Self {
+            execution_status: ExecutionStatus::InProgress,
+            path: path.into(),
+            mapping_addr: None,
+            dwarf: Rc::new(dwarf_builder.build(path, object)?),
+            control_flow: self
+                .control_flow
+                .exec(GlobalAddress::from(object.entry() as usize)),
+            object_sections: object
+                .sections()
+                .filter_map(|section| Some((section.name().ok()?.to_string(), section.address())))
+                .collect(),
+            rendezvous: None,
+            libraries: vec![],
+            attached: self.attached,
+        };
+        debugee.mapping_addr = Some(debugee.define_mapping_addr()?);
+        Ok(debugee)
+    }
+
     /// Return debugee process mapping offset.
     /// This method will panic if called before debugee started,
     /// calling a method on time is the responsibility of the caller.
@@ -111,6 +238,95 @@ impl Debugee {
         self.rendezvous.as_ref().expect("rendezvous must exists")
     }
 
+    /// Return address of dynamic linker function called on shared objects loading or unloading,
+    /// `None` if rendezvous not evaluated yet.
+    pub fn rendezvous_brk(&self) -> Option<RelocatedAddress> {
+        self.rendezvous.as_ref().map(Rendezvous::brk)
+    }
+
+    /// Return shared objects loaded into debugee address space.
+    pub fn libraries(&self) -> &[SharedLibrary] {
+        &self.libraries
+    }
+
+    /// Return shared object which code or data mapped at `addr`.
+    pub fn library_at(&self, addr: RelocatedAddress) -> Option<&SharedLibrary> {
+        self.libraries.iter().find(|lib| lib.contains(addr))
+    }
+
+    /// Return debug information of object (executable or shared library) mapped at `pc`.
+    pub fn debug_info(&self, pc: RelocatedAddress) -> &DebugeeContext<EndianRcSlice> {
+        self.library_at(pc)
+            .map(|lib| &lib.dwarf)
+            .unwrap_or(&self.dwarf)
+    }
+
+    /// Return mapping offset of object (executable or shared library) mapped at `pc`.
+    /// This method will panic if called before debugee started.
+    pub fn mapping_offset_for_pc(&self, pc: RelocatedAddress) -> usize {
+        self.library_at(pc)
+            .map(|lib| lib.load_bias)
+            .unwrap_or_else(|| self.mapping_offset())
+    }
+
+    /// Return address of `link_map` structure of object mapped at `pc`.
+    pub fn link_map_for_pc(&self, pc: RelocatedAddress) -> RelocatedAddress {
+        self.library_at(pc)
+            .map(|lib| lib.link_map_addr())
+            .unwrap_or_else(|| self.rendezvous().link_map_main())
+    }
+
+    /// Return place in source code of instruction at `pc`, place searched in debug information
+    /// of object which contains this instruction.
+    pub fn find_place_at(&self, pc: RelocatedAddress) -> Option<Place<'_>> {
+        self.debug_info(pc)
+            .find_place_from_pc(pc.into_global(self.mapping_offset_for_pc(pc)))
+    }
+
+    /// Re-read list of loaded shared objects from rendezvous structure, debug information
+    /// of new objects loaded immediately. Nothing changes if the dynamic linker
+    /// is in the middle of list update.
+    pub fn update_libraries(&mut self) -> anyhow::Result<LibrariesUpdate> {
+        let rendezvous = self
+            .rendezvous
+            .as_mut()
+            .ok_or_else(|| anyhow!("rendezvous not found"))?;
+        rendezvous.refresh()?;
+        if !rendezvous.is_consistent() {
+            return Ok(LibrariesUpdate::default());
+        }
+        let link_maps = rendezvous.link_maps()?;
+
+        let mut update = LibrariesUpdate::default();
+        let (remain, unloaded) = mem::take(&mut self.libraries)
+            .into_iter()
+            .partition(|lib| link_maps.iter().any(|lm| lib.is_described_by(lm)));
+        self.libraries = remain;
+        update.unloaded = unloaded;
+
+        let start = self.libraries.len();
+        let pid = self.threads_ctl().proc_pid();
+        for lm in link_maps {
+            // skip main executable and virtual objects like vdso
+            if !lm.name.contains('/') {
+                continue;
+            }
+            if self.libraries.iter().any(|lib| lib.is_described_by(&lm)) {
+                continue;
+            }
+            match SharedLibrary::load(pid, &lm) {
+                Ok(lib) => {
+                    info!("shared library {} loaded", lib.path.display());
+                    self.libraries.push(lib);
+                }
+                Err(e) => warn!("load shared library {}: {e:#}", lm.name),
+            }
+        }
+        update.loaded = start..self.libraries.len();
+
+        Ok(update)
+    }
+
     fn init_libthread_db(&mut self) {
         match self.control_flow.threads_ctl.init_thread_db() {
             Ok(_) => {
@@ -152,6 +368,10 @@ impl Debugee {
         &self.control_flow.threads_ctl
     }
 
+    pub fn threads_ctl_mut(&mut self) -> &mut ThreadCtl {
+        &mut self.control_flow.threads_ctl
+    }
+
     fn define_mapping_addr(&mut self) -> anyhow::Result<usize> {
         let absolute_debugee_path_buf = self.path.canonicalize()?;
         let absolute_debugee_path = absolute_debugee_path_buf.as_path();
@@ -170,33 +390,51 @@ impl Debugee {
         Ok(lowest_map.start())
     }
 
-    pub fn frame_info(&self, location: Location) -> anyhow::Result<FrameInfo> {
-        let func = self
-            .dwarf
-            .find_function_by_pc(location.global_pc)
+    pub fn frame_info(&self, frame: &StackFrame) -> anyhow::Result<FrameInfo> {
+        let dwarf = self.debug_info(frame.location.pc);
+        let func = dwarf
+            .find_function_by_pc(frame.location.global_pc)
             .ok_or_else(|| anyhow!("current function not found"))?;
 
-        let base_addr = func.frame_base_addr(self, location.pid)?;
-
-        let cfa = self.dwarf.get_cfa(self, location)?;
+        let base_addr = func.frame_base_addr(self, frame)?;
 
         Ok(FrameInfo {
-            cfa,
+            num: frame.num,
+            cfa: frame.cfa,
             base_addr,
-            return_addr: uw::return_addr(location.pid)?,
+            return_addr: uw::caller_frame(self, frame)?.map(|caller| caller.ip),
         })
     }
 
+    /// Return stack frame number `num` of thread `tid`, caller frames are restored
+    /// by unwinding.
+    pub fn stack_frame(&self, tid: Pid, num: usize) -> anyhow::Result<StackFrame> {
+        let mut frame = uw::innermost_frame(self, tid)?;
+        while frame.num < num {
+            frame = uw::caller_frame(self, &frame)
+                .map_err(|e| anyhow!("frame {num} not found: {e}"))?
+                .ok_or_else(|| anyhow!("frame {num} not found"))?;
+        }
+        Ok(frame)
+    }
+
     pub fn thread_state(&self) -> anyhow::Result<Vec<ThreadDump>> {
         let threads = self.threads_ctl().dump();
         Ok(threads
             .into_iter()
             .map(|thread| {
                 let pc = weak_error!(register::get_register_value(thread.pid, Register::Rip));
-                let bt = weak_error!(uw::backtrace(thread.pid));
+                let bt = weak_error!(uw::backtrace(self, thread.pid));
+                let name = weak_error!(fs::read_to_string(format!(
+                    "/proc/{}/task/{}/comm",
+                    self.threads_ctl().proc_pid(),
+                    thread.pid
+                )))
+                .map(|comm| comm.trim_end().to_string());
                 ThreadDump {
                     in_focus: thread.pid == self.threads_ctl().thread_in_focus(),
                     thread,
+                    name,
                     pc: pc.map(RelocatedAddress::from),
                     bt,
                 }
@@ -217,7 +455,7 @@ impl Debugee {
         Ok(Location {
             pid: tid,
             pc,
-            global_pc: pc.into_global(self.mapping_offset()),
+            global_pc: pc.into_global(self.mapping_offset_for_pc(pc)),
         })
     }
 }
@@ -7,8 +7,11 @@ use object::elf::DT_DEBUG;
 use std::collections::HashMap;
 
 pub struct LinkMap {
-    addr: RelocatedAddress,
-    name: String,
+    pub addr: RelocatedAddress,
+    /// Absolute path of shared object, empty for main executable.
+    pub name: String,
+    /// Difference between addresses in the object file and addresses in memory.
+    pub load_bias: usize,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -25,6 +28,8 @@ pub enum RendezvousError {
 /// This structure maintains a list of shared library descriptors.
 pub struct Rendezvous {
     pid: Pid,
+    /// Address of `r_debug` structure in debugee memory.
+    addr: usize,
     inner: ffi::r_debug,
 }
 
@@ -46,10 +51,11 @@ impl Rendezvous {
 
         while val != 0 {
             if val == DT_DEBUG as usize {
-                let mut rend_addr = ffi::read_val::<usize>(proc_pid, &mut addr)?;
-                let rendezvous = ffi::read_val::<ffi::r_debug>(proc_pid, &mut rend_addr)?;
+                let rend_addr = ffi::read_val::<usize>(proc_pid, &mut addr)?;
+                let rendezvous = ffi::read_val::<ffi::r_debug>(proc_pid, &mut { rend_addr })?;
                 return Ok(Self {
                     pid: proc_pid,
+                    addr: rend_addr,
                     inner: rendezvous,
                 });
             }
@@ -60,10 +66,28 @@ impl Rendezvous {
         Err(RendezvousError::NotFound())
     }
 
+    /// Re-read rendezvous structure from debugee memory, must be called after the dynamic
+    /// linker changes the list of loaded objects.
+    pub fn refresh(&mut self) -> Result<(), RendezvousError> {
+        self.inner = ffi::read_val::<ffi::r_debug>(self.pid, &mut { self.addr })?;
+        Ok(())
+    }
+
     pub fn link_map_main(&self) -> RelocatedAddress {
         RelocatedAddress::from(self.inner.link_map as usize)
     }
 
+    /// Return address of the function which dynamic linker calls before and after
+    /// adding or removing shared objects.
+    pub fn brk(&self) -> RelocatedAddress {
+        RelocatedAddress::from(self.inner.r_brk)
+    }
+
+    /// Return true if the list of loaded objects is not in the middle of update.
+    pub fn is_consistent(&self) -> bool {
+        self.inner.r_state == ffi::RT_CONSISTENT
+    }
+
     pub fn link_maps(&self) -> Result<Vec<LinkMap>, RendezvousError> {
         let mut result = vec![];
         let mut next_link_map_addr = usize::from(self.link_map_main()) as *const libc::c_void;
@@ -75,6 +99,7 @@ impl Rendezvous {
             result.push(LinkMap {
                 addr: RelocatedAddress::from(next_link_map_addr as usize),
                 name,
+                load_bias: lm.l_addr as usize,
             });
 
             next_link_map_addr = lm.l_next;
@@ -95,11 +120,19 @@ mod ffi {
     use std::mem;
     use std::str::from_utf8;
 
+    /// `r_debug::r_state` value, the list of loaded objects is consistent.
+    pub(super) const RT_CONSISTENT: i32 = 0;
+
     #[repr(C)]
     #[derive(Clone, Copy, Debug)]
     pub(super) struct r_debug {
         pub(super) r_version: i32,
         pub(super) link_map: *const libc::c_void,
+        /// Address of function that dynamic linker calls on shared objects loading or unloading.
+        pub(super) r_brk: usize,
+        pub(super) r_state: i32,
+        /// Base address of the dynamic linker.
+        pub(super) r_ldbase: usize,
     }
 
     #[derive(Debug, Clone, Copy)]
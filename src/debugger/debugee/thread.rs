@@ -1,14 +1,21 @@
 use crate::debugger::address::RelocatedAddress;
+use crate::debugger::code;
+use crate::debugger::debugee::debug_registers::{DebugRegisters, HardwareBreakpoint};
 use crate::debugger::debugee::thread::TraceeStatus::{Created, Running, Stopped};
+use crate::debugger::register::{get_register_value, set_register_value, Register};
 use anyhow::{anyhow, bail};
 use itertools::Itertools;
 use log::warn;
 use nix::errno::Errno;
-use nix::sys::wait::{waitpid, WaitStatus};
+use nix::libc::{c_int, siginfo_t};
+use nix::sys::ptrace::Options;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
 use nix::{libc, sys};
 use ouroboros::self_referencing;
 use std::collections::HashMap;
+use std::fs;
 use thread_db;
 
 #[self_referencing]
@@ -29,15 +36,36 @@ pub enum TraceeStatus {
 
 #[derive(Clone, Debug)]
 pub struct TraceeThread {
+    /// Thread number, assigned in order of thread appearance, main thread has number 1.
+    pub number: u32,
     pub pid: Pid,
     pub status: TraceeStatus,
+    /// Signal which will be delivered to thread when it continues.
+    pub pending_signal: Option<Signal>,
+    /// True if thread hits a breakpoint while it was interrupted, thread PC is rewound
+    /// to breakpoint address and the hit is reported before debugee continues.
+    pub queued_breakpoint: bool,
+    /// Signal received by thread while it was interrupted, the signal is handled according
+    /// to signal policy before debugee continues.
+    pub queued_signal: Option<(Signal, siginfo_t)>,
+}
+
+/// Status of a thread after seize.
+enum SeizeStatus {
+    /// Thread stopped, signal received by thread before stop returned if any.
+    Stopped(Option<Signal>),
+    Exited,
 }
 
 pub struct ThreadCtl {
     process_pid: Pid,
     in_focus_tid: Pid,
     threads_state: HashMap<Pid, TraceeThread>,
+    /// Number of the last registered thread.
+    last_number: u32,
     thread_db_proc: Option<ThreadDBProcess>,
+    /// Debug registers state, same for all threads.
+    debug_registers: DebugRegisters,
 }
 
 impl ThreadCtl {
@@ -48,14 +76,126 @@ impl ThreadCtl {
             threads_state: HashMap::from([(
                 proc_pid,
                 TraceeThread {
+                    number: 1,
                     pid: proc_pid,
                     status: Stopped,
+                    pending_signal: None,
+                    queued_breakpoint: false,
+                    queued_signal: None,
                 },
             )]),
+            last_number: 1,
             thread_db_proc: None,
+            debug_registers: DebugRegisters::default(),
         }
     }
 
+    /// Seize all threads of already running process and stop them.
+    /// Thread list taken from `/proc/<pid>/task`, enumeration repeats until no new threads found,
+    /// cause the process may spawn new threads while the debugger attaching to it.
+    /// Threads seized without ptrace options, otherwise threads created during enumeration
+    /// will be attached by the kernel (through PTRACE_EVENT_CLONE) and seized again.
+    /// Options set when all threads are stopped and no more threads can be created.
+    pub fn attach(proc_pid: Pid) -> anyhow::Result<ThreadCtl> {
+        let mut threads_state = HashMap::new();
+
+        loop {
+            let mut new_threads = 0;
+            for task in fs::read_dir(format!("/proc/{proc_pid}/task"))? {
+                let tid = task?
+                    .file_name()
+                    .to_string_lossy()
+                    .parse::<libc::pid_t>()
+                    .map(Pid::from_raw)?;
+                if threads_state.contains_key(&tid) {
+                    continue;
+                }
+
+                let pending_signal = match Self::seize_and_stop(tid) {
+                    Ok(SeizeStatus::Stopped(signal)) => signal,
+                    // thread exited before it was seized or stopped
+                    Ok(SeizeStatus::Exited) | Err(Errno::ESRCH) => continue,
+                    Err(e) => return Err(anyhow::Error::from(e).context(format!("thread: {tid}"))),
+                };
+
+                threads_state.insert(
+                    tid,
+                    TraceeThread {
+                        number: 0,
+                        pid: tid,
+                        status: Stopped,
+                        pending_signal,
+                        queued_breakpoint: false,
+                        queued_signal: None,
+                    },
+                );
+                new_threads += 1;
+            }
+
+            if new_threads == 0 {
+                break;
+            }
+        }
+
+        if !threads_state.contains_key(&proc_pid) {
+            bail!("process {proc_pid} not found");
+        }
+
+        let mut exited = vec![];
+        for &tid in threads_state.keys() {
+            match sys::ptrace::setoptions(tid, Self::trace_options()) {
+                Ok(_) => {}
+                Err(Errno::ESRCH) => exited.push(tid),
+                Err(e) => return Err(anyhow::Error::from(e).context(format!("thread: {tid}"))),
+            }
+        }
+        exited.iter().for_each(|tid| _ = threads_state.remove(tid));
+
+        // main thread goes first, other threads numbered in order of their ids
+        let mut tids = threads_state.keys().copied().collect::<Vec<_>>();
+        tids.sort_by_key(|&tid| (tid != proc_pid, tid));
+        for (tid, number) in tids.iter().zip(1..) {
+            if let Some(thread) = threads_state.get_mut(tid) {
+                thread.number = number;
+            }
+        }
+
+        Ok(Self {
+            process_pid: proc_pid,
+            in_focus_tid: proc_pid,
+            last_number: threads_state.len() as u32,
+            threads_state,
+            thread_db_proc: None,
+            debug_registers: DebugRegisters::default(),
+        })
+    }
+
+    fn trace_options() -> Options {
+        Options::PTRACE_O_TRACECLONE
+            .union(Options::PTRACE_O_TRACEEXEC)
+            .union(Options::PTRACE_O_TRACEFORK)
+            .union(Options::PTRACE_O_TRACEVFORK)
+            .union(Options::PTRACE_O_TRACEEXIT)
+    }
+
+    /// Seize thread without ptrace options and wait until it stops.
+    fn seize_and_stop(tid: Pid) -> nix::Result<SeizeStatus> {
+        sys::ptrace::seize(tid, Options::empty())?;
+        sys::ptrace::interrupt(tid)?;
+        let status = match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+            WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_STOP) => SeizeStatus::Stopped(None),
+            // thread receive a signal before stop, signal delivered when thread
+            // continues, interrupt stop will be reported after that
+            WaitStatus::Stopped(_, signal) => SeizeStatus::Stopped(Some(signal)),
+            WaitStatus::Exited(_, _) | WaitStatus::Signaled(_, _, _) => SeizeStatus::Exited,
+            status => {
+                warn!("unexpected status of seized thread {tid}: {status:?}");
+                SeizeStatus::Stopped(None)
+            }
+        };
+        Ok(status)
+    }
+
     /// Return pid of debugee process main thread.
     pub fn proc_pid(&self) -> Pid {
         self.process_pid
@@ -75,9 +215,14 @@ impl ThreadCtl {
     /// `created` actual for ptrace events like PTRACE_EVENT_CLONE, when wee known about new thread but
     /// this not created yet.
     pub fn register(&mut self, pid: Pid) {
+        self.last_number += 1;
         let new = TraceeThread {
+            number: self.last_number,
             pid,
             status: Created,
+            pending_signal: None,
+            queued_breakpoint: false,
+            queued_signal: None,
         };
         self.threads_state.insert(pid, new);
     }
@@ -94,13 +239,31 @@ impl ThreadCtl {
         }
     }
 
-    /// Continue all currently stopped threads.
+    /// Send signals received by thread `tid` while debugger steps it back to the thread,
+    /// so they will be reported when debugee continues.
+    pub fn redeliver_signals(tid: Pid, signals: &[Signal]) -> nix::Result<()> {
+        for &signal in signals {
+            let res = unsafe { libc::syscall(libc::SYS_tkill, tid.as_raw(), signal as c_int) };
+            Errno::result(res)?;
+        }
+        Ok(())
+    }
+
+    /// Set signal which will be delivered to thread when it continues,
+    /// `None` means that thread continues without signal.
+    pub fn set_pending_signal(&mut self, pid: Pid, signal: Option<Signal>) {
+        if let Some(thread) = self.threads_state.get_mut(&pid) {
+            thread.pending_signal = signal
+        }
+    }
+
+    /// Continue all currently stopped threads, pending signals are delivered.
     pub fn cont_stopped(&mut self) -> Result<(), anyhow::Error> {
         let mut errors = vec![];
 
         self.threads_state.iter_mut().for_each(|(_, thread)| {
             if thread.status == Stopped {
-                if let Err(e) = sys::ptrace::cont(thread.pid, None) {
+                if let Err(e) = sys::ptrace::cont(thread.pid, thread.pending_signal) {
                     // if no such process - continue, it will be removed later, on PTRACE_EVENT_EXIT event.
                     if Errno::ESRCH == e {
                         warn!("thread {} not found, ESRCH", thread.pid);
@@ -109,7 +272,9 @@ impl ThreadCtl {
 
                     errors.push(anyhow::Error::from(e).context(format!("thread: {}", thread.pid)));
                 } else {
-                    thread.status = Running
+                    thread.status = Running;
+                    thread.pending_signal = None;
+                    thread.queued_breakpoint = false;
                 }
             }
         });
@@ -120,6 +285,27 @@ impl ThreadCtl {
         Ok(())
     }
 
+    /// Continue stopped thread `tid`, pending signal is delivered.
+    pub fn cont(&mut self, tid: Pid) -> anyhow::Result<()> {
+        let Some(thread) = self.threads_state.get_mut(&tid) else {
+            return Ok(());
+        };
+        if thread.status != Stopped {
+            return Ok(());
+        }
+        match sys::ptrace::cont(tid, thread.pending_signal) {
+            Ok(()) => {
+                thread.status = Running;
+                thread.pending_signal = None;
+                thread.queued_breakpoint = false;
+            }
+            // thread will be removed later, on PTRACE_EVENT_EXIT event
+            Err(Errno::ESRCH) => warn!("thread {tid} not found, ESRCH"),
+            Err(e) => return Err(anyhow::Error::from(e).context(format!("thread: {tid}"))),
+        }
+        Ok(())
+    }
+
     /// Interrupt all currently running threads.
     /// PTRACE_EVENT_STOP will happen.
     pub fn interrupt_running(&mut self) -> Result<(), anyhow::Error> {
@@ -151,8 +337,25 @@ impl ThreadCtl {
                         continue;
                     }
 
+                    // thread may receive a signal before stop, signal handled before debugee
+                    // continues, interrupt stop will be reported after that
+                    if let WaitStatus::Stopped(_, signal) = wait {
+                        let queued = if signal != Signal::SIGTRAP {
+                            self.queue_signal(need_assume, signal)
+                        } else {
+                            self.queue_breakpoint_hit(need_assume)
+                        };
+                        if let Err(e) = queued {
+                            errors.push(e.context(format!("thread: {}", need_assume)));
+                        }
+                    }
+
                     debug_assert!(
-                        matches!(wait, WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_STOP)),
+                        matches!(
+                            wait,
+                            WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_STOP)
+                                | WaitStatus::Stopped(_, _)
+                        ),
                         "unexpected sign: {:?}",
                         wait
                     );
@@ -175,6 +378,61 @@ impl ThreadCtl {
         Ok(())
     }
 
+    /// Queue breakpoint hit of thread `tid` stopped by SIGTRAP while it was interrupted.
+    /// Thread PC is rewound to the trap instruction, so it stays at breakpoint address.
+    fn queue_breakpoint_hit(&mut self, tid: Pid) -> anyhow::Result<()> {
+        let info = sys::ptrace::getsiginfo(tid)?;
+        if !matches!(info.si_code, code::TRAP_BRKPT | code::SI_KERNEL) {
+            return Ok(());
+        }
+        let pc = get_register_value(tid, Register::Rip)?;
+        set_register_value(tid, Register::Rip, pc - 1)?;
+        if let Some(thread) = self.threads_state.get_mut(&tid) {
+            thread.queued_breakpoint = true;
+        }
+        Ok(())
+    }
+
+    /// Queue signal received by thread `tid` while it was interrupted.
+    fn queue_signal(&mut self, tid: Pid, signal: Signal) -> anyhow::Result<()> {
+        let info = sys::ptrace::getsiginfo(tid)?;
+        if let Some(thread) = self.threads_state.get_mut(&tid) {
+            thread.queued_signal = Some((signal, info));
+        }
+        Ok(())
+    }
+
+    /// Return stopped thread with queued signal and remove the signal from queue,
+    /// if `tid` is set only this thread is checked.
+    pub fn take_queued_signal(&mut self, tid: Option<Pid>) -> Option<(Pid, Signal, siginfo_t)> {
+        let thread = self.threads_state.values_mut().find(|thread| {
+            thread.queued_signal.is_some()
+                && thread.status == Stopped
+                && tid.is_none_or(|tid| tid == thread.pid)
+        })?;
+        let (signal, info) = thread.queued_signal.take()?;
+        Some((thread.pid, signal, info))
+    }
+
+    /// Return stopped thread with queued breakpoint hit and remove the hit from queue,
+    /// if `tid` is set only this thread is checked.
+    pub fn take_queued_breakpoint(&mut self, tid: Option<Pid>) -> Option<Pid> {
+        let thread = self.threads_state.values_mut().find(|thread| {
+            thread.queued_breakpoint
+                && thread.status == Stopped
+                && tid.is_none_or(|tid| tid == thread.pid)
+        })?;
+        thread.queued_breakpoint = false;
+        Some(thread.pid)
+    }
+
+    /// Drop queued breakpoint hit of thread `tid`.
+    pub fn drop_queued_breakpoint(&mut self, tid: Pid) {
+        if let Some(thread) = self.threads_state.get_mut(&tid) {
+            thread.queued_breakpoint = false;
+        }
+    }
+
     /// Return current thread status.
     /// TraceeStatus::OutOfReach returns if thread not found in budge.
     pub fn status(&self, tid: Pid) -> TraceeStatus {
@@ -188,6 +446,59 @@ impl ThreadCtl {
         self.threads_state.iter().map(|(_, v)| v.clone()).collect()
     }
 
+    /// Occupy free debug register and program it in all threads, return debug register index.
+    pub fn set_hw_breakpoint(&mut self, brkpt: HardwareBreakpoint) -> anyhow::Result<usize> {
+        // debug registers of running thread can't be changed
+        if self
+            .threads_state
+            .values()
+            .any(|thread| thread.status == Running)
+        {
+            bail!("all threads must be stopped to set a hardware breakpoint (see `interrupt`)");
+        }
+        let slot = self.debug_registers.occupy(brkpt)?;
+        if let Err(e) = self.apply_debug_registers() {
+            self.debug_registers.release(slot);
+            _ = self.apply_debug_registers();
+            return Err(e);
+        }
+        Ok(slot)
+    }
+
+    /// Release debug register and reprogram all threads.
+    pub fn remove_hw_breakpoint(&mut self, slot: usize) -> anyhow::Result<()> {
+        self.debug_registers.release(slot);
+        self.apply_debug_registers()
+    }
+
+    /// Program debug registers of a single thread, used for threads created after hardware
+    /// breakpoints were set.
+    pub fn apply_debug_registers_to(&self, tid: Pid) -> nix::Result<()> {
+        if self.debug_registers.is_empty() {
+            return Ok(());
+        }
+        self.debug_registers.apply(tid)
+    }
+
+    fn apply_debug_registers(&self) -> anyhow::Result<()> {
+        let mut errors = vec![];
+        self.threads_state.keys().for_each(|&tid| {
+            if let Err(e) = self.debug_registers.apply(tid) {
+                // if no such process - continue, it will be removed later, on PTRACE_EVENT_EXIT event.
+                if Errno::ESRCH == e {
+                    warn!("thread {} not found, ESRCH", tid);
+                    return;
+                }
+                errors.push(anyhow::Error::from(e).context(format!("thread: {}", tid)));
+            }
+        });
+
+        if !errors.is_empty() {
+            bail!(errors.into_iter().join(";"))
+        }
+        Ok(())
+    }
+
     /// Load libthread_db and init libthread_db process handle.
     /// libthread_db must initialized after first thread created.
     pub(super) fn init_thread_db(&mut self) -> anyhow::Result<()> {
@@ -0,0 +1,125 @@
+use crate::debugger::address::PCValue;
+use crate::debugger::breakpoint::{Breakpoint, BreakpointRegistry};
+use crate::debugger::debugee::{Debugee, ExecutionStatus};
+use crate::debugger::panic::PanicCatch;
+use crate::debugger::Watchpoint;
+use crate::weak_error;
+use nix::errno::Errno;
+use nix::sys;
+use nix::sys::signal;
+use nix::sys::signal::Signal;
+use nix::sys::wait::waitpid;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+
+/// Process which debugger follows when debugee forks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FollowForkMode {
+    /// Continue debugging of forking process.
+    #[default]
+    Parent,
+    /// Debug new process.
+    Child,
+}
+
+/// Traced debugee process which is not in debugger focus (like a process forked
+/// with `detach-on-fork off`), contains all process related debugger state.
+/// Inferior process and all its threads are stopped.
+pub(super) struct Inferior {
+    pub(super) debugee: Debugee,
+    pub(super) breakpoints: HashMap<PCValue, Breakpoint>,
+    pub(super) user_breakpoints: BreakpointRegistry,
+    pub(super) watchpoints: Vec<Watchpoint>,
+    pub(super) panic_catch: Option<PanicCatch>,
+}
+
+impl Inferior {
+    /// Detach inferior process, it continues execution without debugger.
+    /// Code under traps is restored if `restore_code` is true.
+    pub(super) fn detach(mut self, restore_code: bool) -> anyhow::Result<()> {
+        detach(
+            &mut self.debugee,
+            &self.breakpoints,
+            &self.watchpoints,
+            restore_code,
+        )?;
+        // process started by debugger may be in group-stop state (stopped before tracing),
+        // detached process returns to this state, so wake it up
+        signal::kill(self.debugee.threads_ctl().proc_pid(), Signal::SIGCONT)?;
+        Ok(())
+    }
+
+    /// Release inferior process when debugger exits.
+    pub(super) fn release(mut self) {
+        release(&mut self.debugee, &self.breakpoints, &self.watchpoints)
+    }
+}
+
+/// Remove hardware watchpoints, restore code under traps (if `restore_code` is true)
+/// and detach all threads of debugee process, pending signals are delivered.
+/// Running threads (in non-stop mode) are stopped before detach.
+pub(super) fn detach(
+    debugee: &mut Debugee,
+    breakpoints: &HashMap<PCValue, Breakpoint>,
+    watchpoints: &[Watchpoint],
+    restore_code: bool,
+) -> anyhow::Result<()> {
+    debugee.threads_ctl_mut().interrupt_running()?;
+    watchpoints.iter().for_each(|wp| {
+        if let Some(slot) = wp.slot {
+            _ = debugee.threads_ctl_mut().remove_hw_breakpoint(slot);
+        }
+    });
+    if restore_code {
+        breakpoints
+            .values()
+            .filter(|brkpt| brkpt.is_enabled())
+            .try_for_each(|brkpt| brkpt.disable())?;
+    }
+    debugee
+        .threads_ctl()
+        .dump()
+        .iter()
+        .try_for_each(|thread| sys::ptrace::detach(thread.pid, thread.pending_signal))?;
+    Ok(())
+}
+
+/// Release debugee process on debugger exit: attached process is detached and
+/// continues its execution, process started by debugger is killed.
+pub(super) fn release(
+    debugee: &mut Debugee,
+    breakpoints: &HashMap<PCValue, Breakpoint>,
+    watchpoints: &[Watchpoint],
+) {
+    let proc_pid = debugee.threads_ctl().proc_pid();
+    match debugee.execution_status {
+        ExecutionStatus::Unload => {
+            kill(proc_pid);
+        }
+        ExecutionStatus::InProgress if debugee.attached => {
+            // restore debugee code before detach, process must continue its execution
+            detach(debugee, breakpoints, watchpoints, true).expect("detach debugee");
+        }
+        ExecutionStatus::InProgress => {
+            weak_error!(debugee.threads_ctl_mut().interrupt_running());
+            debugee
+                .threads_ctl()
+                .dump()
+                .iter()
+                .for_each(|thread| sys::ptrace::detach(thread.pid, None).expect("detach thread"));
+            kill(proc_pid);
+        }
+        ExecutionStatus::Exited => {}
+    }
+}
+
+/// Kill process and wait for its termination.
+fn kill(pid: Pid) {
+    signal::kill(pid, Signal::SIGKILL).expect("kill debugee");
+    match waitpid(pid, None) {
+        // process forked by debugee (or restarted from checkpoint) is not a child
+        // of debugger, it can't be awaited after detach
+        Ok(_) | Err(Errno::ECHILD) => {}
+        Err(e) => panic!("waiting child: {e}"),
+    }
+}
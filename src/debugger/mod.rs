@@ -1,41 +1,79 @@
+mod abi;
 pub mod address;
 mod breakpoint;
+mod call;
+mod checkpoint;
 mod code;
 pub mod command;
 mod debugee;
+mod inferior;
+mod panic;
 pub mod register;
 pub mod rust;
+mod signal_policy;
 mod utils;
 pub mod uw;
 pub mod variable;
+mod watchpoint;
 
+pub use breakpoint::{BreakpointSpec, UserBreakpoint};
+pub use checkpoint::CheckpointInfo;
+pub use debugee::dwarf::debug_file::init_debug_dirs;
 pub use debugee::dwarf::parser::unit::Place;
 pub use debugee::dwarf::r#type::TypeDeclaration;
+pub use debugee::flow::SchedulerLocking;
+pub use debugee::thread::TraceeStatus;
 pub use debugee::ThreadDump;
+pub use inferior::FollowForkMode;
+pub use panic::{PanicInfo, PanicLocation};
+pub use signal_policy::SignalAction;
+pub use watchpoint::{Watchpoint, WatchpointKind};
 
 use crate::debugger::address::{GlobalAddress, PCValue, RelocatedAddress};
-use crate::debugger::breakpoint::Breakpoint;
-use crate::debugger::command::expression::SelectPlan;
+use crate::debugger::breakpoint::{Breakpoint, BreakpointRegistry};
+use crate::debugger::call::{Argument, InferiorCall, SelectedValue};
+use crate::debugger::checkpoint::Checkpoint;
+use crate::debugger::command::expression::{
+    Call, CallArgument, Callee, Condition, Expr, Literal, Operation, SelectPlan,
+};
+use crate::debugger::debugee::debug_registers::HardwareBreakpoint;
+use crate::debugger::debugee::dwarf::parser::unit::{
+    FunctionDie, InlineSubroutineDie, ParameterDie, VariableDie,
+};
 use crate::debugger::debugee::dwarf::r#type::TypeCache;
-use crate::debugger::debugee::dwarf::{AsAllocatedValue, ContextualDieRef, RegisterDump, Symbol};
-use crate::debugger::debugee::flow::{ControlFlow, DebugeeEvent};
-use crate::debugger::debugee::{dwarf, Debugee, ExecutionStatus, FrameInfo, Location};
-use crate::debugger::register::{get_register_from_name, get_register_value, set_register_value};
+use crate::debugger::debugee::dwarf::{
+    AsAllocatedValue, ContextualDieRef, DebugeeContext, NamespaceHierarchy, RegisterDump, Symbol,
+};
+use crate::debugger::debugee::flow::{ControlFlow, DebugeeEvent, Resume};
+use crate::debugger::debugee::library::SharedLibrary;
+use crate::debugger::debugee::{dwarf, Debugee, ExecutionStatus, FrameInfo, Location, StackFrame};
+use crate::debugger::inferior::Inferior;
+use crate::debugger::panic::{PanicCatch, PanicTrap};
+use crate::debugger::register::{
+    get_register_from_name, get_register_value, set_register_value, Register,
+};
 use crate::debugger::uw::Backtrace;
-use crate::debugger::variable::VariableIR;
+use crate::debugger::variable::arithmetic::Value;
+use crate::debugger::variable::assign;
+use crate::debugger::variable::{ScalarVariable, SupportedScalar, VariableIR, VariableIdentity};
+use crate::debugger::watchpoint::WatchpointScope;
 use crate::weak_error;
-use anyhow::anyhow;
-use nix::libc::{c_int, c_void, uintptr_t};
+use anyhow::{anyhow, bail};
+use log::info;
+use nix::errno::Errno;
+use nix::libc::{self, c_int, c_void, uintptr_t};
 use nix::sys;
-use nix::sys::signal;
 use nix::sys::signal::Signal;
-use nix::sys::wait::waitpid;
+use nix::sys::uio::{self, RemoteIoVec};
 use nix::unistd::Pid;
 use object::Object;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::ffi::c_long;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, IoSliceMut};
+use std::iter;
+use std::os::unix::fs::FileExt;
 use std::path::Path;
 use std::{fs, mem, u64};
 
@@ -43,6 +81,24 @@ pub trait EventHook {
     fn on_trap(&self, pc: RelocatedAddress, place: Option<Place>) -> anyhow::Result<()>;
     fn on_signal(&self, signo: c_int, code: c_int);
     fn on_exit(&self, code: i32);
+    /// Called when watched memory accessed or watched variable goes out of scope.
+    /// `old` is none if value not changed, `new` is none if watched variable goes out of scope.
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        place: Option<Place>,
+        watchpoint: &Watchpoint,
+        old: Option<&VariableIR>,
+        new: Option<&VariableIR>,
+    ) -> anyhow::Result<()>;
+    /// Called when thread `tid` panics and panics are caught (see [`Debugger::catch_panic`]).
+    /// `backtrace` is none if thread stack can't be unwound.
+    fn on_panic(
+        &self,
+        tid: Pid,
+        panic: &PanicInfo,
+        backtrace: Option<&Backtrace>,
+    ) -> anyhow::Result<()>;
 }
 
 macro_rules! disable_when_not_stared {
@@ -58,8 +114,32 @@ macro_rules! disable_when_not_stared {
 pub struct Debugger {
     /// Debugee static/runtime state and control flow.
     debugee: Debugee,
-    /// Active and non-active breakpoint list.
+    /// Active breakpoints (int3 traps), set by user or used by debugger internally.
     breakpoints: HashMap<PCValue, Breakpoint>,
+    /// Numbered breakpoints created by user.
+    user_breakpoints: BreakpointRegistry,
+    /// Active hardware watchpoints.
+    watchpoints: Vec<Watchpoint>,
+    /// Addresses where step commands (`next`, `finish`, `until`, `advance`) must stop,
+    /// set for the duration of the command.
+    temporary_traps: HashSet<RelocatedAddress>,
+    /// Internal breakpoints at panic entry points, set if panics are caught.
+    panic_catch: Option<PanicCatch>,
+    /// Traced processes which are not in focus (processes forked with `detach-on-fork off`).
+    inferiors: Vec<Inferior>,
+    /// Process which debugger follows when debugee forks.
+    follow_fork_mode: FollowForkMode,
+    /// If true, process which is not followed after fork is detached.
+    detach_on_fork: bool,
+    /// Snapshots of debugee created by user.
+    checkpoints: Vec<Checkpoint>,
+    /// Number of the last created checkpoint.
+    last_checkpoint_number: u32,
+    /// Which threads may run while user steps or continues debugee.
+    scheduler_locking: SchedulerLocking,
+    /// Number of selected stack frame of thread in focus, variables and arguments are read
+    /// in this frame. Innermost frame is selected when debugee continues or focus changes.
+    frame_in_focus: Cell<usize>,
     /// Type declaration cache.
     type_cache: RefCell<TypeCache>,
     /// Debugger interrupt with UI by EventHook trait.
@@ -87,19 +167,67 @@ impl Debugger {
 
         Ok(Self {
             breakpoints,
+            user_breakpoints: BreakpointRegistry::default(),
+            watchpoints: vec![],
+            temporary_traps: HashSet::new(),
+            panic_catch: None,
+            inferiors: vec![],
+            follow_fork_mode: FollowForkMode::default(),
+            detach_on_fork: true,
+            checkpoints: vec![],
+            last_checkpoint_number: 0,
+            scheduler_locking: SchedulerLocking::default(),
+            frame_in_focus: Cell::new(0),
             hooks: Box::new(hooks),
             type_cache: RefCell::default(),
             debugee: Debugee::new_non_running(program_path, pid, &object)?,
         })
     }
 
-    fn continue_execution(&mut self) -> anyhow::Result<()> {
-        self.step_over_breakpoint()?;
+    /// Attach to an already running process with `pid`.
+    /// All process threads will be stopped, program path is taken from `/proc/<pid>/exe`.
+    /// On debugger drop attached process will be detached and continue its execution.
+    pub fn attach(pid: Pid, hooks: impl EventHook + 'static) -> anyhow::Result<Self> {
+        let program_path = fs::read_link(format!("/proc/{pid}/exe"))?;
+
+        let file = fs::File::open(&program_path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let object = object::File::parse(&*mmap)?;
+
+        let mut debugger = Self {
+            breakpoints: HashMap::new(),
+            user_breakpoints: BreakpointRegistry::default(),
+            watchpoints: vec![],
+            temporary_traps: HashSet::new(),
+            panic_catch: None,
+            inferiors: vec![],
+            follow_fork_mode: FollowForkMode::default(),
+            detach_on_fork: true,
+            checkpoints: vec![],
+            last_checkpoint_number: 0,
+            scheduler_locking: SchedulerLocking::default(),
+            frame_in_focus: Cell::new(0),
+            hooks: Box::new(hooks),
+            type_cache: RefCell::default(),
+            debugee: Debugee::new_attached(&program_path, pid, &object)?,
+        };
+        debugger.update_libraries()?;
+        Ok(debugger)
+    }
+
+    /// Continue debugee until it stops, `resume` defines threads which continue.
+    fn continue_execution(&mut self, resume: Resume) -> anyhow::Result<()> {
+        self.frame_in_focus.set(0);
+        self.debugee.control_flow.resume = resume;
+        if self.step_over_breakpoints()? {
+            return Ok(());
+        }
 
         loop {
             let event = self.debugee.control_flow_tick()?;
             match event {
                 DebugeeEvent::DebugeeExit(code) => {
+                    self.watchpoints.clear();
                     self.hooks.on_exit(code);
                     break;
                 }
@@ -116,6 +244,11 @@ impl Debugger {
                             PCValue::Relocated(addr.relocate(self.debugee.mapping_offset()));
                         self.breakpoints.insert(brkpt.addr, brkpt);
                     }
+                    self.user_breakpoints
+                        .relocate(self.debugee.mapping_offset());
+                    if let Some(ref mut catch) = self.panic_catch {
+                        catch.relocate(self.debugee.mapping_offset());
+                    }
                     self.breakpoints
                         .iter()
                         .try_for_each(|(_, brkpt)| brkpt.enable())?;
@@ -126,21 +259,87 @@ impl Debugger {
                         .all(|(addr, _)| matches!(addr, PCValue::Relocated(_))));
                 }
                 DebugeeEvent::AtEntryPoint(_) => {
-                    self.step_over_breakpoint()?;
+                    self.update_libraries()?;
+                    if self.step_over_breakpoint()? {
+                        break;
+                    }
                 }
                 DebugeeEvent::TrapTrace | DebugeeEvent::NoSuchProcess(_) => {
                     break;
                 }
-                DebugeeEvent::Breakpoint(_, current_pc) => {
-                    let offset_pc = current_pc.into_global(self.debugee.mapping_offset());
+                DebugeeEvent::Breakpoint(pid, current_pc) => {
+                    let temporary = self.temporary_traps.contains(&current_pc);
+                    if Some(current_pc) == self.debugee.rendezvous_brk() {
+                        self.update_libraries()?;
+                        if !temporary {
+                            if self.step_over_breakpoint()? {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+
+                    if self.leave_watchpoints_scope(pid, current_pc)? {
+                        break;
+                    }
+
+                    if let Some(trap) = self
+                        .panic_catch
+                        .as_mut()
+                        .and_then(|catch| catch.hit(pid, current_pc))
+                    {
+                        let panic = match trap {
+                            PanicTrap::Handler => self.read_panic_info(pid),
+                            PanicTrap::Unwind => PanicInfo::default(),
+                        };
+                        let backtrace = weak_error!(uw::backtrace(&self.debugee, pid));
+                        self.hooks.on_panic(pid, &panic, backtrace.as_ref())?;
+                        break;
+                    }
+
+                    // conditions and ignore counts of user breakpoints never cancel
+                    // a stop requested by step command
+                    let stop = if self
+                        .user_breakpoints
+                        .enabled_at(PCValue::Relocated(current_pc))
+                        .next()
+                        .is_some()
+                    {
+                        self.register_breakpoint_hit(current_pc) || temporary
+                    } else {
+                        temporary
+                            || (!self.is_watchpoint_scope_breakpoint(current_pc)
+                                && !self.is_panic_trap(PCValue::Relocated(current_pc)))
+                    };
+                    if !stop {
+                        if self.step_over_breakpoint()? {
+                            break;
+                        }
+                        continue;
+                    }
+
                     self.hooks
-                        .on_trap(current_pc, self.debugee.dwarf.find_place_from_pc(offset_pc))?;
+                        .on_trap(current_pc, self.debugee.find_place_at(current_pc))?;
                     break;
                 }
+                DebugeeEvent::Watchpoint(pid, slot) => {
+                    if self.watchpoint_hit(pid, slot)? {
+                        break;
+                    }
+                }
                 DebugeeEvent::OsSignal(info, _) => {
                     self.hooks.on_signal(info.si_signo, info.si_code);
                     break;
                 }
+                DebugeeEvent::OsSignalNoStop(info, _) => {
+                    self.hooks.on_signal(info.si_signo, info.si_code);
+                }
+                DebugeeEvent::Fork(_, child, vfork) => {
+                    self.follow_fork(child, vfork)?;
+                }
+                DebugeeEvent::Exec(pid) => {
+                    self.exec_debugee(pid)?;
+                }
             }
         }
 
@@ -148,12 +347,120 @@ impl Debugger {
     }
 
     pub fn run_debugee(&mut self) -> anyhow::Result<()> {
-        self.continue_execution()
+        self.continue_execution(self.resume_mode(false))
     }
 
+    /// Continue debugee. If scheduler locking is on or debugger in non-stop mode,
+    /// only thread in focus continues.
     pub fn continue_debugee(&mut self) -> anyhow::Result<()> {
         disable_when_not_stared!(self);
-        self.continue_execution()
+        self.continue_execution(self.resume_mode(false))
+    }
+
+    /// Continue all stopped threads regardless of scheduler locking and non-stop mode.
+    pub fn continue_all(&mut self) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        self.continue_execution(Resume::All)
+    }
+
+    /// Stop all running threads (in non-stop mode debugee threads may run while debugger waits
+    /// for user commands).
+    pub fn interrupt(&mut self) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        self.debugee.threads_ctl_mut().interrupt_running()
+    }
+
+    /// Set which threads may run while user steps or continues debugee.
+    pub fn set_scheduler_locking(&mut self, mode: SchedulerLocking) {
+        self.scheduler_locking = mode;
+    }
+
+    /// Turn on or off non-stop mode. In non-stop mode only the thread that hits a breakpoint
+    /// (or receives a signal) stops, other threads keep running.
+    /// Running threads are interrupted when non-stop mode turns off.
+    pub fn set_non_stop(&mut self, non_stop: bool) -> anyhow::Result<()> {
+        self.debugee.control_flow.non_stop = non_stop;
+        if !non_stop && self.debugee.execution_status == ExecutionStatus::InProgress {
+            self.debugee.threads_ctl_mut().interrupt_running()?;
+        }
+        Ok(())
+    }
+
+    /// Return threads which continue on resume, `stepping` is true if debugee resumed
+    /// by step command.
+    fn resume_mode(&self, stepping: bool) -> Resume {
+        let locked = match self.scheduler_locking {
+            SchedulerLocking::Off => false,
+            SchedulerLocking::Step => stepping,
+            SchedulerLocking::On => true,
+        };
+        if locked || self.debugee.control_flow.non_stop {
+            Resume::Focused
+        } else {
+            Resume::All
+        }
+    }
+
+    /// Continue debugee, thread in focus receives `signal` instead of its pending signal.
+    /// If `signal` is `None` thread continues without signal.
+    pub fn continue_with_signal(&mut self, signal: Option<Signal>) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        let tid = self.debugee.thread_in_focus();
+        self.debugee
+            .threads_ctl_mut()
+            .set_pending_signal(tid, signal);
+        self.continue_execution(self.resume_mode(false))
+    }
+
+    /// Return debugger reaction on `signal`.
+    pub fn signal_action(&self, signal: Signal) -> SignalAction {
+        self.debugee.control_flow.signal_policy.action(signal)
+    }
+
+    /// Set debugger reaction on `signal`.
+    pub fn set_signal_action(
+        &mut self,
+        signal: Signal,
+        action: SignalAction,
+    ) -> anyhow::Result<()> {
+        if signal == Signal::SIGTRAP {
+            bail!("{signal} is used by debugger");
+        }
+        self.debugee
+            .control_flow
+            .signal_policy
+            .set_action(signal, action);
+        Ok(())
+    }
+
+    /// Return demangled name of function which code contains `addr`
+    /// and offset of `addr` from function start.
+    pub fn symbolize(&self, addr: RelocatedAddress) -> Option<(String, usize)> {
+        if self.debugee.execution_status != ExecutionStatus::InProgress {
+            return None;
+        }
+        let offset = self.debugee.mapping_offset_for_pc(addr);
+        let global = usize::from(addr).checked_sub(offset)?;
+        let symbol = self
+            .debugee
+            .debug_info(addr)
+            .find_function_symbol_by_pc(GlobalAddress::from(global))?;
+        let name = format!("{:#}", rustc_demangle::demangle(&symbol.name));
+        Some((name, global - symbol.addr as usize))
+    }
+
+    /// Return address of symbol `name` in debugee address space, `name` is a name from
+    /// symbol table or a path of function (like `vars::main`).
+    pub fn symbol_address(&self, name: &str) -> anyhow::Result<RelocatedAddress> {
+        disable_when_not_stared!(self);
+
+        let dwarf = &self.debugee.dwarf;
+        let addr = dwarf
+            .find_symbol(name)
+            .map(|symbol| GlobalAddress::from(symbol.addr as usize))
+            .or_else(|| dwarf.find_function_symbols(name).into_iter().next())
+            .ok_or_else(|| anyhow!("symbol {name} not found"))?;
+        Ok(addr.relocate(self.debugee.mapping_offset()))
     }
 
     pub fn get_symbol(&self, name: &str) -> anyhow::Result<&Symbol> {
@@ -163,31 +470,64 @@ impl Debugger {
             .ok_or_else(|| anyhow!("symbol not found"))
     }
 
+    /// Return information about innermost stack frame of thread `tid`.
     pub fn frame_info(&self, tid: Pid) -> anyhow::Result<FrameInfo> {
         disable_when_not_stared!(self);
 
-        self.debugee.frame_info(self.debugee.thread_stop_at(tid)?)
+        self.debugee.frame_info(&self.debugee.stack_frame(tid, 0)?)
+    }
+
+    /// Return number of selected stack frame of thread in focus.
+    pub fn frame_num_in_focus(&self) -> usize {
+        self.frame_in_focus.get()
+    }
+
+    /// Return information about selected stack frame of thread in focus.
+    pub fn frame_in_focus_info(&self) -> anyhow::Result<FrameInfo> {
+        disable_when_not_stared!(self);
+
+        self.debugee.frame_info(&self.frame_in_focus()?)
+    }
+
+    /// Select stack frame `num` of thread in focus (frame 0 is the innermost frame),
+    /// commands like `vars`, `args` or `frame` use selected frame until debugee continues.
+    pub fn set_frame_into_focus(&self, num: usize) -> anyhow::Result<FrameInfo> {
+        disable_when_not_stared!(self);
+        let frame = self
+            .debugee
+            .stack_frame(self.debugee.thread_in_focus(), num)?;
+        self.frame_in_focus.set(num);
+
+        let pc = frame.location.pc;
+        self.hooks.on_trap(pc, self.debugee.find_place_at(pc))?;
+        self.debugee.frame_info(&frame)
+    }
+
+    /// Return selected stack frame of thread in focus.
+    fn frame_in_focus(&self) -> anyhow::Result<StackFrame> {
+        self.debugee
+            .stack_frame(self.debugee.thread_in_focus(), self.frame_in_focus.get())
     }
 
-    pub fn step_into(&self) -> anyhow::Result<()> {
+    pub fn step_into(&mut self) -> anyhow::Result<()> {
         disable_when_not_stared!(self);
-        self.step_in()?;
+        if self.step_in()? {
+            return Ok(());
+        }
 
         let location = self.current_thread_stop_at()?;
-        self.hooks.on_trap(
-            location.pc,
-            self.debugee.dwarf.find_place_from_pc(location.global_pc),
-        )
+        self.hooks
+            .on_trap(location.pc, self.debugee.find_place_at(location.pc))
     }
 
-    pub fn stepi(&self) -> anyhow::Result<()> {
+    pub fn stepi(&mut self) -> anyhow::Result<()> {
         disable_when_not_stared!(self);
-        self.single_step_instruction()?;
+        if self.single_step_instruction()? {
+            return Ok(());
+        }
         let location = self.current_thread_stop_at()?;
-        self.hooks.on_trap(
-            location.pc,
-            self.debugee.dwarf.find_place_from_pc(location.global_pc),
-        )
+        self.hooks
+            .on_trap(location.pc, self.debugee.find_place_at(location.pc))
     }
 
     pub fn thread_state(&self) -> anyhow::Result<Vec<ThreadDump>> {
@@ -195,238 +535,1367 @@ impl Debugger {
         self.debugee.thread_state()
     }
 
+    /// Move focus to thread with number `num`, thread in focus used by commands
+    /// like `vars`, `bt`, `frame` or `register`. Return id of thread in focus.
+    pub fn set_thread_into_focus(&mut self, num: u32) -> anyhow::Result<Pid> {
+        disable_when_not_stared!(self);
+        let tid = self
+            .debugee
+            .threads_ctl()
+            .dump()
+            .into_iter()
+            .find(|thread| thread.number == num)
+            .map(|thread| thread.pid)
+            .ok_or_else(|| anyhow!("thread {num} not found"))?;
+        self.debugee.threads_ctl_mut().set_thread_to_focus(tid);
+        self.frame_in_focus.set(0);
+
+        let location = self.current_thread_stop_at()?;
+        self.hooks
+            .on_trap(location.pc, self.debugee.find_place_at(location.pc))?;
+        Ok(tid)
+    }
+
     pub fn backtrace(&self, pid: Pid) -> anyhow::Result<Backtrace> {
         disable_when_not_stared!(self);
-        Ok(uw::backtrace(pid)?)
+        uw::backtrace(&self.debugee, pid)
     }
 
-    pub fn set_breakpoint(&mut self, addr: PCValue) -> anyhow::Result<()> {
-        // todo make method idempotence
-        let brkpt = Breakpoint::new(addr, self.debugee.threads_ctl().proc_pid());
-        if self.debugee.execution_status == ExecutionStatus::InProgress {
-            brkpt.enable()?;
+    /// Set breakpoint at address, return breakpoint number.
+    pub fn set_breakpoint(&mut self, addr: PCValue) -> anyhow::Result<u32> {
+        let spec = match addr {
+            PCValue::Relocated(addr) => BreakpointSpec::Address(addr.into()),
+            PCValue::Global(addr) => BreakpointSpec::Address(addr.into()),
+        };
+        self.set_user_breakpoint(spec, vec![addr])
+    }
+
+    fn set_user_breakpoint(
+        &mut self,
+        spec: BreakpointSpec,
+        mut locations: Vec<PCValue>,
+    ) -> anyhow::Result<u32> {
+        locations.dedup();
+        for &addr in &locations {
+            if !self.breakpoints.contains_key(&addr) {
+                self.add_trap(addr)?;
+            }
         }
-        self.breakpoints.insert(addr, brkpt);
+        Ok(self.user_breakpoints.add(spec, locations))
+    }
+
+    /// Return all user breakpoints ordered by number.
+    pub fn breakpoints(&self) -> Vec<&UserBreakpoint> {
+        self.user_breakpoints.iter().collect()
+    }
+
+    fn user_breakpoint_mut(&mut self, number: u32) -> anyhow::Result<&mut UserBreakpoint> {
+        self.user_breakpoints
+            .get_mut(number)
+            .ok_or_else(|| anyhow!("breakpoint {number} not found"))
+    }
+
+    /// Set condition for breakpoint, debugee will stop at this breakpoint
+    /// only if condition is true.
+    pub fn set_breakpoint_condition(
+        &mut self,
+        number: u32,
+        condition: Condition,
+    ) -> anyhow::Result<()> {
+        self.user_breakpoint_mut(number)?.condition = Some(condition);
         Ok(())
     }
 
-    pub fn remove_breakpoint(&mut self, addr: PCValue) -> anyhow::Result<()> {
-        let brkpt = self.breakpoints.remove(&addr);
-        if let Some(brkpt) = brkpt {
-            if brkpt.is_enabled() {
-                brkpt.disable()?;
+    /// Set count of next breakpoint hits that will be ignored.
+    pub fn set_breakpoint_ignore_count(&mut self, number: u32, count: u32) -> anyhow::Result<()> {
+        self.user_breakpoint_mut(number)?.ignore_count = count;
+        Ok(())
+    }
+
+    /// Enable breakpoint, its trap will be restored in debugee code.
+    pub fn enable_breakpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let brkpt = self.user_breakpoint_mut(number)?;
+        brkpt.enabled = true;
+        for addr in brkpt.locations.clone() {
+            if !self.breakpoints.contains_key(&addr) {
+                self.add_trap(addr)?;
             }
         }
         Ok(())
     }
 
-    /// Read N bytes from debugee process.
-    pub fn read_memory(&self, addr: usize, read_n: usize) -> anyhow::Result<Vec<u8>> {
-        disable_when_not_stared!(self);
-        Ok(read_memory_by_pid(
-            self.debugee.threads_ctl().proc_pid(),
-            addr,
-            read_n,
-        )?)
+    /// Disable breakpoint, debugee will not stop at it until breakpoint enabled again.
+    pub fn disable_breakpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let brkpt = self.user_breakpoint_mut(number)?;
+        brkpt.enabled = false;
+        for addr in brkpt.locations.clone() {
+            self.release_trap(addr)?;
+        }
+        Ok(())
     }
 
-    pub fn write_memory(&self, addr: uintptr_t, value: uintptr_t) -> anyhow::Result<()> {
-        disable_when_not_stared!(self);
-        unsafe {
-            Ok(sys::ptrace::write(
-                self.debugee.threads_ctl().proc_pid(),
-                addr as *mut c_void,
-                value as *mut c_void,
-            )?)
+    /// Delete breakpoint.
+    pub fn remove_breakpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let brkpt = self
+            .user_breakpoints
+            .remove(number)
+            .ok_or_else(|| anyhow!("breakpoint {number} not found"))?;
+        brkpt
+            .locations
+            .into_iter()
+            .try_for_each(|addr| self.release_trap(addr))
+    }
+
+    /// Update hit counters of enabled breakpoints at `pc` which conditions are satisfied.
+    /// Return true if user stop must be reported, step commands stop at their temporary
+    /// traps regardless of the result.
+    fn register_breakpoint_hit(&mut self, pc: RelocatedAddress) -> bool {
+        let hits = self
+            .user_breakpoints
+            .enabled_at(PCValue::Relocated(pc))
+            .filter(|brkpt| self.breakpoint_condition_satisfied(brkpt))
+            .map(|brkpt| brkpt.number)
+            .collect::<Vec<_>>();
+
+        let mut stop = false;
+        for number in hits {
+            if let Some(brkpt) = self.user_breakpoints.get_mut(number) {
+                brkpt.hit_count += 1;
+                if brkpt.ignore_count > 0 {
+                    brkpt.ignore_count -= 1;
+                } else {
+                    stop = true;
+                }
+            }
         }
+        stop
     }
 
-    pub fn current_thread_stop_at(&self) -> nix::Result<Location> {
-        self.debugee.current_thread_stop_at()
+    /// Return false if breakpoint has a condition that evaluated to false.
+    /// If condition evaluation fails debugee must be stopped, so true returned.
+    fn breakpoint_condition_satisfied(&self, brkpt: &UserBreakpoint) -> bool {
+        match brkpt.condition {
+            None => true,
+            Some(ref condition) => {
+                weak_error!(condition.evaluate(|plan| self.read_variable_or_argument(plan)))
+                    .unwrap_or(true)
+            }
+        }
     }
 
-    fn step_over_breakpoint(&self) -> anyhow::Result<()> {
-        // cannot use debugee::Location mapping offset may be not init yet
-        let pid = self.debugee.thread_in_focus();
-        let pc = self.debugee.control_flow.thread_pc(pid)?;
-        let mb_brkpt = self.breakpoints.get(&PCValue::Relocated(pc));
-        if let Some(brkpt) = mb_brkpt {
-            if brkpt.is_enabled() {
-                brkpt.disable()?;
-                ControlFlow::thread_step(pid)?;
-                brkpt.enable()?;
+    /// Read variable or current function argument selected by `select_plan`.
+    fn read_variable_or_argument(&self, select_plan: &SelectPlan) -> anyhow::Result<VariableIR> {
+        let mut vars = self.read_variable(select_plan.clone())?;
+        if vars.is_empty() {
+            vars = self.read_argument(select_plan.clone())?;
+        }
+        vars.into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("variable {} not found", select_plan.source))
+    }
+
+    /// Read details of panic from `info` argument of panic handler, thread `pid`
+    /// must be stopped at panic handler entry.
+    fn read_panic_info(&self, pid: Pid) -> PanicInfo {
+        let frame = weak_error!(self.debugee.stack_frame(pid, 0));
+        panic::read_panic_info(
+            pid,
+            |plan| {
+                let frame = frame
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("unknown panic frame"))?;
+                let source = plan.source.clone();
+                self.read_argument_in_frame(frame, plan)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("{source} not found"))
+            },
+            |args| self.format_arguments(pid, args),
+        )
+    }
+
+    /// Render `core::fmt::Arguments` located at `args_addr` by debugee std functions called
+    /// by thread `tid`: arguments are formatted into a `String`, the string converted into
+    /// a `Box<str>` which data pointer and length are returned in registers, so layout
+    /// of `String` is not needed. Memory of the rendered string is released after reading.
+    fn format_arguments(&self, tid: Pid, args_addr: usize) -> anyhow::Result<String> {
+        let function = |name: &str| {
+            self.find_function_symbol(name)
+                .ok_or_else(|| anyhow!("function {name} not found"))
+        };
+        let format_fn = function(panic::FORMAT_FN)?;
+        let dealloc_fn = function(panic::DEALLOC_FN)?;
+        let word_size = mem::size_of::<usize>();
+
+        // `fmt::Arguments` is a pair of words, so it is passed in two registers
+        let args = read_memory_by_pid(tid, args_addr, word_size * 2)?;
+        let mut format_call = InferiorCall::new(tid)?;
+        let string_addr = format_call.push_data(&vec![0; word_size * 3])?;
+        format_call.push_integer(string_addr as u64)?;
+        for word in args.chunks(word_size) {
+            format_call.push_integer(u64::from_ne_bytes(word.try_into().expect("word size")))?;
+        }
+        let string = self.execute_call(format_call, format_fn, || {
+            Ok(read_memory_by_pid(tid, string_addr, word_size * 3)?)
+        })?;
+
+        // `String` layout is `{capacity, pointer, length}`
+        let [cap, ptr, len] = [0, 1, 2].map(|i| {
+            let word = &string[i * word_size..(i + 1) * word_size];
+            u64::from_ne_bytes(word.try_into().expect("word size"))
+        });
+        let message =
+            read_memory_by_pid(tid, ptr as usize, (len as usize).min(panic::MAX_STRING_LEN));
+        if cap != 0 {
+            let mut dealloc_call = InferiorCall::new(tid)?;
+            for arg in [ptr, cap, 1] {
+                dealloc_call.push_integer(arg)?;
             }
+            weak_error!(self.execute_call(dealloc_call, dealloc_fn, || Ok(())));
         }
-        Ok(())
+        Ok(String::from_utf8_lossy(&message?).into_owned())
     }
 
-    fn single_step_instruction(&self) -> anyhow::Result<()> {
-        let loc = self.current_thread_stop_at()?;
-        if self.breakpoints.get(&PCValue::Relocated(loc.pc)).is_some() {
-            self.step_over_breakpoint()
-        } else {
-            ControlFlow::thread_step(loc.pid)?;
-            Ok(())
+    /// Return address of function `name` found in symbol tables of debugee executable
+    /// or loaded shared libraries.
+    fn find_function_symbol(&self, name: &str) -> Option<RelocatedAddress> {
+        let executable = self
+            .debugee
+            .dwarf
+            .find_function_symbols(name)
+            .into_iter()
+            .map(|addr| addr.relocate(self.debugee.mapping_offset()));
+        let libraries = self.debugee.libraries().iter().flat_map(|lib| {
+            lib.dwarf
+                .find_function_symbols(name)
+                .into_iter()
+                .map(|addr| addr.relocate(lib.load_bias))
+        });
+        executable.chain(libraries).next()
+    }
+
+    /// Write trap (int3 instruction) at `addr`, if debugee not started trap will be written
+    /// right after debugee start.
+    fn add_trap(&mut self, addr: PCValue) -> anyhow::Result<()> {
+        // todo make method idempotence
+        let brkpt = Breakpoint::new(addr, self.debugee.threads_ctl().proc_pid());
+        if self.debugee.execution_status == ExecutionStatus::InProgress {
+            brkpt.enable()?;
         }
+        self.breakpoints.insert(addr, brkpt);
+        Ok(())
     }
 
-    pub fn step_out(&mut self) -> anyhow::Result<()> {
-        disable_when_not_stared!(self);
-        if let Some(ret_addr) = uw::return_addr(self.debugee.thread_in_focus())? {
-            let brkpt_is_set = self
-                .breakpoints
-                .get(&PCValue::Relocated(ret_addr))
-                .is_some();
-            if brkpt_is_set {
-                self.continue_execution()?;
-            } else {
-                self.set_breakpoint(PCValue::Relocated(ret_addr))?;
-                self.continue_execution()?;
-                self.remove_breakpoint(PCValue::Relocated(ret_addr))?;
+    /// Remove trap at `addr` and restore debugee code.
+    fn remove_trap(&mut self, addr: PCValue) -> anyhow::Result<()> {
+        let brkpt = self.breakpoints.remove(&addr);
+        if let Some(brkpt) = brkpt {
+            if brkpt.is_enabled() {
+                brkpt.disable()?;
             }
         }
         Ok(())
     }
 
-    pub fn step_in(&self) -> anyhow::Result<()> {
-        disable_when_not_stared!(self);
+    /// Remove trap at `addr` if it is not used by enabled user breakpoints, watchpoints,
+    /// panic catching, shared libraries tracking (`r_brk`) or as program entry point trap.
+    fn release_trap(&mut self, addr: PCValue) -> anyhow::Result<()> {
+        let entry_point = self.breakpoint_location(self.debugee.control_flow.program_ep());
+        let in_use = self.user_breakpoints.enabled_at(addr).next().is_some()
+            || matches!(addr, PCValue::Relocated(pc) if self.is_watchpoint_scope_breakpoint(pc))
+            || matches!(addr, PCValue::Relocated(pc) if Some(pc) == self.debugee.rendezvous_brk())
+            || addr == entry_point
+            || self.is_panic_trap(addr);
+        if in_use {
+            return Ok(());
+        }
+        self.remove_trap(addr)
+    }
 
-        let location = self.current_thread_stop_at()?;
-        let place = self
-            .debugee
-            .dwarf
-            .find_place_from_pc(location.global_pc)
-            .ok_or_else(|| anyhow!("not in debug frame (may be program not started?)"))?;
+    /// Stop debugee when any of its threads panics, panic message and location are
+    /// reported with [`EventHook::on_panic`], debugee stops at panicking thread.
+    /// Internal breakpoints are set at panic handler (`std::panicking::begin_panic_handler`)
+    /// and at `rust_panic` function, the last one used for panics without
+    /// panic handler call (like `std::panic::resume_unwind`).
+    pub fn catch_panic(&mut self) -> anyhow::Result<()> {
+        if self.panic_catch.is_some() {
+            return Ok(());
+        }
 
-        while place
-            == self
-                .debugee
-                .dwarf
-                .find_place_from_pc(self.current_thread_stop_at()?.global_pc)
-                .ok_or_else(|| anyhow!("unreachable! line not found"))?
+        let dwarf = &self.debugee.dwarf;
+        let locations = |name: &str| -> Vec<PCValue> {
+            dwarf
+                .find_function_symbols(name)
+                .into_iter()
+                .map(|addr| self.breakpoint_location(addr))
+                .collect()
+        };
+        let mut catch = PanicCatch::new(
+            locations(panic::PANIC_HANDLER_FN),
+            locations(panic::RUST_PANIC_FN),
+        );
+        // std may be linked dynamically (`-C prefer-dynamic`)
+        for lib in self.debugee.libraries() {
+            let (handler, rust_panic) = library_panic_locations(lib);
+            catch.extend(handler, rust_panic);
+        }
+        // before start std library is not loaded yet, traps will be set at its loading
+        if catch.locations().next().is_none()
+            && self.debugee.execution_status == ExecutionStatus::InProgress
         {
-            self.single_step_instruction()?
+            bail!("panic functions not found");
         }
 
+        for addr in catch.locations().collect::<Vec<_>>() {
+            if !self.breakpoints.contains_key(&addr) {
+                self.add_trap(addr)?;
+            }
+        }
+        self.panic_catch = Some(catch);
         Ok(())
     }
 
-    pub fn step_over(&mut self) -> anyhow::Result<()> {
-        disable_when_not_stared!(self);
-        let func = self
-            .debugee
-            .dwarf
-            .find_function_by_pc(self.current_thread_stop_at()?.global_pc)
-            .ok_or_else(|| anyhow!("not in debug frame (may be program not started?)"))?;
+    /// Return true if trap at `addr` used for catching panics.
+    fn is_panic_trap(&self, addr: PCValue) -> bool {
+        self.panic_catch
+            .as_ref()
+            .map(|catch| catch.is_trap(addr))
+            .unwrap_or_default()
+    }
 
-        let mut to_delete = vec![];
+    /// Set hardware watchpoint on variable or argument selected by `select_plan`, return
+    /// watchpoint number. Only scalars and pointers can be watched.
+    /// Watchpoint on local variable removed when variable frame returns.
+    pub fn set_watchpoint(
+        &mut self,
+        select_plan: SelectPlan,
+        kind: WatchpointKind,
+    ) -> anyhow::Result<u32> {
+        disable_when_not_stared!(self);
 
-        let current_line = self
+        let value = self.read_variable_or_argument(&select_plan)?;
+        let address = value
+            .raw_address()
+            .ok_or_else(|| anyhow!("{} is not located in memory", select_plan.source))?;
+        let size = value.word_size().ok_or_else(|| {
+            anyhow!(
+                "only scalars and pointers can be watched, but {} is not",
+                select_plan.source
+            )
+        })?;
+        let raw_value = self.read_memory(address, size)?;
+
+        let frame = self.frame_in_focus()?;
+        let scope = self.local_variable_scope(&frame, address)?;
+
+        let slot = self
             .debugee
-            .dwarf
-            .find_place_from_pc(self.current_thread_stop_at()?.global_pc)
-            .ok_or_else(|| anyhow!("current line not found"))?;
-
-        let mut breakpoints_range = vec![];
-
-        for range in func.die.base_attributes.ranges.iter() {
-            let mut line = self
-                .debugee
-                .dwarf
-                .find_place_from_pc(GlobalAddress::from(range.begin))
-                .ok_or_else(|| anyhow!("unknown function range"))?;
-
-            while u64::from(line.address) < range.end {
-                if line.is_stmt {
-                    let load_addr = line.address.relocate(self.debugee.mapping_offset());
-                    if line.address != current_line.address
-                        && self
-                            .breakpoints
-                            .get(&PCValue::Relocated(load_addr))
-                            .is_none()
-                    {
-                        breakpoints_range.push(load_addr);
-                        to_delete.push(load_addr);
+            .threads_ctl_mut()
+            .set_hw_breakpoint(HardwareBreakpoint {
+                addr: RelocatedAddress::from(address),
+                size: size as u8,
+                condition: kind.break_condition(),
+            })?;
+
+        let scope = match scope {
+            None => None,
+            Some((cfa, return_addr)) => {
+                let addr = PCValue::Relocated(return_addr);
+                if !self.breakpoints.contains_key(&addr) {
+                    if let Err(e) = self.add_trap(addr) {
+                        self.debugee.threads_ctl_mut().remove_hw_breakpoint(slot)?;
+                        return Err(e);
                     }
                 }
-
-                match line.next() {
-                    None => break,
-                    Some(n) => line = n,
-                }
+                Some(WatchpointScope {
+                    tid: frame.location.pid,
+                    cfa,
+                    return_addr,
+                })
             }
-        }
+        };
 
-        breakpoints_range
-            .into_iter()
-            .try_for_each(|load_addr| self.set_breakpoint(PCValue::Relocated(load_addr)))?;
+        let number = self.user_breakpoints.next_number();
+        self.watchpoints.push(Watchpoint {
+            number,
+            expr: select_plan.source,
+            kind,
+            address: RelocatedAddress::from(address),
+            size: size as u8,
+            slot: Some(slot),
+            value,
+            raw_value,
+            scope,
+        });
+        Ok(number)
+    }
 
-        if let Some(ret_addr) = uw::return_addr(self.debugee.thread_in_focus())? {
-            if self
-                .breakpoints
-                .get(&PCValue::Relocated(ret_addr))
-                .is_none()
-            {
-                self.set_breakpoint(PCValue::Relocated(ret_addr))?;
-                to_delete.push(ret_addr);
-            }
+    /// Remove watchpoint by its number.
+    pub fn remove_watchpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let idx = self.watchpoint_idx(number)?;
+        let watchpoint = self.watchpoints.remove(idx);
+        self.release_watchpoint(&watchpoint)
+    }
+
+    /// Enable watchpoint, watched memory may change while watchpoint is disabled,
+    /// so last known value is updated.
+    pub fn enable_watchpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+
+        let idx = self.watchpoint_idx(number)?;
+        if self.watchpoints[idx].is_enabled() {
+            return Ok(());
         }
 
-        self.continue_execution()?;
+        let (address, size) = {
+            let wp = &self.watchpoints[idx];
+            (usize::from(wp.address), wp.size as usize)
+        };
+        let raw_value = self.read_memory(address, size)?;
+        let slot = self
+            .debugee
+            .threads_ctl_mut()
+            .set_hw_breakpoint(self.watchpoints[idx].hardware_breakpoint())?;
 
-        to_delete
-            .into_iter()
-            .try_for_each(|addr| self.remove_breakpoint(PCValue::Relocated(addr)))?;
+        let watchpoint = &mut self.watchpoints[idx];
+        if let Some(value) = watchpoint.value.with_raw_value(&raw_value) {
+            watchpoint.value = value;
+        }
+        watchpoint.raw_value = raw_value;
+        watchpoint.slot = Some(slot);
+        Ok(())
+    }
 
+    /// Disable watchpoint, its debug register is released until watchpoint enabled again.
+    pub fn disable_watchpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let idx = self.watchpoint_idx(number)?;
+        if let Some(slot) = self.watchpoints[idx].slot.take() {
+            self.debugee.threads_ctl_mut().remove_hw_breakpoint(slot)?;
+        }
         Ok(())
     }
 
-    pub fn set_breakpoint_at_fn(&mut self, name: &str) -> anyhow::Result<()> {
-        let func = self
-            .debugee
-            .dwarf
-            .find_function_by_name(name)
-            .ok_or_else(|| anyhow!("function not found"))?;
+    fn watchpoint_idx(&self, number: u32) -> anyhow::Result<usize> {
+        self.watchpoints
+            .iter()
+            .position(|wp| wp.number == number)
+            .ok_or_else(|| anyhow!("watchpoint {number} not found"))
+    }
 
-        // todo find range with lowes begin
-        let low_pc = func.die.base_attributes.ranges[0].begin;
-        let entry = self
-            .debugee
-            .dwarf
-            .find_place_from_pc(GlobalAddress::from(low_pc))
-            .ok_or_else(|| anyhow!("invalid function entry"))?
-            // TODO skip prologue smarter
-            .next()
-            .ok_or_else(|| anyhow!("invalid function entry"))?;
+    /// Return active watchpoints.
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
 
-        let addr = if self.debugee.execution_status == ExecutionStatus::InProgress {
-            PCValue::Relocated(entry.address.relocate(self.debugee.mapping_offset()))
-        } else {
-            PCValue::Global(entry.address)
+    /// Return CFA and return address of `frame` if `address` points into it.
+    fn local_variable_scope(
+        &self,
+        frame: &StackFrame,
+        address: usize,
+    ) -> anyhow::Result<Option<(RelocatedAddress, RelocatedAddress)>> {
+        let Ok(info) = self.debugee.frame_info(frame) else {
+            return Ok(None);
         };
-
-        self.set_breakpoint(addr)
+        let sp = frame
+            .registers
+            .get(gimli::X86_64::RSP)
+            .ok_or_else(|| anyhow!("stack pointer of frame #{} is unknown", frame.num))?
+            as usize;
+        if !(sp..usize::from(info.cfa)).contains(&address) {
+            return Ok(None);
+        }
+        Ok(info.return_addr.map(|ret_addr| (info.cfa, ret_addr)))
     }
 
-    pub fn set_breakpoint_at_line(&mut self, fine_name: &str, line: u64) -> anyhow::Result<()> {
-        if let Some(place) = self.debugee.dwarf.find_stmt_line(fine_name, line) {
-            let addr = if self.debugee.execution_status == ExecutionStatus::InProgress {
-                PCValue::Relocated(place.address.relocate(self.debugee.mapping_offset()))
-            } else {
-                PCValue::Global(place.address)
-            };
+    /// Free debug register and breakpoint at frame return address used by watchpoint.
+    fn release_watchpoint(&mut self, watchpoint: &Watchpoint) -> anyhow::Result<()> {
+        if let Some(slot) = watchpoint.slot {
+            self.debugee.threads_ctl_mut().remove_hw_breakpoint(slot)?;
+        }
 
-            self.set_breakpoint(addr)?;
+        if let Some(ref scope) = watchpoint.scope {
+            // trap may be shared with user breakpoints or other watchpoints from the same frame
+            self.release_trap(PCValue::Relocated(scope.return_addr))?;
         }
         Ok(())
     }
 
-    fn variables_into_variable_ir<D: AsAllocatedValue>(
-        &self,
-        location: Location,
-        vars: &[ContextualDieRef<D>],
-        select_plan: SelectPlan,
-    ) -> anyhow::Result<Vec<VariableIR>> {
+    /// Return true if trap at `pc` used for tracking watchpoint scope.
+    fn is_watchpoint_scope_breakpoint(&self, pc: RelocatedAddress) -> bool {
+        self.watchpoints.iter().any(|wp| {
+            wp.scope
+                .as_ref()
+                .map(|scope| scope.return_addr == pc)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Remove watchpoints which frame returns at `pc` in thread `pid`.
+    /// Return true if at least one watchpoint removed, debugee must be stopped in this case.
+    fn leave_watchpoints_scope(&mut self, pid: Pid, pc: RelocatedAddress) -> anyhow::Result<bool> {
+        let sp = RelocatedAddress::from(get_register_value(pid, Register::Rsp)?);
+        let (ended, active): (Vec<_>, Vec<_>) = mem::take(&mut self.watchpoints)
+            .into_iter()
+            .partition(|wp| match wp.scope {
+                Some(ref scope) => {
+                    scope.tid == pid
+                        && scope.return_addr == pc
+                        && usize::from(sp) >= usize::from(scope.cfa)
+                }
+                None => false,
+            });
+        self.watchpoints = active;
+
+        for watchpoint in &ended {
+            self.release_watchpoint(watchpoint)?;
+            let place = self.debugee.find_place_at(pc);
+            self.hooks
+                .on_watchpoint(pc, place, watchpoint, Some(&watchpoint.value), None)?;
+        }
+
+        Ok(!ended.is_empty())
+    }
+
+    /// Handle hardware watchpoint trigger in debug register `slot`.
+    /// Return true if debugee must be stopped.
+    fn watchpoint_hit(&mut self, pid: Pid, slot: usize) -> anyhow::Result<bool> {
+        let Some(idx) = self.watchpoints.iter().position(|wp| wp.slot == Some(slot)) else {
+            return Ok(false);
+        };
+
+        let (address, size) = {
+            let wp = &self.watchpoints[idx];
+            (usize::from(wp.address), wp.size as usize)
+        };
+        let raw_value = self.read_memory(address, size)?;
+
+        let watchpoint = &mut self.watchpoints[idx];
+        let changed = watchpoint.raw_value != raw_value;
+        let stop = match watchpoint.kind {
+            WatchpointKind::Write => changed,
+            WatchpointKind::Read => !changed,
+            WatchpointKind::Access => true,
+        };
+
+        let new_value = watchpoint
+            .value
+            .with_raw_value(&raw_value)
+            .ok_or_else(|| anyhow!("invalid value of {}", watchpoint.expr))?;
+        let old_value = mem::replace(&mut watchpoint.value, new_value);
+        watchpoint.raw_value = raw_value;
+
+        if !stop {
+            return Ok(false);
+        }
+
+        let watchpoint = &self.watchpoints[idx];
+        let pc = self.debugee.control_flow.thread_pc(pid)?;
+        let place = self.debugee.find_place_at(pc);
+        self.hooks.on_watchpoint(
+            pc,
+            place,
+            watchpoint,
+            changed.then_some(&old_value),
+            Some(&watchpoint.value),
+        )?;
+        Ok(true)
+    }
+
+    /// Read N bytes from debugee process.
+    pub fn read_memory(&self, addr: usize, read_n: usize) -> anyhow::Result<Vec<u8>> {
+        disable_when_not_stared!(self);
+        // in non-stop mode main thread may be running, but thread in focus is always stopped
+        Ok(read_memory_by_pid(
+            self.debugee.thread_in_focus(),
+            addr,
+            read_n,
+        )?)
+    }
+
+    /// Read `read_n` bytes of debugee memory like [`Debugger::read_memory`], but bytes
+    /// replaced by breakpoint traps are restored to their original values.
+    pub fn read_memory_unpatched(&self, addr: usize, read_n: usize) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = self.read_memory(addr, read_n)?;
+        for brkpt in self.breakpoints.values() {
+            let (PCValue::Relocated(brkpt_addr), Some(saved)) = (brkpt.addr, brkpt.saved_data())
+            else {
+                continue;
+            };
+            if let Some(byte) = usize::from(brkpt_addr)
+                .checked_sub(addr)
+                .and_then(|offset| bytes.get_mut(offset))
+            {
+                *byte = saved;
+            }
+        }
+        Ok(bytes)
+    }
+
+    pub fn write_memory(&self, addr: uintptr_t, value: uintptr_t) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        unsafe {
+            Ok(sys::ptrace::write(
+                self.debugee.thread_in_focus(),
+                addr as *mut c_void,
+                value as *mut c_void,
+            )?)
+        }
+    }
+
+    pub fn current_thread_stop_at(&self) -> nix::Result<Location> {
+        self.debugee.current_thread_stop_at()
+    }
+
+    /// Step over breakpoint in thread in focus.
+    /// Return true if debugee stopped at watchpoint triggered by the step.
+    fn step_over_breakpoint(&mut self) -> anyhow::Result<bool> {
+        self.step_thread_over_breakpoint(self.debugee.thread_in_focus())
+    }
+
+    /// Step over breakpoints in all stopped threads, thread stopped at breakpoint
+    /// may be not in focus if user switches focus to another thread.
+    /// Thread with queued breakpoint hit stays at breakpoint if it is still enabled,
+    /// so the hit is reported when debugee continues.
+    /// Return true if debugee stopped at watchpoint triggered by the step.
+    fn step_over_breakpoints(&mut self) -> anyhow::Result<bool> {
+        for thread in self.debugee.threads_ctl().dump() {
+            if thread.status != TraceeStatus::Stopped {
+                continue;
+            }
+            if thread.queued_breakpoint {
+                let pc = self.debugee.control_flow.thread_pc(thread.pid)?;
+                if self
+                    .breakpoints
+                    .get(&PCValue::Relocated(pc))
+                    .is_some_and(Breakpoint::is_enabled)
+                {
+                    continue;
+                }
+                self.debugee
+                    .threads_ctl_mut()
+                    .drop_queued_breakpoint(thread.pid);
+            }
+            if self.step_thread_over_breakpoint(thread.pid)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Step over breakpoint in thread `pid`.
+    /// Return true if debugee stopped at watchpoint triggered by the step.
+    fn step_thread_over_breakpoint(&mut self, pid: Pid) -> anyhow::Result<bool> {
+        // cannot use debugee::Location mapping offset may be not init yet
+        let pc = self.debugee.control_flow.thread_pc(pid)?;
+        let mb_brkpt = self.breakpoints.get(&PCValue::Relocated(pc));
+        let mut triggered = None;
+        if let Some(brkpt) = mb_brkpt {
+            if brkpt.is_enabled() {
+                brkpt.disable()?;
+                triggered = ControlFlow::thread_step(pid)?;
+                brkpt.enable()?;
+            }
+        }
+        self.step_watchpoint_hit(pid, triggered)
+    }
+
+    /// Execute single instruction in thread in focus.
+    /// Return true if debugee stopped at watchpoint triggered by the instruction.
+    fn single_step_instruction(&mut self) -> anyhow::Result<bool> {
+        self.frame_in_focus.set(0);
+        let loc = self.current_thread_stop_at()?;
+        if self.breakpoints.get(&PCValue::Relocated(loc.pc)).is_some() {
+            self.step_over_breakpoint()
+        } else {
+            let triggered = ControlFlow::thread_step(loc.pid)?;
+            self.step_watchpoint_hit(loc.pid, triggered)
+        }
+    }
+
+    /// Handle hardware watchpoint at debug register `slot` triggered by single step
+    /// of thread `pid`. Return true if debugee stopped at the watchpoint.
+    fn step_watchpoint_hit(&mut self, pid: Pid, slot: Option<usize>) -> anyhow::Result<bool> {
+        let Some(slot) = slot else {
+            return Ok(false);
+        };
+        self.debugee.threads_ctl_mut().set_thread_to_focus(pid);
+        self.watchpoint_hit(pid, slot)
+    }
+
+    /// Run until current function returns. If execution stopped in inlined function code,
+    /// then run until execution leaves code of this inlined call.
+    /// Return value returned by function, `None` if function returns nothing, execution
+    /// stopped before function returns or execution leaves inlined call.
+    pub fn step_out(&mut self) -> anyhow::Result<Option<VariableIR>> {
+        disable_when_not_stared!(self);
+        let mut traps = self.inlined_call_exits()?;
+        let inlined = !traps.is_empty();
+        let frame = uw::innermost_frame(&self.debugee, self.debugee.thread_in_focus())?;
+        let ret_addr = uw::caller_frame(&self.debugee, &frame)?.map(|caller| caller.ip);
+        traps.extend(ret_addr);
+        self.run_to_temporary_traps(traps)?;
+
+        if inlined || self.debugee.execution_status != ExecutionStatus::InProgress {
+            return Ok(None);
+        }
+        // return address may be reached by other thread or by nested call of recursive function,
+        // stack pointer points to the CFA of returned frame
+        let location = self.current_thread_stop_at()?;
+        let returned = Some(location.pc) == ret_addr
+            && location.pid == frame.location.pid
+            && get_register_value(location.pid, Register::Rsp)? == u64::from(frame.cfa);
+        if !returned {
+            return Ok(None);
+        }
+        self.return_value(frame.location)
+    }
+
+    /// Read value returned by function which contains location `callee`,
+    /// execution must be stopped right after return from this function.
+    fn return_value(&self, callee: Location) -> anyhow::Result<Option<VariableIR>> {
+        let func = self
+            .debugee
+            .debug_info(callee.pc)
+            .find_function_by_pc(callee.global_pc)
+            .ok_or_else(|| anyhow!("returned function not found"))?;
+        let Some(r#type) = func.return_type() else {
+            return Ok(None);
+        };
+
+        let frame = uw::innermost_frame(&self.debugee, callee.pid)?;
+        let evaluator = func.unit.evaluator(&self.debugee).with_frame(&frame);
+        let evaluation_context = &dwarf::r#type::EvaluationContext {
+            evaluator: &evaluator,
+            pid: callee.pid,
+        };
+        let value = abi::read_return_value(evaluation_context, &r#type)?;
+        let parser = variable::VariableParser::new(&r#type);
+        Ok(Some(parser.parse(
+            evaluation_context,
+            variable::VariableIdentity::new(NamespaceHierarchy::default(), None),
+            Some(value),
+        )))
+    }
+
+    /// Return addresses where execution leaves code of inlined call at current location:
+    /// ends of inlined code ranges and statements of function outside of inlined code.
+    /// Empty if current location is not in inlined function code.
+    fn inlined_call_exits(&self) -> anyhow::Result<Vec<RelocatedAddress>> {
+        let location = self.current_thread_stop_at()?;
+        let Some(call) = self.innermost_inlined_call(location) else {
+            return Ok(vec![]);
+        };
+        let dwarf = self.debugee.debug_info(location.pc);
+        let mapping_offset = self.debugee.mapping_offset_for_pc(location.pc);
+        let func = dwarf
+            .find_function_by_pc(location.global_pc)
+            .ok_or_else(|| anyhow!("current function not found"))?;
+        let in_func = |addr: u64| {
+            func.die
+                .base_attributes
+                .ranges
+                .iter()
+                .any(|range| range.begin <= addr && addr < range.end)
+        };
+
+        let mut exits = call
+            .die
+            .base_attributes
+            .ranges
+            .iter()
+            .map(|range| range.end)
+            .filter(|&end| in_func(end))
+            .map(GlobalAddress::from)
+            .collect::<Vec<_>>();
+        exits.extend(
+            function_statements(dwarf, func)?
+                .into_iter()
+                .map(|place| place.address)
+                .filter(|&addr| !call.contains(addr)),
+        );
+        exits.sort_unstable_by_key(|&addr| usize::from(addr));
+        exits.dedup();
+
+        Ok(exits
+            .into_iter()
+            .map(|addr| addr.relocate(mapping_offset))
+            .collect())
+    }
+
+    /// Step to the next source line, stepping into called functions.
+    /// Entering or leaving of inlined function code is a step too,
+    /// compiler generated code (line 0) and non-statement rows of the current line are skipped.
+    /// Return true if debugee stopped at watchpoint before the next line is reached.
+    pub fn step_in(&mut self) -> anyhow::Result<bool> {
+        disable_when_not_stared!(self);
+
+        let start = self.current_thread_stop_at()?;
+        self.debugee
+            .find_place_at(start.pc)
+            .ok_or_else(|| anyhow!("not in debug frame (may be program not started?)"))?;
+        loop {
+            if self.single_step_instruction()? {
+                return Ok(true);
+            }
+            if self.is_next_line(start)? {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Return true if thread in focus reaches the next line since `start` location.
+    fn is_next_line(&self, start: Location) -> anyhow::Result<bool> {
+        let place = self
+            .debugee
+            .find_place_at(start.pc)
+            .ok_or_else(|| anyhow!("not in debug frame (may be program not started?)"))?;
+        let location = self.current_thread_stop_at()?;
+        let next_place = self
+            .debugee
+            .find_place_at(location.pc)
+            .ok_or_else(|| anyhow!("unreachable! line not found"))?;
+        if next_place == place {
+            return Ok(false);
+        }
+        if self.innermost_inlined_call(location) != self.innermost_inlined_call(start) {
+            return Ok(true);
+        }
+        let same_line = next_place.file == place.file
+            && next_place.line_number == place.line_number
+            && !next_place.is_stmt;
+        Ok(next_place.line_number != 0 && !same_line)
+    }
+
+    /// Return the most nested inlined function call which code contains `location`.
+    fn innermost_inlined_call(
+        &self,
+        location: Location,
+    ) -> Option<ContextualDieRef<'_, InlineSubroutineDie>> {
+        self.debugee
+            .debug_info(location.pc)
+            .find_inlined_calls(location.global_pc)
+            .first()
+            .copied()
+    }
+
+    /// Step to the next source line of current function, called functions are stepped over.
+    /// If execution stopped in inlined function code, then lines of this inlined call and
+    /// its callers are the lines of current function, other inlined calls are stepped over.
+    pub fn step_over(&mut self) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        let current_place = self.current_place_address()?;
+        self.run_to_function_statement(|place, _| place.address != current_place)
+    }
+
+    /// Continue until a source line greater than the current one is reached
+    /// (useful to leave a loop) or current function returns.
+    pub fn until(&mut self) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        let location = self.current_thread_stop_at()?;
+        let current_line = self
+            .debugee
+            .find_place_at(location.pc)
+            .ok_or_else(|| anyhow!("current line not found"))?
+            .line_number;
+        self.run_to_function_statement(|place, in_current_call| {
+            !in_current_call || place.line_number > current_line
+        })
+    }
+
+    /// Run until location `spec` is reached or current function returns.
+    pub fn advance(&mut self, spec: &BreakpointSpec) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+        let mut locations = match spec {
+            BreakpointSpec::Address(addr) => vec![RelocatedAddress::from(*addr)],
+            _ => {
+                let mut locations = self.executable_locations(spec)?;
+                for lib in self.debugee.libraries() {
+                    locations.extend(library_locations(lib, spec)?);
+                }
+                locations
+                    .into_iter()
+                    .filter_map(|location| match location {
+                        PCValue::Relocated(addr) => Some(addr),
+                        PCValue::Global(_) => None,
+                    })
+                    .collect()
+            }
+        };
+        if locations.is_empty() {
+            bail!("no suitable place found for {spec}");
+        }
+
+        if let Some(ret_addr) = uw::return_addr(&self.debugee, self.debugee.thread_in_focus())? {
+            locations.push(ret_addr);
+        }
+        self.run_to_temporary_traps(locations)
+    }
+
+    /// Return address of the line table row at current location.
+    fn current_place_address(&self) -> anyhow::Result<GlobalAddress> {
+        let location = self.current_thread_stop_at()?;
+        Ok(self
+            .debugee
+            .debug_info(location.pc)
+            .find_place_from_pc(location.global_pc)
+            .ok_or_else(|| anyhow!("current line not found"))?
+            .address)
+    }
+
+    /// Continue until one of current function statements accepted by `filter` is reached
+    /// or current function returns. Statements of inlined calls which are not
+    /// on the current inlined call chain are never reached (calls are stepped over).
+    /// Filter receives statement place and flag that statement belongs to the innermost
+    /// subroutine (function or inlined call) of current location.
+    fn run_to_function_statement(
+        &mut self,
+        filter: impl Fn(&Place, bool) -> bool,
+    ) -> anyhow::Result<()> {
+        let location = self.current_thread_stop_at()?;
+        let dwarf = self.debugee.debug_info(location.pc);
+        let mapping_offset = self.debugee.mapping_offset_for_pc(location.pc);
+        let func = dwarf
+            .find_function_by_pc(location.global_pc)
+            .ok_or_else(|| anyhow!("not in debug frame (may be program not started?)"))?;
+        let inlined_calls = dwarf.find_inlined_calls(location.global_pc);
+
+        let mut traps = vec![];
+        for place in function_statements(dwarf, func)? {
+            let owner_calls = dwarf.find_inlined_calls(place.address);
+            let in_call_chain = owner_calls
+                .first()
+                .map(|call| inlined_calls.contains(call))
+                .unwrap_or(true);
+            let in_current_call = owner_calls.first() == inlined_calls.first();
+            if in_call_chain && filter(&place, in_current_call) {
+                traps.push(place.address.relocate(mapping_offset));
+            }
+        }
+
+        if let Some(ret_addr) = uw::return_addr(&self.debugee, self.debugee.thread_in_focus())? {
+            traps.push(ret_addr);
+        }
+        self.run_to_temporary_traps(traps)
+    }
+
+    /// Set temporary traps at `addresses` (except already trapped), continue execution
+    /// and remove traps when execution stops. Debugee always stops at any of `addresses`,
+    /// even if user breakpoint at the same address has unsatisfied condition.
+    fn run_to_temporary_traps(
+        &mut self,
+        mut addresses: Vec<RelocatedAddress>,
+    ) -> anyhow::Result<()> {
+        addresses.sort_unstable_by_key(|&addr| usize::from(addr));
+        addresses.dedup();
+        let to_delete = addresses
+            .iter()
+            .copied()
+            .filter(|&addr| !self.breakpoints.contains_key(&PCValue::Relocated(addr)))
+            .collect::<Vec<_>>();
+        to_delete
+            .iter()
+            .try_for_each(|&addr| self.add_trap(PCValue::Relocated(addr)))?;
+
+        self.temporary_traps = addresses.into_iter().collect();
+        let result = self.continue_execution(self.resume_mode(true));
+        self.temporary_traps.clear();
+        result?;
+
+        to_delete
+            .into_iter()
+            .try_for_each(|addr| self.remove_trap(PCValue::Relocated(addr)))
+    }
+
+    /// Set breakpoint at function, return breakpoint number.
+    /// Breakpoint bound to all function instances (monomorphizations and inlined call sites)
+    /// in debugee and loaded shared libraries.
+    /// Function may be qualified with shared library name (like `libfoo.so::handler`),
+    /// if library not loaded yet breakpoint stays pending until library loading.
+    pub fn set_breakpoint_at_fn(&mut self, name: &str) -> anyhow::Result<u32> {
+        let spec = BreakpointSpec::Function(name.to_string());
+
+        let mut locations = self.executable_locations(&spec)?;
+        for lib in self.debugee.libraries() {
+            locations.extend(library_locations(lib, &spec)?);
+        }
+        if locations.is_empty() && spec.library_function().is_none() {
+            bail!("function not found");
+        }
+
+        self.set_user_breakpoint(spec, locations)
+    }
+
+    /// Set breakpoint at source line, return breakpoint number.
+    /// Breakpoint bound to all code instances of the line in debugee and loaded shared libraries.
+    pub fn set_breakpoint_at_line(&mut self, fine_name: &str, line: u64) -> anyhow::Result<u32> {
+        let spec = BreakpointSpec::Line(fine_name.to_string(), line);
+
+        let mut locations = self.executable_locations(&spec)?;
+        for lib in self.debugee.libraries() {
+            locations.extend(library_locations(lib, &spec)?);
+        }
+        if locations.is_empty() {
+            bail!("no suitable place found for {fine_name}:{line}");
+        }
+
+        self.set_user_breakpoint(spec, locations)
+    }
+
+    /// Update list of shared libraries loaded by dynamic linker.
+    /// Breakpoints are bound to new libraries, locations in unloaded libraries are removed.
+    fn update_libraries(&mut self) -> anyhow::Result<()> {
+        // dynamic linker calls `r_brk` function on each change of loaded objects list
+        if let Some(brk) = self.debugee.rendezvous_brk() {
+            if !self.breakpoints.contains_key(&PCValue::Relocated(brk)) {
+                self.add_trap(PCValue::Relocated(brk))?;
+            }
+        }
+
+        let update = self.debugee.update_libraries()?;
+
+        for lib in update.unloaded {
+            // library memory already unmapped, so traps are removed without code restoring
+            let in_library =
+                |addr: &PCValue| matches!(addr, PCValue::Relocated(addr) if lib.contains(*addr));
+            self.breakpoints.retain(|addr, _| !in_library(addr));
+            self.user_breakpoints.remove_locations(in_library);
+            if let Some(ref mut catch) = self.panic_catch {
+                catch.remove_locations(in_library);
+            }
+        }
+
+        for idx in update.loaded {
+            let lib = &self.debugee.libraries()[idx];
+            let bindings = self
+                .user_breakpoints
+                .iter()
+                .map(|brkpt| {
+                    let locations = library_locations(lib, &brkpt.spec)?;
+                    Ok((brkpt.number, brkpt.enabled, locations))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let (handler, rust_panic) = library_panic_locations(lib);
+            self.bind_user_breakpoints(bindings)?;
+
+            if self.panic_catch.is_some() {
+                for &addr in handler.iter().chain(&rust_panic) {
+                    if !self.breakpoints.contains_key(&addr) {
+                        self.add_trap(addr)?;
+                    }
+                }
+                if let Some(ref mut catch) = self.panic_catch {
+                    catch.extend(handler, rust_panic);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add new locations to user breakpoints, traps are written for enabled breakpoints.
+    /// `bindings` contains breakpoint number, enabled flag and locations.
+    fn bind_user_breakpoints(
+        &mut self,
+        bindings: Vec<(u32, bool, Vec<PCValue>)>,
+    ) -> anyhow::Result<()> {
+        for (number, enabled, locations) in bindings {
+            if enabled {
+                for &addr in &locations {
+                    if !self.breakpoints.contains_key(&addr) {
+                        self.add_trap(addr)?;
+                    }
+                }
+            }
+            if let Some(brkpt) = self.user_breakpoints.get_mut(number) {
+                brkpt.locations.extend(locations);
+            }
+        }
+        Ok(())
+    }
+
+    /// Return breakpoint locations in debugee executable.
+    fn executable_locations(&self, spec: &BreakpointSpec) -> anyhow::Result<Vec<PCValue>> {
+        match spec {
+            BreakpointSpec::Address(_) => Ok(vec![]),
+            BreakpointSpec::Line(file, line) => Ok(self
+                .debugee
+                .dwarf
+                .find_stmt_lines(file, *line)
+                .into_iter()
+                .map(|place| self.breakpoint_location(place.address))
+                .collect()),
+            BreakpointSpec::Function(_) if spec.library_function().is_some() => Ok(vec![]),
+            BreakpointSpec::Function(name) => {
+                function_locations(&self.debugee.dwarf, name, |addr| {
+                    self.breakpoint_location(addr)
+                })
+            }
+        }
+    }
+
+    /// Set process which debugger follows when debugee forks.
+    pub fn set_follow_fork_mode(&mut self, mode: FollowForkMode) {
+        self.follow_fork_mode = mode;
+    }
+
+    /// If `detach` is false, process which is not followed after fork stays traced and
+    /// stopped, debugger may switch to it with [`Debugger::switch_inferior`].
+    pub fn set_detach_on_fork(&mut self, detach: bool) {
+        self.detach_on_fork = detach;
+    }
+
+    /// Return process ids of all traced processes, process in focus goes first.
+    pub fn inferiors(&self) -> Vec<Pid> {
+        iter::once(&self.debugee)
+            .chain(self.inferiors.iter().map(|inferior| &inferior.debugee))
+            .map(|debugee| debugee.threads_ctl().proc_pid())
+            .collect()
+    }
+
+    /// Move debugger focus to traced process `pid`, state of previous process is saved
+    /// until debugger switches back to it. Exited process is forgotten.
+    pub fn switch_inferior(&mut self, pid: Pid) -> anyhow::Result<()> {
+        if self.debugee.threads_ctl().proc_pid() == pid {
+            return Ok(());
+        }
+        let idx = self
+            .inferiors
+            .iter()
+            .position(|inferior| inferior.debugee.threads_ctl().proc_pid() == pid)
+            .ok_or_else(|| anyhow!("process {pid} is not traced"))?;
+
+        if self.debugee.execution_status == ExecutionStatus::InProgress {
+            // process leaves focus, so its threads must be stopped
+            self.debugee.threads_ctl_mut().interrupt_running()?;
+        }
+        let inferior = self.inferiors.remove(idx);
+        let previous = self.swap_inferior(inferior);
+        if previous.debugee.execution_status != ExecutionStatus::Exited {
+            self.inferiors.push(previous);
+        }
+        Ok(())
+    }
+
+    /// Replace state of process in focus with `inferior`, return state of previous process.
+    fn swap_inferior(&mut self, mut inferior: Inferior) -> Inferior {
+        mem::swap(&mut self.debugee, &mut inferior.debugee);
+        mem::swap(&mut self.breakpoints, &mut inferior.breakpoints);
+        mem::swap(&mut self.user_breakpoints, &mut inferior.user_breakpoints);
+        mem::swap(&mut self.watchpoints, &mut inferior.watchpoints);
+        mem::swap(&mut self.panic_catch, &mut inferior.panic_catch);
+        self.frame_in_focus.set(0);
+        inferior
+    }
+
+    /// Create state of process `child` forked by debugee.
+    /// Child inherits all traps and user breakpoints, but not watchpoints.
+    fn fork_inferior(&self, child: Pid, vfork: bool) -> anyhow::Result<Inferior> {
+        let mut breakpoints = HashMap::with_capacity(self.breakpoints.len());
+        for (&addr, brkpt) in &self.breakpoints {
+            let child_brkpt = brkpt.fork(child);
+            if self.is_watchpoint_scope_only_trap(addr) {
+                // memory of vfork child is a memory of debugee, so trap stays there
+                if !vfork && child_brkpt.is_enabled() {
+                    child_brkpt.disable()?;
+                }
+                continue;
+            }
+            breakpoints.insert(addr, child_brkpt);
+        }
+
+        Ok(Inferior {
+            debugee: self.debugee.fork(child)?,
+            breakpoints,
+            user_breakpoints: self.user_breakpoints.clone(),
+            watchpoints: vec![],
+            panic_catch: self.panic_catch.clone(),
+        })
+    }
+
+    /// Return true if trap at `addr` is used only for tracking of watchpoints scope.
+    fn is_watchpoint_scope_only_trap(&self, addr: PCValue) -> bool {
+        matches!(addr, PCValue::Relocated(pc) if self.is_watchpoint_scope_breakpoint(pc))
+            && self.user_breakpoints.enabled_at(addr).next().is_none()
+            && !self.is_panic_trap(addr)
+    }
+
+    /// Save debugee state into a new checkpoint, return checkpoint number.
+    /// Checkpoint is a stopped copy of debugee process, only thread in focus is copied.
+    pub fn checkpoint(&mut self) -> anyhow::Result<u32> {
+        disable_when_not_stared!(self);
+        let child = checkpoint::fork_process(self.debugee.thread_in_focus())?;
+        let inferior = self.fork_inferior(child, false)?;
+        self.last_checkpoint_number += 1;
+        self.checkpoints.push(Checkpoint {
+            number: self.last_checkpoint_number,
+            inferior,
+        });
+        Ok(self.last_checkpoint_number)
+    }
+
+    /// Return description of all checkpoints sorted by number.
+    pub fn checkpoints(&self) -> anyhow::Result<Vec<CheckpointInfo>> {
+        self.checkpoints
+            .iter()
+            .map(|checkpoint| {
+                let debugee = &checkpoint.inferior.debugee;
+                let location = debugee.current_thread_stop_at()?;
+                let place = debugee.find_place_at(location.pc);
+                Ok(CheckpointInfo {
+                    number: checkpoint.number,
+                    pid: checkpoint.pid(),
+                    pc: location.pc,
+                    file: place.as_ref().map(|place| place.file.to_path_buf()),
+                    line: place.map(|place| place.line_number),
+                })
+            })
+            .collect()
+    }
+
+    /// Restart debugee from checkpoint `number`. Copy of checkpoint process replaces debugee
+    /// process (debugee process is killed or detached if debugger attached to it), checkpoint
+    /// stays untouched, so debugee may be restarted from it again.
+    /// Current breakpoints are set in a new process, watchpoints are removed.
+    pub fn restart_checkpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let snapshot = &self
+            .checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.number == number)
+            .ok_or_else(|| anyhow!("checkpoint {number} not found"))?
+            .inferior;
+        if self.debugee.execution_status == ExecutionStatus::InProgress {
+            self.debugee.threads_ctl_mut().interrupt_running()?;
+        }
+
+        let child = checkpoint::fork_process(snapshot.debugee.thread_in_focus())?;
+        let debugee = snapshot.debugee.fork(child)?;
+        // new process memory contains traps of checkpoint, replace them with current traps
+        snapshot
+            .breakpoints
+            .values()
+            .filter(|brkpt| brkpt.is_enabled())
+            .try_for_each(|brkpt| brkpt.fork(child).disable())?;
+        let mut breakpoints = HashMap::with_capacity(self.breakpoints.len());
+        for (&addr, brkpt) in &self.breakpoints {
+            if self.is_watchpoint_scope_only_trap(addr) {
+                continue;
+            }
+            let child_brkpt = brkpt.fork(child);
+            // trap may be located in library which is not loaded at checkpoint
+            if child_brkpt.is_enabled() && weak_error!(child_brkpt.enable()).is_none() {
+                continue;
+            }
+            breakpoints.insert(addr, child_brkpt);
+        }
+
+        let restarted = Inferior {
+            debugee,
+            breakpoints,
+            user_breakpoints: self.user_breakpoints.clone(),
+            watchpoints: vec![],
+            panic_catch: self.panic_catch.clone(),
+        };
+        self.swap_inferior(restarted).release();
+
+        let location = self.current_thread_stop_at()?;
+        self.hooks
+            .on_trap(location.pc, self.debugee.find_place_at(location.pc))
+    }
+
+    /// Remove checkpoint `number`, checkpoint process is killed.
+    pub fn delete_checkpoint(&mut self, number: u32) -> anyhow::Result<()> {
+        let idx = self
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.number == number)
+            .ok_or_else(|| anyhow!("checkpoint {number} not found"))?;
+        self.checkpoints.remove(idx).release();
+        Ok(())
+    }
+
+    /// Handle fork of debugee, new process `child` is stopped.
+    /// Depending on follow-fork-mode debugger stays with debugee or switches to new process,
+    /// process which is not followed is detached or kept stopped (if detach-on-fork is off).
+    fn follow_fork(&mut self, child: Pid, vfork: bool) -> anyhow::Result<()> {
+        let child = self.fork_inferior(child, vfork)?;
+        // memory of vfork child is a memory of debugee, so code restored only for fork
+        let restore_code = !vfork;
+
+        match self.follow_fork_mode {
+            FollowForkMode::Parent if self.detach_on_fork => {
+                info!(
+                    "detach after fork from child process {}",
+                    child.debugee.threads_ctl().proc_pid()
+                );
+                child.detach(restore_code)?;
+            }
+            FollowForkMode::Parent => self.inferiors.push(child),
+            FollowForkMode::Child => {
+                self.debugee.threads_ctl_mut().interrupt_running()?;
+                let parent = self.swap_inferior(child);
+                info!(
+                    "switch to child process {}",
+                    self.debugee.threads_ctl().proc_pid()
+                );
+                if self.detach_on_fork {
+                    parent.detach(restore_code)?;
+                } else {
+                    self.inferiors.push(parent);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle exec in debugee process `pid`: debug information of new program is loaded,
+    /// user breakpoints are bound to new program.
+    fn exec_debugee(&mut self, pid: Pid) -> anyhow::Result<()> {
+        let path = fs::read_link(format!("/proc/{pid}/exe"))?;
+        let file = fs::File::open(&path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let object = object::File::parse(&*mmap)?;
+        info!("process {pid} executes new program {}", path.display());
+
+        self.debugee = self.debugee.exec(&path, &object)?;
+        // memory of previous program is gone, so traps are removed without code restoring
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+        self.user_breakpoints.remove_locations(|_| true);
+
+        let entry_point = GlobalAddress::from(object.entry() as usize);
+        self.add_trap(self.breakpoint_location(entry_point))?;
+
+        let bindings = self
+            .user_breakpoints
+            .iter()
+            .map(|brkpt| {
+                let locations = self.executable_locations(&brkpt.spec)?;
+                Ok((brkpt.number, brkpt.enabled, locations))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.bind_user_breakpoints(bindings)?;
+
+        if self.panic_catch.take().is_some() {
+            weak_error!(self.catch_panic());
+        }
+        Ok(())
+    }
+
+    /// Return address of breakpoint location, relocated if debugee already started.
+    fn breakpoint_location(&self, addr: GlobalAddress) -> PCValue {
+        if self.debugee.execution_status == ExecutionStatus::InProgress {
+            PCValue::Relocated(addr.relocate(self.debugee.mapping_offset()))
+        } else {
+            PCValue::Global(addr)
+        }
+    }
+
+    fn variables_into_variable_ir<D: AsAllocatedValue>(
+        &self,
+        frame: &StackFrame,
+        vars: &[ContextualDieRef<D>],
+        select_plan: SelectPlan,
+    ) -> anyhow::Result<Vec<VariableIR>> {
         let mut type_cache = self.type_cache.borrow_mut();
 
         Ok(vars
@@ -445,13 +1914,13 @@ impl Debugger {
                         name = var_name.unwrap_or_default()
                     ));
                 let r#type = weak_error!(mb_type)?;
-                let mb_value = var.read_value_at_location(location, &self.debugee, r#type);
+                let mb_value = var.read_value_in_frame(frame, &self.debugee, r#type);
 
-                let evaluator = var.unit.evaluator(&self.debugee);
+                let evaluator = var.unit.evaluator(&self.debugee).with_frame(frame);
                 let parser = variable::VariableParser::new(r#type);
                 let evaluation_context = &dwarf::r#type::EvaluationContext {
                     evaluator: &evaluator,
-                    pid: location.pid,
+                    pid: frame.location.pid,
                 };
 
                 let var = parser.parse(
@@ -469,57 +1938,112 @@ impl Debugger {
             .collect())
     }
 
-    // Read all local variables from current thread.
+    // Read all local variables from selected frame of current thread.
     pub fn read_local_variables(&self) -> anyhow::Result<Vec<VariableIR>> {
         disable_when_not_stared!(self);
 
-        let location = self.current_thread_stop_at()?;
+        let frame = self.frame_in_focus()?;
+        let location = frame.location;
         let current_func = self
             .debugee
-            .dwarf
+            .debug_info(location.pc)
             .find_function_by_pc(location.global_pc)
             .ok_or_else(|| anyhow!("not in function"))?;
         let vars = current_func.local_variables(location.global_pc);
-        self.variables_into_variable_ir(location, &vars, SelectPlan::empty())
+        self.variables_into_variable_ir(&frame, &vars, SelectPlan::empty())
     }
 
-    // Read any variable from current thread.
+    // Read any variable from selected frame of current thread.
     pub fn read_variable(&self, select_plan: SelectPlan) -> anyhow::Result<Vec<VariableIR>> {
         disable_when_not_stared!(self);
-        let location = self.current_thread_stop_at()?;
+        let frame = self.frame_in_focus()?;
         let variable_name = select_plan
             .base_variable_name()
             .ok_or(anyhow!("invalid select expression"))?;
-        let vars = self.debugee.dwarf.find_variables(location, variable_name);
-        self.variables_into_variable_ir(self.current_thread_stop_at()?, &vars, select_plan)
+        let vars = self
+            .debugee
+            .debug_info(frame.location.pc)
+            .find_variables(frame.location, variable_name);
+        self.variables_into_variable_ir(&frame, &vars, select_plan)
+    }
+
+    /// Evaluate expression (arithmetic, comparison, cast, etc.) in selected frame of current thread,
+    /// result is a new variable with name `name`.
+    pub fn evaluate_expression(&self, expr: &Expr, name: &str) -> anyhow::Result<VariableIR> {
+        disable_when_not_stared!(self);
+        expr.evaluate(name, |plan| self.read_variable_or_argument(plan))
+    }
+
+    /// Evaluate expression as an address in debugee memory. Pointers evaluated into their values,
+    /// integers into themselves and other variables into their locations.
+    /// Names that are not variables in scope resolved as symbols.
+    pub fn evaluate_address(&self, expr: &Expr) -> anyhow::Result<RelocatedAddress> {
+        disable_when_not_stared!(self);
+
+        let var = expr.evaluate("address", |plan| {
+            self.read_variable_or_argument(plan).or_else(|err| {
+                let (Some(Operation::FindVariable(name)), 1) = (plan.plan.front(), plan.plan.len())
+                else {
+                    return Err(err);
+                };
+                let addr = self.symbol_address(name).map_err(|_| err)?;
+                Ok(VariableIR::Scalar(ScalarVariable {
+                    identity: VariableIdentity::new(Default::default(), Some(name.clone())),
+                    raw_address: None,
+                    type_name: Some("usize".to_string()),
+                    value: Some(SupportedScalar::Usize(usize::from(addr))),
+                }))
+            })
+        })?;
+
+        let addr = match var {
+            VariableIR::Scalar(_) | VariableIR::Pointer(_) => {
+                match Value::from_variable(&var)?.into_literal()? {
+                    Literal::Int(addr) => usize::try_from(addr).ok(),
+                    _ => None,
+                }
+            }
+            _ => var.raw_address(),
+        };
+        addr.map(RelocatedAddress::from)
+            .ok_or_else(|| anyhow!("expression is not an address"))
     }
 
-    // Read current function parameters.
+    // Read parameters of function in selected frame.
     pub fn read_arguments(&self) -> anyhow::Result<Vec<VariableIR>> {
         disable_when_not_stared!(self);
 
-        let location = self.current_thread_stop_at()?;
+        let frame = self.frame_in_focus()?;
+        let location = frame.location;
         let current_func = self
             .debugee
-            .dwarf
+            .debug_info(location.pc)
             .find_function_by_pc(location.global_pc)
             .ok_or_else(|| anyhow!("not in function"))?;
         let params = current_func.parameters();
-        self.variables_into_variable_ir(location, &params, SelectPlan::empty())
+        self.variables_into_variable_ir(&frame, &params, SelectPlan::empty())
     }
 
-    // Read any argument from current function.
+    // Read any argument of function in selected frame.
     pub fn read_argument(&self, select_plan: SelectPlan) -> anyhow::Result<Vec<VariableIR>> {
         disable_when_not_stared!(self);
+        self.read_argument_in_frame(&self.frame_in_focus()?, select_plan)
+    }
 
+    /// Read any argument of function in stack `frame`.
+    fn read_argument_in_frame(
+        &self,
+        frame: &StackFrame,
+        select_plan: SelectPlan,
+    ) -> anyhow::Result<Vec<VariableIR>> {
         let arg_name = select_plan
             .base_variable_name()
             .ok_or(anyhow!("invalid select expression"))?;
 
-        let location = self.current_thread_stop_at()?;
+        let location = frame.location;
         let current_func = self
             .debugee
-            .dwarf
+            .debug_info(location.pc)
             .find_function_by_pc(location.global_pc)
             .ok_or_else(|| anyhow!("not in function"))?;
         let params = current_func.parameters();
@@ -535,7 +2059,307 @@ impl Debugger {
                     .unwrap_or_default()
             })
             .collect::<Vec<_>>();
-        self.variables_into_variable_ir(location, &params, select_plan)
+        self.variables_into_variable_ir(frame, &params, select_plan)
+    }
+
+    /// Assign `value` to a variable (or argument) or its part selected by `select_plan`
+    /// in the frame in focus. Value is encoded according to DWARF type of target,
+    /// scalars, c-style enums, pointers and rust enums (variants without fields) can be assigned.
+    pub fn set_variable(&self, select_plan: SelectPlan, value: &str) -> anyhow::Result<()> {
+        disable_when_not_stared!(self);
+
+        let frame = self.frame_in_focus()?;
+        match self.find_value_die(&frame, &select_plan)? {
+            ValueDie::Variable(var) => self.assign_value(&frame, &var, &select_plan, value),
+            ValueDie::Parameter(param) => self.assign_value(&frame, &param, &select_plan, value),
+        }
+    }
+
+    /// Find variable or parameter which is a base of select expression in stack `frame`:
+    /// local variable, function parameter or static variable.
+    fn find_value_die(
+        &self,
+        frame: &StackFrame,
+        select_plan: &SelectPlan,
+    ) -> anyhow::Result<ValueDie<'_>> {
+        let name = select_plan
+            .base_variable_name()
+            .ok_or(anyhow!("invalid select expression"))?;
+        let location = frame.location;
+        let dwarf = self.debugee.debug_info(location.pc);
+        let func = dwarf
+            .find_function_by_pc(location.global_pc)
+            .ok_or_else(|| anyhow!("not in function"))?;
+
+        // declarations of shadowed variables goes before the innermost one
+        let local_var = func
+            .local_variables(location.global_pc)
+            .into_iter()
+            .rev()
+            .find(|var| var.die.name() == Some(name));
+        if let Some(var) = local_var {
+            return Ok(ValueDie::Variable(var));
+        }
+        let param = func
+            .parameters()
+            .into_iter()
+            .find(|param| param.die.base_attributes.name.as_deref() == Some(name));
+        if let Some(param) = param {
+            return Ok(ValueDie::Parameter(param));
+        }
+
+        let static_var = dwarf
+            .find_variables(location, name)
+            .into_iter()
+            .find(|var| var.assume_parent_function().is_none())
+            .ok_or_else(|| anyhow!("variable {name} not found"))?;
+        Ok(ValueDie::Variable(static_var))
+    }
+
+    fn assign_value<D: AsAllocatedValue>(
+        &self,
+        frame: &StackFrame,
+        var: &ContextualDieRef<D>,
+        select_plan: &SelectPlan,
+        value: &str,
+    ) -> anyhow::Result<()> {
+        let name = var.die.name().unwrap_or_default();
+        let r#type = var
+            .r#type()
+            .ok_or_else(|| anyhow!("unknown type for variable {name}"))?;
+        let var_value = var
+            .read_value_in_frame(frame, &self.debugee, &r#type)
+            .ok_or_else(|| anyhow!("value of variable {name} is unavailable"))?;
+
+        let evaluator = var.unit.evaluator(&self.debugee).with_frame(frame);
+        let eval_ctx = dwarf::r#type::EvaluationContext {
+            evaluator: &evaluator,
+            pid: frame.location.pid,
+        };
+        let target = assign::find_target(&eval_ctx, &r#type, &var_value, select_plan)?;
+        let encoded = assign::encode_value(&r#type, target.type_id, value)?;
+
+        match target.place {
+            assign::Place::Memory(addr) => {
+                write_memory_by_pid(frame.location.pid, addr + encoded.offset, &encoded.data)?
+            }
+            // register values of outer frames are restored by unwinding,
+            // so only registers of the innermost frame can be written
+            assign::Place::Variable { .. } if frame.num != 0 => {
+                bail!("variable {name} is not in memory, it can be changed in the innermost frame only")
+            }
+            assign::Place::Variable { offset } => var.write_value_in_frame(
+                frame,
+                &self.debugee,
+                offset + encoded.offset,
+                &encoded.data,
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Read value selected by `select_plan` in stack `frame`.
+    fn select_value<D: AsAllocatedValue>(
+        &self,
+        frame: &StackFrame,
+        var: &ContextualDieRef<D>,
+        select_plan: &SelectPlan,
+    ) -> anyhow::Result<SelectedValue> {
+        let name = var.die.name().unwrap_or_default();
+        let r#type = var
+            .r#type()
+            .ok_or_else(|| anyhow!("unknown type for variable {name}"))?;
+        let var_value = var
+            .read_value_in_frame(frame, &self.debugee, &r#type)
+            .ok_or_else(|| anyhow!("value of variable {name} is unavailable"))?;
+
+        let evaluator = var.unit.evaluator(&self.debugee).with_frame(frame);
+        let eval_ctx = dwarf::r#type::EvaluationContext {
+            evaluator: &evaluator,
+            pid: frame.location.pid,
+        };
+        let target = assign::find_target(&eval_ctx, &r#type, &var_value, select_plan)?;
+        let size = r#type
+            .type_size_in_bytes(&eval_ctx, target.type_id)
+            .ok_or_else(|| anyhow!("unknown size of {}", select_plan.source))?
+            as usize;
+
+        let (data, address) = match target.place {
+            assign::Place::Memory(addr) => (
+                read_memory_by_pid(frame.location.pid, addr, size)?,
+                Some(addr),
+            ),
+            assign::Place::Variable { offset } => (
+                var_value
+                    .raw_data
+                    .get(offset..offset + size)
+                    .ok_or_else(|| anyhow!("value of {} is unavailable", select_plan.source))?
+                    .to_vec(),
+                var_value.address.map(|addr| addr + offset),
+            ),
+        };
+        Ok(SelectedValue {
+            source: select_plan.source.clone(),
+            type_name: r#type.type_name(target.type_id),
+            data,
+            address,
+        })
+    }
+
+    /// Call debugee function or method by thread in focus. Arguments are evaluated in
+    /// the frame in focus, only thread in focus runs while function executes,
+    /// thread state restored after the call. Return value returned by function
+    /// or `None` if function returns nothing (unit type).
+    pub fn call_function(&self, call: Call) -> anyhow::Result<Option<VariableIR>> {
+        disable_when_not_stared!(self);
+
+        let frame = self.frame_in_focus()?;
+        let tid = frame.location.pid;
+        let innermost_frame = self.debugee.stack_frame(tid, 0)?;
+        let location = innermost_frame.location;
+        let dwarf = self.debugee.debug_info(location.pc);
+
+        let select = |select_plan: &SelectPlan| match self.find_value_die(&frame, select_plan)? {
+            ValueDie::Variable(var) => self.select_value(&frame, &var, select_plan),
+            ValueDie::Parameter(param) => self.select_value(&frame, &param, select_plan),
+        };
+
+        let mut args = vec![];
+        let func = match call.callee {
+            Callee::Function(ref name) => {
+                let mut functions = dwarf.find_functions(name);
+                if functions.len() > 1 {
+                    bail!("function name {name} is ambiguous, use qualified name")
+                }
+                functions
+                    .pop()
+                    .ok_or_else(|| anyhow!("function {name} not found"))?
+            }
+            Callee::Method {
+                ref receiver,
+                ref name,
+            } => {
+                let receiver = select(receiver)?;
+                let func = dwarf
+                    .find_functions(name)
+                    .into_iter()
+                    .find(|func| {
+                        func.formal_parameters().first().is_some_and(|param| {
+                            param.die.base_attributes.name.as_deref() == Some("self")
+                                && param.r#type().is_some_and(|r#type| {
+                                    call::receiver_matches(&r#type, &receiver)
+                                })
+                        })
+                    })
+                    .ok_or_else(|| {
+                        let type_name = receiver.type_name.as_deref().unwrap_or("unknown");
+                        anyhow!("method {name} of type {type_name} not found")
+                    })?;
+                args.push(Argument::Receiver(receiver));
+                func
+            }
+        };
+
+        let params = func.formal_parameters();
+        if params.len() != args.len() + call.args.len() {
+            bail!(
+                "function expects {} arguments, {} given",
+                params.len(),
+                args.len() + call.args.len()
+            );
+        }
+        for arg in &call.args {
+            args.push(match arg {
+                CallArgument::Literal(text) => Argument::Literal(text),
+                CallArgument::Str(string) => Argument::Str(string),
+                CallArgument::Variable(plan) => Argument::Value(select(plan)?),
+                CallArgument::Ref(plan) => Argument::Ref(select(plan)?),
+            });
+        }
+
+        let evaluator = func
+            .unit
+            .evaluator(&self.debugee)
+            .with_frame(&innermost_frame);
+        let eval_ctx = &dwarf::r#type::EvaluationContext {
+            evaluator: &evaluator,
+            pid: tid,
+        };
+        let mut inferior_call = InferiorCall::new(tid)?;
+        let return_type = func.return_type();
+        if let Some(ref r#type) = return_type {
+            inferior_call.reserve_return_value(eval_ctx, r#type)?;
+        }
+
+        for (param, arg) in params.iter().zip(args) {
+            let r#type = param
+                .r#type()
+                .ok_or_else(|| anyhow!("unknown type of argument {}", arg.source()))?;
+            let data = call::argument_data(&mut inferior_call, eval_ctx, &r#type, arg)?;
+            inferior_call.push_argument(eval_ctx, &r#type, &data)?;
+        }
+
+        let func_addr = func
+            .die
+            .base_attributes
+            .ranges
+            .iter()
+            .map(|range| range.begin)
+            .min()
+            .map(|addr| {
+                GlobalAddress::from(addr as usize)
+                    .relocate(self.debugee.mapping_offset_for_pc(location.pc))
+            })
+            .ok_or_else(|| anyhow!("function address is unknown"))?;
+
+        let result = self.execute_call(inferior_call, func_addr, || {
+            return_type
+                .as_ref()
+                .map(|r#type| abi::read_return_value(eval_ctx, r#type))
+                .transpose()
+        })?;
+
+        let (Some(r#type), Some(value)) = (return_type, result) else {
+            return Ok(None);
+        };
+        let parser = variable::VariableParser::new(&r#type);
+        Ok(Some(parser.parse(
+            eval_ctx,
+            variable::VariableIdentity::new(NamespaceHierarchy::default(), Some(call.source)),
+            Some(value),
+        )))
+    }
+
+    /// Execute prepared call of function at `func_addr`, see [`InferiorCall::execute`].
+    /// Function returns to program entry point (which is never executed again),
+    /// trap is placed there for the call duration.
+    fn execute_call<T>(
+        &self,
+        inferior_call: InferiorCall,
+        func_addr: RelocatedAddress,
+        on_return: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let trap_addr = self
+            .debugee
+            .control_flow
+            .program_ep()
+            .relocate(self.debugee.mapping_offset());
+        let trap = Breakpoint::new(
+            PCValue::Relocated(trap_addr),
+            self.debugee.threads_ctl().proc_pid(),
+        );
+        let trap_exists = self
+            .breakpoints
+            .get(&trap.addr)
+            .is_some_and(Breakpoint::is_enabled);
+        if !trap_exists {
+            trap.enable()?;
+        }
+        let result = inferior_call.execute(func_addr, trap_addr, on_return);
+        if !trap_exists {
+            trap.disable()?;
+        }
+        result
     }
 
     pub fn get_register_value(&self, register_name: &str) -> anyhow::Result<u64> {
@@ -553,7 +2377,7 @@ impl Debugger {
     ) -> anyhow::Result<RegisterDump> {
         self.get_registers(Location {
             pc,
-            global_pc: pc.into_global(self.debugee.mapping_offset()),
+            global_pc: pc.into_global(self.debugee.mapping_offset_for_pc(pc)),
             pid: self.debugee.thread_in_focus(),
         })
     }
@@ -561,9 +2385,11 @@ impl Debugger {
     pub fn get_registers(&self, at_location: Location) -> anyhow::Result<RegisterDump> {
         disable_when_not_stared!(self);
         let current_location = self.current_thread_stop_at()?;
-        self.debugee
-            .dwarf
-            .registers(&self.debugee, at_location, current_location)
+        self.debugee.debug_info(at_location.pc).registers(
+            &self.debugee,
+            at_location,
+            &RegisterDump::current(current_location.pid)?,
+        )
     }
 
     pub fn set_register_value(&self, register_name: &str, val: u64) -> anyhow::Result<()> {
@@ -577,44 +2403,145 @@ impl Debugger {
     }
 }
 
-impl Drop for Debugger {
-    fn drop(&mut self) {
-        match self.debugee.execution_status {
-            ExecutionStatus::Unload => {
-                signal::kill(self.debugee.threads_ctl().proc_pid(), Signal::SIGKILL)
-                    .expect("kill debugee");
-                waitpid(self.debugee.threads_ctl().proc_pid(), None).expect("waiting child");
+/// Variable or function parameter die.
+enum ValueDie<'a> {
+    Variable(ContextualDieRef<'a, VariableDie>),
+    Parameter(ContextualDieRef<'a, ParameterDie>),
+}
+
+/// Return breakpoint locations for all instances of function `name` in `dwarf`.
+fn function_locations(
+    dwarf: &DebugeeContext,
+    name: &str,
+    relocate: impl Fn(GlobalAddress) -> PCValue,
+) -> anyhow::Result<Vec<PCValue>> {
+    dwarf
+        .find_function_instances(name)
+        .into_iter()
+        .map(|instance| {
+            let entry = dwarf
+                .find_place_from_pc(instance.low_pc)
+                .ok_or_else(|| anyhow!("invalid function entry"))?;
+            // inlined instances have no prologue
+            let addr = match entry.prologue_end(instance.high_pc) {
+                Some(body) if !instance.inlined => body.address,
+                _ => entry.address,
+            };
+            Ok(relocate(addr))
+        })
+        .collect()
+}
+
+/// Return places of statements in function code.
+fn function_statements<'a>(
+    dwarf: &'a DebugeeContext,
+    func: ContextualDieRef<FunctionDie>,
+) -> anyhow::Result<Vec<Place<'a>>> {
+    let mut statements = vec![];
+    for range in func.die.base_attributes.ranges.iter() {
+        let mut line = dwarf
+            .find_place_from_pc(GlobalAddress::from(range.begin))
+            .ok_or_else(|| anyhow!("unknown function range"))?;
+
+        while u64::from(line.address) < range.end {
+            let next = line.next();
+            if line.is_stmt {
+                statements.push(line);
             }
-            ExecutionStatus::InProgress => {
-                self.debugee.threads_ctl().dump().iter().for_each(|thread| {
-                    sys::ptrace::detach(thread.pid, None).expect("detach thread")
-                });
-                signal::kill(self.debugee.threads_ctl().proc_pid(), Signal::SIGKILL)
-                    .expect("kill debugee");
-                waitpid(self.debugee.threads_ctl().proc_pid(), None).expect("waiting child");
+
+            match next {
+                None => break,
+                Some(n) => line = n,
             }
-            ExecutionStatus::Exited => {}
         }
     }
+    Ok(statements)
 }
 
-/// Read N bytes from `PID` process.
-pub fn read_memory_by_pid(pid: Pid, addr: usize, read_n: usize) -> nix::Result<Vec<u8>> {
-    let mut read_reminder = read_n as isize;
-    let mut result = Vec::with_capacity(read_n);
+/// Return breakpoint locations in shared library.
+fn library_locations(lib: &SharedLibrary, spec: &BreakpointSpec) -> anyhow::Result<Vec<PCValue>> {
+    let relocate = |addr: GlobalAddress| PCValue::Relocated(addr.relocate(lib.load_bias));
+    match spec {
+        BreakpointSpec::Address(_) => Ok(vec![]),
+        BreakpointSpec::Line(file, line) => Ok(lib
+            .dwarf
+            .find_stmt_lines(file, *line)
+            .into_iter()
+            .map(|place| relocate(place.address))
+            .collect()),
+        BreakpointSpec::Function(name) => match spec.library_function() {
+            Some((library, _)) if !lib.is(library) => Ok(vec![]),
+            Some((_, function)) => function_locations(&lib.dwarf, function, relocate),
+            None => function_locations(&lib.dwarf, name, relocate),
+        },
+    }
+}
 
-    let single_read_size = mem::size_of::<c_long>();
+/// Return panic handler and `rust_panic` locations in shared library.
+fn library_panic_locations(lib: &SharedLibrary) -> (Vec<PCValue>, Vec<PCValue>) {
+    let locations = |name: &str| -> Vec<PCValue> {
+        lib.dwarf
+            .find_function_symbols(name)
+            .into_iter()
+            .map(|addr| PCValue::Relocated(addr.relocate(lib.load_bias)))
+            .collect()
+    };
+    (
+        locations(panic::PANIC_HANDLER_FN),
+        locations(panic::RUST_PANIC_FN),
+    )
+}
 
-    let mut addr = addr as *mut c_long;
-    while read_reminder > 0 {
-        let value = sys::ptrace::read(pid, addr as *mut c_void)?;
-        result.extend(value.to_ne_bytes().into_iter().take(read_reminder as usize));
+impl Drop for Debugger {
+    fn drop(&mut self) {
+        inferior::release(&mut self.debugee, &self.breakpoints, &self.watchpoints);
+        mem::take(&mut self.inferiors)
+            .into_iter()
+            .for_each(Inferior::release);
+        mem::take(&mut self.checkpoints)
+            .into_iter()
+            .for_each(Checkpoint::release);
+    }
+}
 
-        read_reminder -= single_read_size as isize;
-        addr = unsafe { addr.offset(1) };
+/// Read N bytes from `PID` process.
+/// Memory read in bulk with `process_vm_readv`, if it reads less than requested (like for memory
+/// pages without read permission) then the unread tail is read from `/proc/<pid>/mem`.
+pub fn read_memory_by_pid(pid: Pid, addr: usize, read_n: usize) -> nix::Result<Vec<u8>> {
+    let mut result = vec![0; read_n];
+    let remote = [RemoteIoVec {
+        base: addr,
+        len: read_n,
+    }];
+    let read = uio::process_vm_readv(pid, &mut [IoSliceMut::new(&mut result)], &remote)
+        .unwrap_or_default();
+    if read < read_n {
+        proc_memory(pid)?
+            .read_exact_at(&mut result[read..], (addr + read) as u64)
+            .map_err(into_errno)?;
     }
+    Ok(result)
+}
+
+/// Write bytes into `PID` process memory, bytes around written data stay untouched.
+pub fn write_memory_by_pid(pid: Pid, addr: usize, data: &[u8]) -> nix::Result<()> {
+    proc_memory(pid)?
+        .write_all_at(data, addr as u64)
+        .map_err(into_errno)
+}
 
-    debug_assert!(result.len() == read_n);
+/// Open debugee memory for reading and writing. Unlike `PTRACE_POKETEXT`, writing to
+/// `/proc/<pid>/mem` doesn't require a stopped thread, so traps may be set
+/// while debugee threads are running (in non-stop mode), and memory is written
+/// in a single call regardless of word alignment.
+fn proc_memory(pid: Pid) -> nix::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!("/proc/{pid}/mem"))
+        .map_err(into_errno)
+}
 
-    Ok(result)
+fn into_errno(e: io::Error) -> Errno {
+    Errno::from_i32(e.raw_os_error().unwrap_or(libc::EIO))
 }
@@ -0,0 +1,244 @@
+use crate::debugger::address::{PCValue, RelocatedAddress};
+use crate::debugger::command::expression::{Operation, SelectPlan};
+use crate::debugger::read_memory_by_pid;
+use crate::debugger::register::{get_register_value, Register};
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::{
+    ScalarVariable, SpecializedVariableIR, SupportedScalar, VariableIR,
+};
+use crate::weak_error;
+use anyhow::{anyhow, bail};
+use nix::unistd::Pid;
+use std::collections::HashSet;
+use std::mem;
+
+/// Panic handler, called with `&core::panic::PanicInfo` argument
+/// (`std::panicking::begin_panic_handler` exported as `rust_begin_unwind`).
+pub(super) const PANIC_HANDLER_FN: &str = "rust_begin_unwind";
+/// Start of unwinding, called for all panics including `std::panic::resume_unwind`.
+pub(super) const RUST_PANIC_FN: &str = "rust_panic";
+/// Renders `fmt::Arguments` into a `String` (non-inlined part of `std::fmt::format`).
+pub(super) const FORMAT_FN: &str = "alloc::fmt::format::format_inner";
+/// Global allocator deallocation function.
+pub(super) const DEALLOC_FN: &str = "__rust_dealloc";
+
+/// Maximum length of string that read from debugee memory.
+pub(super) const MAX_STRING_LEN: usize = 4096;
+
+/// Source location of `panic!` call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanicLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Panic details, `None` fields if they can't be read from debugee
+/// (for example if panic started by `std::panic::resume_unwind`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PanicInfo {
+    /// Panic message as printed by panic hook.
+    pub message: Option<String>,
+    pub location: Option<PanicLocation>,
+}
+
+/// Panic trap where debugee stopped.
+pub(super) enum PanicTrap {
+    /// Panic handler entry, panic details are in handler argument.
+    Handler,
+    /// Unwinding started without panic handler call.
+    Unwind,
+}
+
+/// Internal breakpoints at panic entry points, set by `catch panic` command.
+#[derive(Clone)]
+pub(super) struct PanicCatch {
+    /// Traps at panic handler entry.
+    handler: Vec<PCValue>,
+    /// Traps at `rust_panic` entry.
+    rust_panic: Vec<PCValue>,
+    /// Threads which panic already reported at panic handler,
+    /// `rust_panic` trap is ignored for these threads.
+    reported: HashSet<Pid>,
+}
+
+impl PanicCatch {
+    pub(super) fn new(handler: Vec<PCValue>, rust_panic: Vec<PCValue>) -> Self {
+        Self {
+            handler,
+            rust_panic,
+            reported: HashSet::new(),
+        }
+    }
+
+    pub(super) fn locations(&self) -> impl Iterator<Item = PCValue> + '_ {
+        self.handler.iter().chain(self.rust_panic.iter()).copied()
+    }
+
+    pub(super) fn is_trap(&self, addr: PCValue) -> bool {
+        self.locations().any(|location| location == addr)
+    }
+
+    /// Add traps found in loaded shared library.
+    pub(super) fn extend(&mut self, handler: Vec<PCValue>, rust_panic: Vec<PCValue>) {
+        self.handler.extend(handler);
+        self.rust_panic.extend(rust_panic);
+    }
+
+    /// Remove traps which locations match a predicate (like traps in unloaded library).
+    pub(super) fn remove_locations(&mut self, pred: impl Fn(&PCValue) -> bool) {
+        self.handler.retain(|addr| !pred(addr));
+        self.rust_panic.retain(|addr| !pred(addr));
+    }
+
+    /// Relocate global trap addresses with mapping offset.
+    pub(super) fn relocate(&mut self, mapping_offset: usize) {
+        self.handler
+            .iter_mut()
+            .chain(self.rust_panic.iter_mut())
+            .for_each(|location| {
+                if let PCValue::Global(addr) = *location {
+                    *location = PCValue::Relocated(addr.relocate(mapping_offset));
+                }
+            });
+    }
+
+    /// Return kind of panic trap if thread `pid` stopped at it and debugee must be stopped.
+    pub(super) fn hit(&mut self, pid: Pid, pc: RelocatedAddress) -> Option<PanicTrap> {
+        let pc = PCValue::Relocated(pc);
+        if self.handler.contains(&pc) {
+            self.reported.insert(pid);
+            return Some(PanicTrap::Handler);
+        }
+        if self.rust_panic.contains(&pc) && !self.reported.remove(&pid) {
+            return Some(PanicTrap::Unwind);
+        }
+        None
+    }
+}
+
+/// Read panic details from `info` argument of panic handler, `read_argument` reads
+/// a part of panic handler argument selected by plan, `format` renders `fmt::Arguments`
+/// located at given address.
+/// Argument is read through its DWARF type. Std is usually shipped without type and variable
+/// debug information, in this case `core::panic::PanicInfo` is read from memory
+/// assuming the layout of current std (see [`read_raw_panic_info`]).
+pub(super) fn read_panic_info(
+    pid: Pid,
+    read_argument: impl Fn(SelectPlan) -> anyhow::Result<VariableIR>,
+    format: impl Fn(usize) -> anyhow::Result<String>,
+) -> PanicInfo {
+    // select `*info`, then `*(*info).{field}` for each field
+    let select = |fields: &[&str]| {
+        let mut plan = SelectPlan::select_variable("info");
+        plan.plan.push_back(Operation::Deref);
+        for field in fields {
+            plan.plan.push_back(Operation::GetField(field.to_string()));
+            plan.plan.push_back(Operation::Deref);
+        }
+        plan
+    };
+
+    if read_argument(select(&[])).is_err() {
+        // traps set at function entry, so first argument still in register
+        return weak_error!(get_register_value(pid, Register::Rdi))
+            .map(|addr| read_raw_panic_info(pid, addr as usize, format))
+            .unwrap_or_default();
+    }
+
+    let message = weak_error!(read_argument(select(&["message"])).and_then(|arguments| {
+        let addr = arguments
+            .raw_address()
+            .ok_or_else(|| anyhow!("unknown address of message"))?;
+        read_message(pid, addr, &format)
+    }));
+    let location = weak_error!(
+        read_argument(select(&["location"])).and_then(|location| location_from_ir(&location))
+    );
+    PanicInfo { message, location }
+}
+
+/// Return `core::panic::Location` fields: file name, line and column numbers.
+fn location_from_ir(location: &VariableIR) -> anyhow::Result<PanicLocation> {
+    let VariableIR::Struct(location) = location else {
+        bail!("unexpected type of panic location");
+    };
+    let field = |name: &str| {
+        location
+            .members
+            .iter()
+            .find(|member| member.name() == name)
+            .ok_or_else(|| anyhow!("field {name} not found in panic location"))
+    };
+    let number = |name: &str| match field(name)? {
+        VariableIR::Scalar(ScalarVariable {
+            value: Some(SupportedScalar::U32(number)),
+            ..
+        }) => Ok(*number),
+        _ => Err(anyhow!("unexpected type of {name} in panic location")),
+    };
+
+    let file = match field("file")? {
+        VariableIR::Specialized(SpecializedVariableIR::Str {
+            string: Some(file), ..
+        }) => file.value.clone(),
+        _ => bail!("unexpected type of file in panic location"),
+    };
+    Ok(PanicLocation {
+        file,
+        line: number("line")?,
+        column: number("col")?,
+    })
+}
+
+/// Read `core::panic::PanicInfo` located at `addr` without debug information,
+/// it starts with references to panic message and location.
+fn read_raw_panic_info(
+    pid: Pid,
+    addr: usize,
+    format: impl Fn(usize) -> anyhow::Result<String>,
+) -> PanicInfo {
+    let message = weak_error!(read_word(pid, addr).and_then(|msg| read_message(pid, msg, &format)));
+    let location = weak_error!(read_word(pid, addr + mem::size_of::<usize>())
+        .and_then(|location| read_location(pid, location)));
+    PanicInfo { message, location }
+}
+
+fn read_word(pid: Pid, addr: usize) -> anyhow::Result<usize> {
+    let data = read_memory_by_pid(pid, addr, mem::size_of::<usize>())?;
+    Ok(usize::from_ne_bytes(data.try_into().expect("word size")))
+}
+
+fn read_string(pid: Pid, addr: usize, len: usize) -> anyhow::Result<String> {
+    let data = read_memory_by_pid(pid, addr, len.min(MAX_STRING_LEN))?;
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+/// Read `core::panic::Location`: file name (`&str`), line and column numbers (`u32`).
+fn read_location(pid: Pid, addr: usize) -> anyhow::Result<PanicLocation> {
+    let word_size = mem::size_of::<usize>();
+    let file_ptr = read_word(pid, addr)?;
+    let file_len = read_word(pid, addr + word_size)?;
+    let position = read_memory_by_pid(pid, addr + word_size * 2, 8)?;
+    Ok(PanicLocation {
+        file: read_string(pid, file_ptr, file_len)?,
+        line: u32::from_ne_bytes(position[..4].try_into().expect("u32 size")),
+        column: u32::from_ne_bytes(position[4..].try_into().expect("u32 size")),
+    })
+}
+
+/// Read message from `core::fmt::Arguments` located at `addr`. Arguments is a pair of words,
+/// message without formatting arguments is stored as pointer and tagged length (lowest bit
+/// is set), such message is read from memory. Other messages are rendered by `format`.
+fn read_message(
+    pid: Pid,
+    addr: usize,
+    format: impl Fn(usize) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let ptr = read_word(pid, addr)?;
+    let tagged_len = read_word(pid, addr + mem::size_of::<usize>())?;
+    if tagged_len & 1 == 1 {
+        return read_string(pid, ptr, tagged_len >> 1);
+    }
+    format(addr)
+}
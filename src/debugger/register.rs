@@ -1,6 +1,9 @@
 use anyhow::anyhow;
+use nix::errno::Errno;
+use nix::libc;
 use nix::sys;
 use nix::unistd::Pid;
+use std::{mem, ptr};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Register {
@@ -148,6 +151,61 @@ pub(super) fn set_register_value(pid: Pid, reg: Register, value: u64) -> nix::Re
     sys::ptrace::setregs(pid, regs)
 }
 
+/// Return floating point and vector registers of thread `pid`.
+pub(super) fn get_fp_registers(pid: Pid) -> nix::Result<libc::user_fpregs_struct> {
+    let mut fpregs = mem::MaybeUninit::<libc::user_fpregs_struct>::uninit();
+    let res = unsafe {
+        libc::ptrace(
+            libc::PTRACE_GETFPREGS,
+            pid.as_raw(),
+            ptr::null_mut::<libc::c_void>(),
+            fpregs.as_mut_ptr(),
+        )
+    };
+    Errno::result(res)?;
+    Ok(unsafe { fpregs.assume_init() })
+}
+
+/// Set floating point and vector registers of thread `pid`.
+pub(super) fn set_fp_registers(pid: Pid, fpregs: &libc::user_fpregs_struct) -> nix::Result<()> {
+    let res = unsafe {
+        libc::ptrace(
+            libc::PTRACE_SETFPREGS,
+            pid.as_raw(),
+            ptr::null_mut::<libc::c_void>(),
+            fpregs as *const libc::user_fpregs_struct,
+        )
+    };
+    Errno::result(res).map(drop)
+}
+
+/// Return value of XMM register `num` (0 for xmm0, up to 15 for xmm15).
+pub(super) fn get_xmm_register_value(pid: Pid, num: usize) -> nix::Result<u128> {
+    let fpregs = get_fp_registers(pid)?;
+
+    // every register is a four 32-bit words in `xmm_space`
+    let bytes = fpregs.xmm_space[num * 4..num * 4 + 4]
+        .iter()
+        .flat_map(|word| word.to_ne_bytes())
+        .collect::<Vec<_>>();
+    Ok(u128::from_ne_bytes(
+        bytes.try_into().expect("xmm register size"),
+    ))
+}
+
+/// Set value of XMM register `num` (0 for xmm0, up to 15 for xmm15).
+pub(super) fn set_xmm_register_value(pid: Pid, num: usize, value: u128) -> nix::Result<()> {
+    let mut fpregs = get_fp_registers(pid)?;
+    let bytes = value.to_ne_bytes();
+    for (i, word) in fpregs.xmm_space[num * 4..num * 4 + 4]
+        .iter_mut()
+        .enumerate()
+    {
+        *word = u32::from_ne_bytes(bytes[i * 4..i * 4 + 4].try_into().expect("word size"));
+    }
+    set_fp_registers(pid, &fpregs)
+}
+
 pub(super) fn get_register_value_dwarf(pid: Pid, dwarf_num: i32) -> anyhow::Result<u64> {
     let descr = LIST
         .iter()
@@ -156,6 +214,14 @@ pub(super) fn get_register_value_dwarf(pid: Pid, dwarf_num: i32) -> anyhow::Resu
     Ok(get_register_value(pid, descr.r)?)
 }
 
+pub(super) fn set_register_value_dwarf(pid: Pid, dwarf_num: i32, value: u64) -> anyhow::Result<()> {
+    let descr = LIST
+        .iter()
+        .find(|r| r.dwarf_num == dwarf_num)
+        .ok_or_else(|| anyhow!("invalid dwarf register number {}", dwarf_num))?;
+    Ok(set_register_value(pid, descr.r, value)?)
+}
+
 #[allow(unused)]
 pub fn get_register_name(reg: Register) -> &'static str {
     match LIST.iter().find(|r| r.r == reg) {
@@ -0,0 +1,72 @@
+use nix::sys::signal::Signal;
+use std::collections::HashMap;
+
+/// Debugger reaction on signal received by debugee.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignalAction {
+    /// Stop debugee when signal received.
+    pub stop: bool,
+    /// Report signal to user.
+    pub print: bool,
+    /// Deliver signal to debugee when it continues.
+    pub pass: bool,
+}
+
+impl Default for SignalAction {
+    fn default() -> Self {
+        Self {
+            stop: true,
+            print: true,
+            pass: true,
+        }
+    }
+}
+
+/// Actions for signals received by debugee, set by `handle` command.
+/// Signals without explicitly set action stop debugee, printed and passed to debugee.
+#[derive(Clone)]
+pub struct SignalPolicy(HashMap<Signal, SignalAction>);
+
+impl Default for SignalPolicy {
+    fn default() -> Self {
+        // signals used by programs in normal work
+        let quiet = SignalAction {
+            stop: false,
+            print: false,
+            pass: true,
+        };
+        let mut actions = [
+            Signal::SIGALRM,
+            Signal::SIGURG,
+            Signal::SIGCHLD,
+            Signal::SIGWINCH,
+            Signal::SIGPROF,
+            Signal::SIGIO,
+            Signal::SIGVTALRM,
+        ]
+        .into_iter()
+        .map(|signal| (signal, quiet))
+        .collect::<HashMap<_, _>>();
+
+        // interrupt from terminal addressed to debugger, not to debugee
+        actions.insert(
+            Signal::SIGINT,
+            SignalAction {
+                stop: true,
+                print: true,
+                pass: false,
+            },
+        );
+        Self(actions)
+    }
+}
+
+impl SignalPolicy {
+    pub fn action(&self, signal: Signal) -> SignalAction {
+        self.0.get(&signal).copied().unwrap_or_default()
+    }
+
+    pub fn set_action(&mut self, signal: Signal, action: SignalAction) {
+        self.0.insert(signal, action);
+    }
+}
@@ -1,12 +1,54 @@
 use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::dwarf::RegisterDump;
+use crate::debugger::debugee::{Debugee, Location, StackFrame};
+use crate::debugger::read_memory_by_pid;
+use anyhow::anyhow;
+use gimli::{Register, X86_64};
 use nix::unistd::Pid;
-use unwind::{Accessors, AddressSpace, Byteorder, Cursor, PTraceState, RegNum};
+use std::mem;
+use std::path::PathBuf;
+
+/// Code of signal return trampoline (`__restore_rt` in glibc): `mov $0xf, %rax; syscall`.
+const SIGRETURN_CODE: [u8; 9] = [0x48, 0xc7, 0xc0, 0x0f, 0x00, 0x00, 0x00, 0x0f, 0x05];
+/// Offset of `uc_mcontext` in `ucontext_t`, stack pointer of signal return trampoline
+/// points to `ucontext_t` of interrupted code.
+const MCONTEXT_OFFSET: usize = 40;
+/// Registers in order of `gregset_t` of `mcontext_t`, saved pc is a return address.
+const MCONTEXT_REGISTERS: [Register; 17] = [
+    X86_64::R8,
+    X86_64::R9,
+    X86_64::R10,
+    X86_64::R11,
+    X86_64::R12,
+    X86_64::R13,
+    X86_64::R14,
+    X86_64::R15,
+    X86_64::RDI,
+    X86_64::RSI,
+    X86_64::RBP,
+    X86_64::RBX,
+    X86_64::RDX,
+    X86_64::RAX,
+    X86_64::RCX,
+    X86_64::RSP,
+    X86_64::RA,
+];
+/// Backtrace depth limit, protects from endless unwinding of corrupted stack.
+const MAX_DEPTH: usize = 1024;
+/// Function name of signal return trampoline if it has no symbol.
+const SIGNAL_FRAME_NAME: &str = "<signal handler called>";
 
 pub struct KnownPlace {
     pub func_name: String,
     pub start_ip: u64,
     pub offset: u64,
     pub signal_frame: bool,
+    /// True for virtual frame of inlined function call, such frames share
+    /// instruction pointer with frame of function which contains inlined code.
+    pub inlined: bool,
+    /// Source file of frame instruction, `None` if code has no debug information.
+    pub file: Option<PathBuf>,
+    pub line: Option<u64>,
 }
 
 pub struct BacktracePart {
@@ -16,58 +58,262 @@ pub struct BacktracePart {
 
 pub type Backtrace = Vec<BacktracePart>;
 
-pub fn backtrace(pid: Pid) -> unwind::Result<Backtrace> {
-    let state = PTraceState::new(pid.as_raw() as u32)?;
-    let address_space = AddressSpace::new(Accessors::ptrace(), Byteorder::DEFAULT)?;
-    let mut cursor = Cursor::remote(&address_space, &state)?;
+/// Return backtrace of thread `pid`, unwinding stops at `main` function,
+/// at thread start function or at the outermost frame.
+pub fn backtrace(debugee: &Debugee, pid: Pid) -> anyhow::Result<Backtrace> {
+    let mut frame = innermost_frame(debugee, pid)?;
     let mut backtrace = vec![];
 
     loop {
-        let ip = cursor.register(RegNum::IP)?;
-
-        match (cursor.procedure_info(), cursor.procedure_name()) {
-            (Ok(ref info), Ok(ref name)) if ip == info.start_ip() + name.offset() => {
-                let fn_name = format!("{:#}", rustc_demangle::demangle(name.name()));
-
-                let in_main = fn_name == "main"
-                    || fn_name.contains("::main")
-                    || fn_name.contains("::thread_start");
-
-                backtrace.push(BacktracePart {
-                    ip,
-                    place: Some(KnownPlace {
-                        func_name: fn_name,
-                        start_ip: info.start_ip(),
-                        offset: name.offset(),
-                        signal_frame: cursor.is_signal_frame().unwrap_or_default(),
-                    }),
-                });
-
-                if in_main {
-                    break;
-                }
-            }
-            _ => {
-                backtrace.push(BacktracePart { ip, place: None });
-            }
-        }
+        let parts = frame_parts(debugee, &frame);
+        let in_main = parts
+            .iter()
+            .filter_map(|part| part.place.as_ref())
+            .any(|place| {
+                place.func_name == "main"
+                    || place.func_name.contains("::main")
+                    || place.func_name.contains("::thread_start")
+            });
+        backtrace.extend(parts);
 
-        if !cursor.step()? {
+        if in_main || backtrace.len() >= MAX_DEPTH {
             break;
         }
+        // frame which can't be unwound is considered as the outermost
+        match caller_frame(debugee, &frame).ok().flatten() {
+            Some(caller) => frame = caller,
+            None => break,
+        }
     }
 
     Ok(backtrace)
 }
 
-pub fn return_addr(pid: Pid) -> unwind::Result<Option<RelocatedAddress>> {
-    let state = PTraceState::new(pid.as_raw() as u32)?;
-    let address_space = AddressSpace::new(Accessors::ptrace(), Byteorder::DEFAULT)?;
-    let mut cursor = Cursor::remote(&address_space, &state)?;
+/// Return address of current function of thread `pid`, `None` if function is the outermost.
+pub fn return_addr(debugee: &Debugee, pid: Pid) -> anyhow::Result<Option<RelocatedAddress>> {
+    let frame = innermost_frame(debugee, pid)?;
+    Ok(caller_frame(debugee, &frame)?.map(|caller| caller.ip))
+}
+
+/// Return the innermost stack frame of thread `pid`.
+pub fn innermost_frame(debugee: &Debugee, pid: Pid) -> anyhow::Result<StackFrame> {
+    let location = debugee.thread_stop_at(pid)?;
+    let mut frame = StackFrame {
+        num: 0,
+        ip: location.pc,
+        location,
+        cfa: RelocatedAddress::default(),
+        registers: RegisterDump::current(pid)?,
+        signal_frame: is_sigreturn_trampoline(pid, location.pc),
+    };
+    frame.cfa = unwind(debugee, &frame)?.0;
+    Ok(frame)
+}
 
-    if !cursor.step()? {
+/// Return caller of `frame`, `None` if `frame` is the outermost frame.
+pub fn caller_frame(debugee: &Debugee, frame: &StackFrame) -> anyhow::Result<Option<StackFrame>> {
+    let (_, registers) = unwind(debugee, frame)?;
+    let Some(ip) = registers.get(X86_64::RA).filter(|&addr| addr != 0) else {
         return Ok(None);
+    };
+    let ip = RelocatedAddress::from(ip);
+    let pid = frame.location.pid;
+    let signal_frame = is_sigreturn_trampoline(pid, ip);
+
+    // return address follows the call instruction, but code interrupted
+    // by signal continues exactly from saved address
+    let pc = if frame.signal_frame || signal_frame {
+        ip
+    } else {
+        ip.offset(-1)
+    };
+    let mapping_offset = debugee.mapping_offset_for_pc(pc);
+    if usize::from(pc) < mapping_offset {
+        return Err(anyhow!("invalid return address {ip}"));
+    }
+    let mut caller = StackFrame {
+        num: frame.num + 1,
+        ip,
+        location: Location {
+            pid,
+            pc,
+            global_pc: pc.into_global(mapping_offset),
+        },
+        cfa: RelocatedAddress::default(),
+        registers,
+        signal_frame,
+    };
+    // the outermost frames may have no unwind information,
+    // stack pointer is the closest approximation of their CFA
+    caller.cfa = match unwind(debugee, &caller) {
+        Ok((cfa, _)) => cfa,
+        Err(e) => caller
+            .registers
+            .get(X86_64::RSP)
+            .map(RelocatedAddress::from)
+            .ok_or(e)?,
+    };
+    Ok(Some(caller))
+}
+
+/// Unwind `frame`, return its CFA and registers of caller.
+/// Call frame information is used if exists, otherwise frame is unwound
+/// by chain of frame pointers.
+fn unwind(
+    debugee: &Debugee,
+    frame: &StackFrame,
+) -> anyhow::Result<(RelocatedAddress, RegisterDump)> {
+    if frame.signal_frame {
+        return unwind_signal_frame(frame);
+    }
+    debugee
+        .debug_info(frame.location.pc)
+        .unwind(debugee, frame.location, &frame.registers)
+        .or_else(|e| unwind_by_frame_pointer(frame).map_err(|_| e))
+}
+
+/// Restore registers of interrupted code from `ucontext_t` saved by kernel
+/// at signal delivery, CFA of trampoline is its stack pointer.
+fn unwind_signal_frame(frame: &StackFrame) -> anyhow::Result<(RelocatedAddress, RegisterDump)> {
+    let sp = frame
+        .registers
+        .get(X86_64::RSP)
+        .ok_or_else(|| anyhow!("stack pointer of signal frame is unknown"))?;
+    let word_size = mem::size_of::<u64>();
+    let gregs = read_memory_by_pid(
+        frame.location.pid,
+        sp as usize + MCONTEXT_OFFSET,
+        MCONTEXT_REGISTERS.len() * word_size,
+    )?;
+
+    let mut registers = RegisterDump::default();
+    MCONTEXT_REGISTERS
+        .iter()
+        .zip(gregs.chunks_exact(word_size))
+        .for_each(|(&register, value)| {
+            registers.set(
+                register,
+                u64::from_ne_bytes(value.try_into().expect("word size")),
+            )
+        });
+    Ok((RelocatedAddress::from(sp), registers))
+}
+
+/// Unwind frame with standard prologue (`push %rbp; mov %rsp, %rbp`):
+/// saved frame pointer and return address located at frame pointer.
+fn unwind_by_frame_pointer(frame: &StackFrame) -> anyhow::Result<(RelocatedAddress, RegisterDump)> {
+    let fp = frame
+        .registers
+        .get(X86_64::RBP)
+        .filter(|&fp| fp != 0)
+        .ok_or_else(|| anyhow!("frame pointer is unknown"))?;
+    let word_size = mem::size_of::<u64>();
+    let data = read_memory_by_pid(frame.location.pid, fp as usize, word_size * 2)?;
+    let (saved_fp, ret_addr) = data.split_at(word_size);
+    let cfa = fp + 2 * word_size as u64;
+
+    let mut registers = frame.registers.callee_saved();
+    registers.set(
+        X86_64::RBP,
+        u64::from_ne_bytes(saved_fp.try_into().expect("word size")),
+    );
+    registers.set(X86_64::RSP, cfa);
+    registers.set(
+        X86_64::RA,
+        u64::from_ne_bytes(ret_addr.try_into().expect("word size")),
+    );
+    Ok((RelocatedAddress::from(cfa), registers))
+}
+
+fn is_sigreturn_trampoline(pid: Pid, ip: RelocatedAddress) -> bool {
+    read_memory_by_pid(pid, ip.into(), SIGRETURN_CODE.len())
+        .map(|code| code == SIGRETURN_CODE)
+        .unwrap_or_default()
+}
+
+/// Return backtrace parts of frame: virtual frames of inlined function calls
+/// (the most nested call first) and frame of function which contains inlined code.
+/// Function name taken from symbol table or debug information if symbol not found.
+fn frame_parts(debugee: &Debugee, frame: &StackFrame) -> Vec<BacktracePart> {
+    let location = frame.location;
+    let mapping_offset = debugee.mapping_offset_for_pc(location.pc) as u64;
+    let dwarf = debugee.debug_info(location.pc);
+    let ip = u64::from(frame.ip);
+
+    let mut parts = vec![];
+    let mut source = dwarf
+        .find_place_from_pc(location.global_pc)
+        .map(|place| (place.file.to_path_buf(), place.line_number));
+    for call in dwarf.find_inlined_calls(location.global_pc) {
+        let pc = u64::from(location.global_pc);
+        let start_ip = call
+            .die
+            .base_attributes
+            .ranges
+            .iter()
+            .find(|range| range.begin <= pc && pc < range.end)
+            .map(|range| range.begin + mapping_offset)
+            .unwrap_or(ip);
+        let (file, line) = source.unzip();
+        parts.push(BacktracePart {
+            ip,
+            place: Some(KnownPlace {
+                func_name: call.function_name().unwrap_or_else(|| "??".to_string()),
+                start_ip,
+                offset: ip - start_ip,
+                signal_frame: false,
+                inlined: true,
+                file,
+                line,
+            }),
+        });
+        // caller of inlined function is at call site
+        source = call
+            .call_place()
+            .map(|(file, line)| (file.to_path_buf(), line));
     }
 
-    Ok(Some(RelocatedAddress::from(cursor.register(RegNum::IP)?)))
+    let function = dwarf
+        .find_function_symbol_by_pc(location.global_pc)
+        .map(|symbol| {
+            (
+                format!("{:#}", rustc_demangle::demangle(&symbol.name)),
+                symbol.addr,
+            )
+        })
+        .or_else(|| {
+            let func = dwarf.find_function_by_pc(location.global_pc)?;
+            let pc = u64::from(location.global_pc);
+            let start = func
+                .die
+                .base_attributes
+                .ranges
+                .iter()
+                .find(|range| range.begin <= pc && pc < range.end)?
+                .begin;
+            Some((func.full_name()?, start))
+        });
+
+    let (func_name, start_ip) = match function {
+        Some((name, start)) => (name, start + mapping_offset),
+        None if frame.signal_frame => (SIGNAL_FRAME_NAME.to_string(), ip),
+        None => {
+            parts.push(BacktracePart { ip, place: None });
+            return parts;
+        }
+    };
+    let (file, line) = source.unzip();
+    parts.push(BacktracePart {
+        ip,
+        place: Some(KnownPlace {
+            func_name,
+            start_ip,
+            offset: ip - start_ip,
+            signal_frame: frame.signal_frame,
+            inlined: false,
+            file,
+            line,
+        }),
+    });
+    parts
 }
@@ -0,0 +1,606 @@
+use crate::debugger::command::expression::{BinaryOperator, Literal, ScalarType};
+use crate::debugger::variable::render::RenderRepr;
+use crate::debugger::variable::{
+    PointerVariable, ScalarVariable, SpecializedVariableIR, SupportedScalar, VariableIR,
+    VariableIdentity,
+};
+use anyhow::{anyhow, bail};
+
+/// Intermediate value of expression evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    /// Scalar of known type.
+    Scalar(SupportedScalar),
+    /// Integer literal, its type inferred from another operand.
+    Int(i128),
+    /// Float literal, its type inferred from another operand.
+    Float(f64),
+    /// Address in debugee memory.
+    Pointer {
+        addr: usize,
+        type_name: Option<String>,
+    },
+    String(String),
+    EnumVariant(String),
+}
+
+impl From<Literal> for Value {
+    fn from(literal: Literal) -> Self {
+        match literal {
+            Literal::Int(int) => Value::Int(int),
+            Literal::Float(float) => Value::Float(float),
+            Literal::Bool(bool) => Value::Scalar(SupportedScalar::Bool(bool)),
+            Literal::String(string) => Value::String(string),
+            Literal::Char(char) => Value::Scalar(SupportedScalar::Char(char)),
+            Literal::EnumVariant(path) => Value::EnumVariant(path),
+        }
+    }
+}
+
+impl Value {
+    pub(crate) fn from_variable(var: &VariableIR) -> anyhow::Result<Self> {
+        let value = match var {
+            VariableIR::Scalar(scalar) => match scalar.value.as_ref() {
+                Some(SupportedScalar::Empty()) | None => {
+                    bail!("variable {} has no comparable value", var.name())
+                }
+                Some(value) => Value::Scalar(value.clone()),
+            },
+            VariableIR::CEnum(r#enum) => match r#enum.value.as_ref() {
+                Some(variant) => Value::EnumVariant(variant.clone()),
+                None => bail!("unknown variant of enum {}", var.name()),
+            },
+            VariableIR::RustEnum(r#enum) => match r#enum.value.as_ref() {
+                Some(variant) => Value::EnumVariant(variant.name().to_string()),
+                None => bail!("unknown variant of enum {}", var.name()),
+            },
+            VariableIR::Pointer(pointer) => match pointer.value {
+                Some(ptr) => Value::Pointer {
+                    addr: ptr as usize,
+                    type_name: pointer.type_name.clone(),
+                },
+                None => bail!("pointer {} has no value", var.name()),
+            },
+            VariableIR::Specialized(SpecializedVariableIR::String {
+                string: Some(string),
+                ..
+            }) => Value::String(string.value.clone()),
+            VariableIR::Specialized(SpecializedVariableIR::Str {
+                string: Some(string),
+                ..
+            }) => Value::String(string.value.clone()),
+            _ => bail!("variable {} is not comparable", var.name()),
+        };
+        Ok(value)
+    }
+
+    /// Convert value into literal for comparison, characters are compared as strings.
+    pub(crate) fn into_literal(self) -> anyhow::Result<Literal> {
+        let literal = match self {
+            Value::Scalar(SupportedScalar::Bool(bool)) => Literal::Bool(bool),
+            Value::Scalar(SupportedScalar::Char(char)) => Literal::String(char.to_string()),
+            Value::Scalar(SupportedScalar::F32(float)) => Literal::Float(float as f64),
+            Value::Scalar(SupportedScalar::F64(float)) => Literal::Float(float),
+            Value::Scalar(ref scalar) => match as_int(scalar) {
+                Some(int) => Literal::Int(int),
+                None => bail!("value {scalar} is not comparable"),
+            },
+            Value::Int(int) => Literal::Int(int),
+            Value::Float(float) => Literal::Float(float),
+            Value::Pointer { addr, .. } => Literal::Int(addr as i128),
+            Value::String(string) => Literal::String(string),
+            Value::EnumVariant(path) => Literal::EnumVariant(path),
+        };
+        Ok(literal)
+    }
+
+    /// Convert value into a new variable with name `name`.
+    /// Integer literal without inferred type becomes `i64` (or `i128` if it not fits),
+    /// float literal becomes `f64`.
+    pub(crate) fn into_variable(self, name: &str) -> anyhow::Result<VariableIR> {
+        let identity = VariableIdentity::no_namespace(Some(name.to_string()));
+        let scalar = match self {
+            Value::Scalar(scalar) => scalar,
+            Value::Int(int) => match i64::try_from(int) {
+                Ok(int) => SupportedScalar::I64(int),
+                Err(_) => SupportedScalar::I128(int),
+            },
+            Value::Float(float) => SupportedScalar::F64(float),
+            Value::Pointer { addr, type_name } => {
+                return Ok(VariableIR::Pointer(PointerVariable {
+                    identity,
+                    raw_address: None,
+                    type_name,
+                    value: Some(addr as *const ()),
+                    target_type: None,
+                }))
+            }
+            Value::String(_) | Value::EnumVariant(_) => {
+                bail!("expression value is not a scalar")
+            }
+        };
+        Ok(VariableIR::Scalar(ScalarVariable {
+            identity,
+            raw_address: None,
+            type_name: scalar_type(&scalar).map(|t| t.name().to_string()),
+            value: Some(scalar),
+        }))
+    }
+
+    pub(crate) fn as_bool(&self) -> anyhow::Result<bool> {
+        match self {
+            Value::Scalar(SupportedScalar::Bool(bool)) => Ok(*bool),
+            _ => bail!("expect boolean value, got {}", self.describe()),
+        }
+    }
+
+    pub(crate) fn neg(self) -> anyhow::Result<Self> {
+        let value = match self {
+            Value::Int(int) => Value::Int(int.checked_neg().ok_or(overflow("negate"))?),
+            Value::Float(float) => Value::Float(-float),
+            Value::Scalar(SupportedScalar::F32(float)) => {
+                Value::Scalar(SupportedScalar::F32(-float))
+            }
+            Value::Scalar(SupportedScalar::F64(float)) => {
+                Value::Scalar(SupportedScalar::F64(-float))
+            }
+            Value::Scalar(ref scalar) if is_signed(scalar) => {
+                let r#type = scalar_type(scalar).expect("signed integer");
+                let int = as_int(scalar).expect("signed integer");
+                let negated = int.checked_neg().and_then(|int| int_scalar(r#type, int));
+                Value::Scalar(negated.ok_or(overflow("negate"))?)
+            }
+            _ => bail!("cannot negate {}", self.describe()),
+        };
+        Ok(value)
+    }
+
+    pub(crate) fn not(self) -> anyhow::Result<Self> {
+        let value = match self {
+            Value::Int(int) => Value::Int(!int),
+            Value::Scalar(SupportedScalar::Bool(bool)) => {
+                Value::Scalar(SupportedScalar::Bool(!bool))
+            }
+            Value::Scalar(ref scalar) if as_int(scalar).is_some() => {
+                let r#type = scalar_type(scalar).expect("integer");
+                let int = as_int(scalar).expect("integer");
+                Value::Scalar(wrap_int(r#type, !int).expect("integer"))
+            }
+            _ => bail!("cannot apply `!` to {}", self.describe()),
+        };
+        Ok(value)
+    }
+
+    /// Cast value to `r#type` with the same semantic as `as` operator in Rust.
+    pub(crate) fn cast(self, r#type: ScalarType) -> anyhow::Result<Self> {
+        let scalar = match (&self, r#type) {
+            (_, ScalarType::Bool) => None,
+            (Value::Scalar(SupportedScalar::U8(int)), ScalarType::Char) => {
+                Some(SupportedScalar::Char(*int as char))
+            }
+            (Value::Int(int), ScalarType::Char) => u8::try_from(*int)
+                .ok()
+                .map(|int| SupportedScalar::Char(int as char)),
+            (Value::Scalar(SupportedScalar::Char(char)), ScalarType::Char) => {
+                Some(SupportedScalar::Char(*char))
+            }
+            (_, ScalarType::Char) => None,
+            (Value::Int(int), _) => wrap_int(r#type, *int),
+            (Value::Float(float), _) => Some(float_scalar(r#type, *float)),
+            (Value::Pointer { addr, .. }, _) if r#type.is_integer() => {
+                wrap_int(r#type, *addr as i128)
+            }
+            (Value::Scalar(SupportedScalar::Bool(bool)), _) if r#type.is_integer() => {
+                wrap_int(r#type, *bool as i128)
+            }
+            (Value::Scalar(SupportedScalar::Char(char)), _) if r#type.is_integer() => {
+                wrap_int(r#type, *char as i128)
+            }
+            (Value::Scalar(SupportedScalar::F32(float)), _) => {
+                Some(float_scalar(r#type, *float as f64))
+            }
+            (Value::Scalar(SupportedScalar::F64(float)), _) => Some(float_scalar(r#type, *float)),
+            (Value::Scalar(scalar), _) => as_int(scalar).and_then(|int| wrap_int(r#type, int)),
+            _ => None,
+        };
+        scalar
+            .map(Value::Scalar)
+            .ok_or_else(|| anyhow!("cannot cast {} as {}", self.describe(), r#type.name()))
+    }
+
+    /// Apply arithmetic, bitwise or comparison operator, operands must have the same type
+    /// (except a shift amount), literals take the type of another operand.
+    pub(crate) fn binary(self, op: BinaryOperator, rhs: Value) -> anyhow::Result<Self> {
+        match op {
+            BinaryOperator::Compare(cmp) => {
+                let result = self.into_literal()?.compare(cmp, &rhs.into_literal()?)?;
+                return Ok(Value::Scalar(SupportedScalar::Bool(result)));
+            }
+            BinaryOperator::And | BinaryOperator::Or => {
+                let (lhs, rhs) = (self.as_bool()?, rhs.as_bool()?);
+                let result = if op == BinaryOperator::And {
+                    lhs && rhs
+                } else {
+                    lhs || rhs
+                };
+                return Ok(Value::Scalar(SupportedScalar::Bool(result)));
+            }
+            BinaryOperator::Shl | BinaryOperator::Shr => return self.shift(op, rhs),
+            _ => {}
+        }
+
+        let value = match (self, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => Value::Int(int_op(op, lhs, rhs)?),
+            (Value::Float(lhs), Value::Float(rhs)) => Value::Float(float_op(op, lhs, rhs)?),
+            (Value::Int(lhs), Value::Float(rhs)) => Value::Float(float_op(op, lhs as f64, rhs)?),
+            (Value::Float(lhs), Value::Int(rhs)) => Value::Float(float_op(op, lhs, rhs as f64)?),
+            (Value::Pointer { addr, type_name }, Value::Int(offset))
+                if matches!(op, BinaryOperator::Add | BinaryOperator::Sub) =>
+            {
+                let addr = usize::try_from(int_op(op, addr as i128, offset)?)
+                    .map_err(|_| overflow("calculate address"))?;
+                Value::Pointer { addr, type_name }
+            }
+            (Value::Scalar(lhs), rhs) => {
+                let rhs = typed(rhs, &lhs)?;
+                Value::Scalar(scalar_op(op, lhs, rhs)?)
+            }
+            (lhs, Value::Scalar(rhs)) => {
+                let lhs = typed(lhs, &rhs)?;
+                Value::Scalar(scalar_op(op, lhs, rhs)?)
+            }
+            (lhs, rhs) => bail!(
+                "unsupported operands: {} and {}",
+                lhs.describe(),
+                rhs.describe()
+            ),
+        };
+        Ok(value)
+    }
+
+    fn shift(self, op: BinaryOperator, rhs: Value) -> anyhow::Result<Self> {
+        let amount = match rhs {
+            Value::Int(int) => Some(int),
+            Value::Scalar(ref scalar) => as_int(scalar),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow!("shift amount must be an integer, got {}", rhs.describe()))?;
+
+        let (int, r#type) = match self {
+            Value::Int(int) => (int, None),
+            Value::Scalar(ref scalar) if as_int(scalar).is_some() => {
+                (as_int(scalar).expect("integer"), scalar_type(scalar))
+            }
+            _ => bail!("cannot shift {}", self.describe()),
+        };
+        let bits = r#type
+            .map(|t| int_scalar(t, 0).expect("integer").size() * 8)
+            .unwrap_or(128);
+        let amount = u32::try_from(amount)
+            .ok()
+            .filter(|&amount| (amount as usize) < bits)
+            .ok_or(overflow("shift"))?;
+
+        let result = if op == BinaryOperator::Shl {
+            int.wrapping_shl(amount)
+        } else {
+            int >> amount
+        };
+        Ok(match r#type {
+            None => Value::Int(result),
+            Some(r#type) => Value::Scalar(wrap_int(r#type, result).expect("integer")),
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Value::Scalar(scalar) => match scalar_type(scalar) {
+                Some(r#type) => format!("{scalar} of type {}", r#type.name()),
+                None => scalar.to_string(),
+            },
+            Value::Int(int) => format!("integer {int}"),
+            Value::Float(float) => format!("float {float}"),
+            Value::Pointer { addr, .. } => format!("pointer {addr:#X}"),
+            Value::String(string) => format!("string \"{string}\""),
+            Value::EnumVariant(path) => format!("enum variant {path}"),
+        }
+    }
+}
+
+fn overflow(operation: &str) -> anyhow::Error {
+    anyhow!("attempt to {operation} with overflow")
+}
+
+/// Convert literal `value` to a type of `scalar`.
+fn typed(value: Value, scalar: &SupportedScalar) -> anyhow::Result<SupportedScalar> {
+    let r#type = scalar_type(scalar).ok_or_else(|| anyhow!("value {scalar} has no type"))?;
+    let typed = match value {
+        Value::Scalar(scalar) => scalar,
+        Value::Int(int) => int_scalar(r#type, int)
+            .ok_or_else(|| anyhow!("literal {int} out of range for {}", r#type.name()))?,
+        Value::Float(float) if r#type.is_float() => float_scalar(r#type, float),
+        value => bail!(
+            "mismatched types: expect {}, got {}",
+            r#type.name(),
+            value.describe()
+        ),
+    };
+    Ok(typed)
+}
+
+fn scalar_op(
+    op: BinaryOperator,
+    lhs: SupportedScalar,
+    rhs: SupportedScalar,
+) -> anyhow::Result<SupportedScalar> {
+    let (lhs_type, rhs_type) = (scalar_type(&lhs), scalar_type(&rhs));
+    let r#type = match (lhs_type, rhs_type) {
+        (Some(lhs_type), Some(rhs_type)) if same_type(lhs_type, rhs_type) => lhs_type,
+        _ => bail!(
+            "mismatched types: {} and {}",
+            lhs_type.map(|t| t.name()).unwrap_or("()"),
+            rhs_type.map(|t| t.name()).unwrap_or("()")
+        ),
+    };
+
+    let result = match (lhs, rhs) {
+        (SupportedScalar::Bool(lhs), SupportedScalar::Bool(rhs)) => match op {
+            BinaryOperator::BitAnd => SupportedScalar::Bool(lhs & rhs),
+            BinaryOperator::BitOr => SupportedScalar::Bool(lhs | rhs),
+            BinaryOperator::BitXor => SupportedScalar::Bool(lhs ^ rhs),
+            _ => bail!("unsupported operation {op:?} for bool"),
+        },
+        (SupportedScalar::F32(lhs), SupportedScalar::F32(rhs)) => {
+            SupportedScalar::F32(float_op(op, lhs as f64, rhs as f64)? as f32)
+        }
+        (SupportedScalar::F64(lhs), SupportedScalar::F64(rhs)) => {
+            SupportedScalar::F64(float_op(op, lhs, rhs)?)
+        }
+        (lhs, rhs) => match (as_int(&lhs), as_int(&rhs)) {
+            (Some(lhs), Some(rhs)) => {
+                int_scalar(r#type, int_op(op, lhs, rhs)?).ok_or_else(|| overflow(op_name(op)))?
+            }
+            _ => bail!("unsupported operation {op:?} for {}", r#type.name()),
+        },
+    };
+    Ok(result)
+}
+
+/// Return true if types are the same, `usize` and `isize` are the same as `u64` and `i64`.
+fn same_type(lhs: ScalarType, rhs: ScalarType) -> bool {
+    let normalize = |r#type| match r#type {
+        ScalarType::Usize => ScalarType::U64,
+        ScalarType::Isize => ScalarType::I64,
+        r#type => r#type,
+    };
+    normalize(lhs) == normalize(rhs)
+}
+
+fn op_name(op: BinaryOperator) -> &'static str {
+    match op {
+        BinaryOperator::Add => "add",
+        BinaryOperator::Sub => "subtract",
+        BinaryOperator::Mul => "multiply",
+        BinaryOperator::Div => "divide",
+        BinaryOperator::Rem => "calculate the remainder",
+        _ => "calculate",
+    }
+}
+
+fn int_op(op: BinaryOperator, lhs: i128, rhs: i128) -> anyhow::Result<i128> {
+    let result = match op {
+        BinaryOperator::Add => lhs.checked_add(rhs),
+        BinaryOperator::Sub => lhs.checked_sub(rhs),
+        BinaryOperator::Mul => lhs.checked_mul(rhs),
+        BinaryOperator::Div if rhs == 0 => bail!("attempt to divide by zero"),
+        BinaryOperator::Div => lhs.checked_div(rhs),
+        BinaryOperator::Rem if rhs == 0 => {
+            bail!("attempt to calculate the remainder with a divisor of zero")
+        }
+        BinaryOperator::Rem => lhs.checked_rem(rhs),
+        BinaryOperator::BitAnd => Some(lhs & rhs),
+        BinaryOperator::BitOr => Some(lhs | rhs),
+        BinaryOperator::BitXor => Some(lhs ^ rhs),
+        _ => bail!("unsupported operation {op:?} for integers"),
+    };
+    result.ok_or_else(|| overflow(op_name(op)))
+}
+
+fn float_op(op: BinaryOperator, lhs: f64, rhs: f64) -> anyhow::Result<f64> {
+    Ok(match op {
+        BinaryOperator::Add => lhs + rhs,
+        BinaryOperator::Sub => lhs - rhs,
+        BinaryOperator::Mul => lhs * rhs,
+        BinaryOperator::Div => lhs / rhs,
+        BinaryOperator::Rem => lhs % rhs,
+        _ => bail!("unsupported operation {op:?} for floats"),
+    })
+}
+
+fn scalar_type(scalar: &SupportedScalar) -> Option<ScalarType> {
+    let r#type = match scalar {
+        SupportedScalar::I8(_) => ScalarType::I8,
+        SupportedScalar::I16(_) => ScalarType::I16,
+        SupportedScalar::I32(_) => ScalarType::I32,
+        SupportedScalar::I64(_) => ScalarType::I64,
+        SupportedScalar::I128(_) => ScalarType::I128,
+        SupportedScalar::Isize(_) => ScalarType::Isize,
+        SupportedScalar::U8(_) => ScalarType::U8,
+        SupportedScalar::U16(_) => ScalarType::U16,
+        SupportedScalar::U32(_) => ScalarType::U32,
+        SupportedScalar::U64(_) => ScalarType::U64,
+        SupportedScalar::U128(_) => ScalarType::U128,
+        SupportedScalar::Usize(_) => ScalarType::Usize,
+        SupportedScalar::F32(_) => ScalarType::F32,
+        SupportedScalar::F64(_) => ScalarType::F64,
+        SupportedScalar::Bool(_) => ScalarType::Bool,
+        SupportedScalar::Char(_) => ScalarType::Char,
+        SupportedScalar::Empty() => return None,
+    };
+    Some(r#type)
+}
+
+fn is_signed(scalar: &SupportedScalar) -> bool {
+    matches!(
+        scalar,
+        SupportedScalar::I8(_)
+            | SupportedScalar::I16(_)
+            | SupportedScalar::I32(_)
+            | SupportedScalar::I64(_)
+            | SupportedScalar::I128(_)
+            | SupportedScalar::Isize(_)
+    )
+}
+
+/// Return value of integer scalar, `None` if scalar is not an integer
+/// (or `u128` value is too big).
+fn as_int(scalar: &SupportedScalar) -> Option<i128> {
+    Some(match *scalar {
+        SupportedScalar::I8(int) => int as i128,
+        SupportedScalar::I16(int) => int as i128,
+        SupportedScalar::I32(int) => int as i128,
+        SupportedScalar::I64(int) => int as i128,
+        SupportedScalar::I128(int) => int,
+        SupportedScalar::Isize(int) => int as i128,
+        SupportedScalar::U8(int) => int as i128,
+        SupportedScalar::U16(int) => int as i128,
+        SupportedScalar::U32(int) => int as i128,
+        SupportedScalar::U64(int) => int as i128,
+        SupportedScalar::U128(int) => i128::try_from(int).ok()?,
+        SupportedScalar::Usize(int) => int as i128,
+        _ => return None,
+    })
+}
+
+/// Create scalar of `r#type` from integer, `None` returns if integer out of type range.
+fn int_scalar(r#type: ScalarType, int: i128) -> Option<SupportedScalar> {
+    Some(match r#type {
+        ScalarType::I8 => SupportedScalar::I8(int.try_into().ok()?),
+        ScalarType::I16 => SupportedScalar::I16(int.try_into().ok()?),
+        ScalarType::I32 => SupportedScalar::I32(int.try_into().ok()?),
+        ScalarType::I64 => SupportedScalar::I64(int.try_into().ok()?),
+        ScalarType::I128 => SupportedScalar::I128(int),
+        ScalarType::Isize => SupportedScalar::Isize(int.try_into().ok()?),
+        ScalarType::U8 => SupportedScalar::U8(int.try_into().ok()?),
+        ScalarType::U16 => SupportedScalar::U16(int.try_into().ok()?),
+        ScalarType::U32 => SupportedScalar::U32(int.try_into().ok()?),
+        ScalarType::U64 => SupportedScalar::U64(int.try_into().ok()?),
+        ScalarType::U128 => SupportedScalar::U128(int.try_into().ok()?),
+        ScalarType::Usize => SupportedScalar::Usize(int.try_into().ok()?),
+        ScalarType::F32 => SupportedScalar::F32(int as f32),
+        ScalarType::F64 => SupportedScalar::F64(int as f64),
+        ScalarType::Bool | ScalarType::Char => return None,
+    })
+}
+
+/// Create scalar of `r#type` from integer like `as` operator do (truncate integer if needed).
+fn wrap_int(r#type: ScalarType, int: i128) -> Option<SupportedScalar> {
+    Some(match r#type {
+        ScalarType::I8 => SupportedScalar::I8(int as i8),
+        ScalarType::I16 => SupportedScalar::I16(int as i16),
+        ScalarType::I32 => SupportedScalar::I32(int as i32),
+        ScalarType::I64 => SupportedScalar::I64(int as i64),
+        ScalarType::I128 => SupportedScalar::I128(int),
+        ScalarType::Isize => SupportedScalar::Isize(int as isize),
+        ScalarType::U8 => SupportedScalar::U8(int as u8),
+        ScalarType::U16 => SupportedScalar::U16(int as u16),
+        ScalarType::U32 => SupportedScalar::U32(int as u32),
+        ScalarType::U64 => SupportedScalar::U64(int as u64),
+        ScalarType::U128 => SupportedScalar::U128(int as u128),
+        ScalarType::Usize => SupportedScalar::Usize(int as usize),
+        ScalarType::F32 => SupportedScalar::F32(int as f32),
+        ScalarType::F64 => SupportedScalar::F64(int as f64),
+        ScalarType::Bool | ScalarType::Char => return None,
+    })
+}
+
+/// Create scalar of `r#type` from float like `as` operator do (saturate if needed).
+fn float_scalar(r#type: ScalarType, float: f64) -> SupportedScalar {
+    match r#type {
+        ScalarType::I8 => SupportedScalar::I8(float as i8),
+        ScalarType::I16 => SupportedScalar::I16(float as i16),
+        ScalarType::I32 => SupportedScalar::I32(float as i32),
+        ScalarType::I64 => SupportedScalar::I64(float as i64),
+        ScalarType::I128 => SupportedScalar::I128(float as i128),
+        ScalarType::Isize => SupportedScalar::Isize(float as isize),
+        ScalarType::U8 => SupportedScalar::U8(float as u8),
+        ScalarType::U16 => SupportedScalar::U16(float as u16),
+        ScalarType::U32 => SupportedScalar::U32(float as u32),
+        ScalarType::U64 => SupportedScalar::U64(float as u64),
+        ScalarType::U128 => SupportedScalar::U128(float as u128),
+        ScalarType::Usize => SupportedScalar::Usize(float as usize),
+        ScalarType::F32 => SupportedScalar::F32(float as f32),
+        ScalarType::F64 | ScalarType::Bool | ScalarType::Char => SupportedScalar::F64(float),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::command::expression::CompareOperator;
+
+    #[test]
+    fn test_binary_operations() {
+        let u8 = |v| Value::Scalar(SupportedScalar::U8(v));
+        let i32 = |v| Value::Scalar(SupportedScalar::I32(v));
+
+        assert_eq!(
+            u8(200).binary(BinaryOperator::Add, Value::Int(55)).unwrap(),
+            u8(255)
+        );
+        assert!(u8(200).binary(BinaryOperator::Add, Value::Int(56)).is_err());
+        assert!(u8(1).binary(BinaryOperator::Add, Value::Int(256)).is_err());
+        assert!(u8(1).binary(BinaryOperator::Add, i32(1)).is_err());
+        assert!(i32(1).binary(BinaryOperator::Div, Value::Int(0)).is_err());
+        assert_eq!(
+            Value::Int(2).binary(BinaryOperator::Mul, i32(-3)).unwrap(),
+            i32(-6)
+        );
+        assert_eq!(u8(0x81).binary(BinaryOperator::Shl, i32(1)).unwrap(), u8(2));
+        assert!(u8(1).binary(BinaryOperator::Shl, Value::Int(8)).is_err());
+        assert_eq!(
+            Value::Int(7)
+                .binary(BinaryOperator::Rem, Value::Int(4))
+                .unwrap(),
+            Value::Int(3)
+        );
+        assert_eq!(
+            Value::Float(1.5)
+                .binary(BinaryOperator::Compare(CompareOperator::Lt), i32(2))
+                .unwrap(),
+            Value::Scalar(SupportedScalar::Bool(true))
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        assert_eq!(
+            Value::Int(300).cast(ScalarType::U8).unwrap(),
+            Value::Scalar(SupportedScalar::U8(44))
+        );
+        assert_eq!(
+            Value::Scalar(SupportedScalar::I8(-1))
+                .cast(ScalarType::U32)
+                .unwrap(),
+            Value::Scalar(SupportedScalar::U32(u32::MAX))
+        );
+        assert_eq!(
+            Value::Float(-1.5).cast(ScalarType::U8).unwrap(),
+            Value::Scalar(SupportedScalar::U8(0))
+        );
+        assert_eq!(
+            Value::Scalar(SupportedScalar::Char('a'))
+                .cast(ScalarType::U8)
+                .unwrap(),
+            Value::Scalar(SupportedScalar::U8(97))
+        );
+        assert_eq!(
+            Value::Int(97).cast(ScalarType::Char).unwrap(),
+            Value::Scalar(SupportedScalar::Char('a'))
+        );
+        assert!(Value::Int(1).cast(ScalarType::Bool).is_err());
+        assert!(Value::Scalar(SupportedScalar::I32(97))
+            .cast(ScalarType::Char)
+            .is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::debugger::command::expression::{Literal, Operation, SelectPlan};
+use crate::debugger::debugee::dwarf::r#type::{
+    ComplexType, EvaluationContext, MemberLocation, StructureMember, TypeDeclaration, TypeIdentity,
+};
+use crate::debugger::read_memory_by_pid;
+use crate::debugger::variable::ObjectBinaryRepr;
+use anyhow::{anyhow, bail};
+use gimli::{
+    DW_ATE_address, DW_ATE_boolean, DW_ATE_float, DW_ATE_signed, DW_ATE_signed_char,
+    DW_ATE_unsigned, DW_ATE_unsigned_char, DW_ATE_ASCII, DW_ATE_UTF,
+};
+use std::mem;
+
+/// Place of assignment target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Place {
+    /// Part of variable value at `offset`, variable located according to its location
+    /// expression (registers for example).
+    Variable { offset: usize },
+    /// Address in debugee memory.
+    Memory(usize),
+}
+
+impl Place {
+    fn offset(self, offset: usize) -> Self {
+        match self {
+            Place::Variable { offset: base } => Place::Variable {
+                offset: base + offset,
+            },
+            Place::Memory(addr) => Place::Memory(addr + offset),
+        }
+    }
+}
+
+/// Part of variable which value is changed by assignment.
+pub(crate) struct Target {
+    pub(crate) place: Place,
+    pub(crate) type_id: TypeIdentity,
+}
+
+/// Encoded value of assignment target.
+pub(crate) struct EncodedValue {
+    /// Offset of encoded data from the start of target.
+    /// Only discriminant of rust enum is changed, so it may be not zero.
+    pub(crate) offset: usize,
+    pub(crate) data: Vec<u8>,
+}
+
+/// Find a part of variable of type `r#type` with value `value` selected by `select_plan`.
+/// Supported operations are: get structure field (or field of current enum variant),
+/// get array element by index and pointer dereference.
+pub(crate) fn find_target(
+    eval_ctx: &EvaluationContext,
+    r#type: &ComplexType,
+    value: &ObjectBinaryRepr,
+    select_plan: &SelectPlan,
+) -> anyhow::Result<Target> {
+    let read = |place: Place, size: usize| -> anyhow::Result<Vec<u8>> {
+        match place {
+            Place::Variable { offset } => value
+                .raw_data
+                .get(offset..offset + size)
+                .map(|data| data.to_vec())
+                .ok_or_else(|| anyhow!("variable value is too short")),
+            Place::Memory(addr) => Ok(read_memory_by_pid(eval_ctx.pid, addr, size)?),
+        }
+    };
+
+    let mut target = Target {
+        place: value
+            .address
+            .map(Place::Memory)
+            .unwrap_or(Place::Variable { offset: 0 }),
+        type_id: r#type.root,
+    };
+
+    for op in &select_plan.plan {
+        let type_name = type_name(r#type, target.type_id);
+        let decl = r#type
+            .types
+            .get(&target.type_id)
+            .ok_or_else(|| anyhow!("unknown type {type_name}"))?;
+
+        match op {
+            Operation::FindVariable(_) => {}
+            Operation::GetField(field) => {
+                let members = match decl {
+                    TypeDeclaration::Structure { members, .. }
+                    | TypeDeclaration::Union { members, .. } => members,
+                    TypeDeclaration::RustEnum {
+                        discr_type,
+                        enumerators,
+                        ..
+                    } => {
+                        let discr = discr_type
+                            .as_deref()
+                            .ok_or_else(|| anyhow!("unknown discriminant of {type_name}"))?;
+                        let discr_type = discr
+                            .type_ref
+                            .ok_or_else(|| anyhow!("unknown discriminant type of {type_name}"))?;
+                        let (size, signed) = integer_layout(r#type, discr_type)?;
+                        let discr_value = read_int(
+                            &read(target.place.offset(member_offset(discr)?), size)?,
+                            signed,
+                        );
+                        let variant = enumerators
+                            .get(&Some(discr_value))
+                            .or_else(|| enumerators.get(&None))
+                            .ok_or_else(|| anyhow!("unknown variant of {type_name}"))?;
+                        target.place = target.place.offset(member_offset(variant)?);
+                        match variant.type_ref.and_then(|t| r#type.types.get(&t)) {
+                            Some(TypeDeclaration::Structure { members, .. }) => members,
+                            _ => bail!("unknown variant type of {type_name}"),
+                        }
+                    }
+                    _ => bail!("{type_name} has no fields"),
+                };
+                let member = members
+                    .iter()
+                    .find(|member| member.name.as_deref() == Some(field))
+                    .ok_or_else(|| anyhow!("field {field} not found in {type_name}"))?;
+                target.place = target.place.offset(member_offset(member)?);
+                target.type_id = member
+                    .type_ref
+                    .ok_or_else(|| anyhow!("unknown type of field {field}"))?;
+            }
+            Operation::GetByIndex(idx) => {
+                let TypeDeclaration::Array(array) = decl else {
+                    bail!("{type_name} is not an array");
+                };
+                let element_type = array
+                    .element_type
+                    .ok_or_else(|| anyhow!("unknown element type of {type_name}"))?;
+                let (lower, upper) = array
+                    .bounds(eval_ctx)
+                    .ok_or_else(|| anyhow!("unknown bounds of {type_name}"))?;
+                let len = (upper - lower) as usize;
+                if *idx >= len {
+                    bail!("index {idx} out of bounds, array length is {len}");
+                }
+                let element_size = r#type
+                    .type_size_in_bytes(eval_ctx, element_type)
+                    .ok_or_else(|| anyhow!("unknown element size of {type_name}"))?;
+                target.place = target.place.offset(idx * element_size as usize);
+                target.type_id = element_type;
+            }
+            Operation::Deref => {
+                let TypeDeclaration::Pointer { target_type, .. } = decl else {
+                    bail!("{type_name} is not a pointer");
+                };
+                let ptr = read(target.place, mem::size_of::<usize>())?;
+                target.place = Place::Memory(read_int(&ptr, false) as usize);
+                target.type_id =
+                    target_type.ok_or_else(|| anyhow!("unknown target type of {type_name}"))?;
+            }
+            Operation::Slice(_) => bail!("slice can't be assigned"),
+        }
+    }
+
+    Ok(target)
+}
+
+/// Encode `value` as a value of type `type_id`. Value is a literal: number, bool,
+/// character (like `'a'`) or enum variant (like `Variant` or `Enum::Variant`).
+/// Rust enum may be changed only to a variant without fields.
+pub(crate) fn encode_value(
+    r#type: &ComplexType,
+    type_id: TypeIdentity,
+    value: &str,
+) -> anyhow::Result<EncodedValue> {
+    let literal =
+        Literal::from_text(value).unwrap_or_else(|| Literal::EnumVariant(value.to_string()));
+    let type_name = type_name(r#type, type_id);
+    let incompatible = || anyhow!("value {value} is incompatible with type {type_name}");
+    let decl = r#type
+        .types
+        .get(&type_id)
+        .ok_or_else(|| anyhow!("unknown type {type_name}"))?;
+
+    #[allow(non_upper_case_globals)]
+    let data = match decl {
+        TypeDeclaration::Scalar(scalar) => {
+            let size = scalar
+                .byte_size
+                .ok_or_else(|| anyhow!("unknown size of {type_name}"))?
+                as usize;
+            let encoding = scalar
+                .encoding
+                .ok_or_else(|| anyhow!("unknown encoding of {type_name}"))?;
+            match (encoding, &literal) {
+                (DW_ATE_signed | DW_ATE_signed_char, Literal::Int(int)) => {
+                    encode_int(*int, size, true, &type_name)?
+                }
+                (DW_ATE_unsigned | DW_ATE_unsigned_char | DW_ATE_address, Literal::Int(int)) => {
+                    encode_int(*int, size, false, &type_name)?
+                }
+                (DW_ATE_float, Literal::Int(_) | Literal::Float(_)) => {
+                    let float = match literal {
+                        Literal::Int(int) => int as f64,
+                        Literal::Float(float) => float,
+                        _ => unreachable!(),
+                    };
+                    match size {
+                        4 => (float as f32).to_le_bytes().to_vec(),
+                        8 => float.to_le_bytes().to_vec(),
+                        _ => bail!("unsupported float size {size}"),
+                    }
+                }
+                (DW_ATE_boolean, Literal::Bool(bool)) => {
+                    encode_int(*bool as i128, size, false, &type_name)?
+                }
+                (
+                    DW_ATE_UTF | DW_ATE_ASCII | DW_ATE_signed_char | DW_ATE_unsigned_char,
+                    Literal::Char(char),
+                ) => encode_int(*char as i128, size, false, &type_name)?,
+                _ => return Err(incompatible()),
+            }
+        }
+        TypeDeclaration::CStyleEnum {
+            byte_size,
+            discr_type,
+            enumerators,
+            ..
+        } => {
+            let discr = match &literal {
+                Literal::Int(int) => *int,
+                Literal::EnumVariant(path) => {
+                    let variant = variant_name(path);
+                    enumerators
+                        .iter()
+                        .find_map(|(discr, name)| (name == variant).then_some(*discr as i128))
+                        .ok_or_else(|| anyhow!("variant {variant} not found in {type_name}"))?
+                }
+                _ => return Err(incompatible()),
+            };
+            let (size, signed) = match discr_type {
+                Some(discr_type) => integer_layout(r#type, *discr_type)?,
+                None => (
+                    byte_size.ok_or_else(|| anyhow!("unknown size of {type_name}"))? as usize,
+                    false,
+                ),
+            };
+            encode_int(discr, size, signed, &type_name)?
+        }
+        TypeDeclaration::RustEnum {
+            discr_type,
+            enumerators,
+            ..
+        } => {
+            let Literal::EnumVariant(path) = &literal else {
+                return Err(incompatible());
+            };
+            let variant = variant_name(path);
+            let (discr_value, member) = enumerators
+                .iter()
+                .find(|(_, member)| member.name.as_deref() == Some(variant))
+                .ok_or_else(|| anyhow!("variant {variant} not found in {type_name}"))?;
+            let discr_value =
+                discr_value.ok_or_else(|| anyhow!("variant {variant} has no discriminant"))?;
+            let has_fields = match member.type_ref.and_then(|t| r#type.types.get(&t)) {
+                Some(TypeDeclaration::Structure { members, .. }) => !members.is_empty(),
+                _ => true,
+            };
+            if has_fields {
+                bail!("variant {variant} has fields, only variants without fields can be assigned");
+            }
+
+            let discr = discr_type
+                .as_deref()
+                .ok_or_else(|| anyhow!("unknown discriminant of {type_name}"))?;
+            let (size, signed) = integer_layout(
+                r#type,
+                discr
+                    .type_ref
+                    .ok_or_else(|| anyhow!("unknown discriminant type of {type_name}"))?,
+            )?;
+            return Ok(EncodedValue {
+                offset: member_offset(discr)?,
+                data: encode_int(discr_value as i128, size, signed, &type_name)?,
+            });
+        }
+        TypeDeclaration::Pointer { .. } => match literal {
+            Literal::Int(int) => encode_int(int, mem::size_of::<usize>(), false, &type_name)?,
+            _ => return Err(incompatible()),
+        },
+        TypeDeclaration::Array(_)
+        | TypeDeclaration::Structure { .. }
+        | TypeDeclaration::Union { .. } => {
+            bail!("only scalars, enums and pointers can be assigned, {type_name} is not")
+        }
+    };
+
+    Ok(EncodedValue { offset: 0, data })
+}
+
+fn type_name(r#type: &ComplexType, type_id: TypeIdentity) -> String {
+    r#type
+        .types
+        .contains_key(&type_id)
+        .then(|| r#type.type_name(type_id))
+        .flatten()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn variant_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn member_offset(member: &StructureMember) -> anyhow::Result<usize> {
+    match member.in_struct_location {
+        Some(MemberLocation::Offset(offset)) => Ok(offset as usize),
+        _ => bail!(
+            "unsupported location of member {}",
+            member.name.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// Return size and signedness of integer type (discriminant types are integers).
+fn integer_layout(r#type: &ComplexType, type_id: TypeIdentity) -> anyhow::Result<(usize, bool)> {
+    match r#type.types.get(&type_id) {
+        Some(TypeDeclaration::Scalar(scalar)) => Ok((
+            scalar
+                .byte_size
+                .ok_or_else(|| anyhow!("unknown size of integer type"))? as usize,
+            scalar.encoding == Some(DW_ATE_signed),
+        )),
+        _ => bail!("integer type expected"),
+    }
+}
+
+/// Read little-endian integer not larger than 8 bytes.
+fn read_int(data: &[u8], signed: bool) -> i64 {
+    let mut bytes = [0; 8];
+    let len = data.len().min(bytes.len());
+    bytes[..len].copy_from_slice(&data[..len]);
+    let shift = (bytes.len() - len) as u32 * 8;
+    let value = i64::from_le_bytes(bytes);
+    match (signed, shift) {
+        (_, 0) | (_, 64) => value,
+        (true, shift) => value << shift >> shift,
+        (false, _) => value,
+    }
+}
+
+/// Encode integer as `size` bytes in little-endian, return error if value
+/// is out of range of integer type.
+fn encode_int(value: i128, size: usize, signed: bool, type_name: &str) -> anyhow::Result<Vec<u8>> {
+    let bits = size as u32 * 8;
+    let in_range = match (signed, bits) {
+        (_, 0) => false,
+        (true, 128..) => true,
+        (true, bits) => {
+            let bound = 1_i128 << (bits - 1);
+            (-bound..bound).contains(&value)
+        }
+        (false, 128..) => value >= 0,
+        (false, bits) => (0..1_i128 << bits).contains(&value),
+    };
+    if !in_range {
+        bail!("value {value} is out of range of type {type_name}");
+    }
+    Ok(value.to_le_bytes()[..size.min(16)].to_vec())
+}
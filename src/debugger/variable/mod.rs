@@ -16,6 +16,8 @@ use std::collections::{HashMap, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
 use std::mem;
 
+pub(crate) mod arithmetic;
+pub(super) mod assign;
 pub mod render;
 mod specialization;
 use crate::debugger::command::expression::{Operation, SelectPlan};
@@ -51,6 +53,37 @@ impl Display for VariableIdentity {
     }
 }
 
+/// Raw data of an object (variable, structure member, array element, etc.)
+/// with its address in debugee memory.
+#[derive(Clone)]
+pub struct ObjectBinaryRepr {
+    pub raw_data: Bytes,
+    /// Object address in debugee memory, `None` if object is not placed in memory
+    /// (for example, it located in registers).
+    pub address: Option<usize>,
+}
+
+impl ObjectBinaryRepr {
+    /// Create representation for object placed at `address` in debugee memory.
+    pub fn new(raw_data: Bytes, address: Option<usize>) -> Self {
+        Self { raw_data, address }
+    }
+
+    /// Return representation of the object part (structure member, array element).
+    /// `part` expected to be a slice of object raw data, otherwise address of part is unknown.
+    fn part(&self, part: Bytes) -> Self {
+        let base = self.raw_data.as_ptr() as usize;
+        let address = (part.as_ptr() as usize)
+            .checked_sub(base)
+            .filter(|offset| offset + part.len() <= self.raw_data.len())
+            .and_then(|offset| Some(self.address? + offset));
+        Self {
+            raw_data: part,
+            address,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum SupportedScalar {
     I8(i8),
@@ -96,9 +129,83 @@ impl Display for SupportedScalar {
     }
 }
 
+impl SupportedScalar {
+    /// Return scalar size in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            SupportedScalar::I8(_) | SupportedScalar::U8(_) | SupportedScalar::Bool(_) => 1,
+            SupportedScalar::I16(_) | SupportedScalar::U16(_) => 2,
+            SupportedScalar::I32(_)
+            | SupportedScalar::U32(_)
+            | SupportedScalar::F32(_)
+            | SupportedScalar::Char(_) => 4,
+            SupportedScalar::I64(_)
+            | SupportedScalar::U64(_)
+            | SupportedScalar::F64(_)
+            | SupportedScalar::Isize(_)
+            | SupportedScalar::Usize(_) => 8,
+            SupportedScalar::I128(_) | SupportedScalar::U128(_) => 16,
+            SupportedScalar::Empty() => 0,
+        }
+    }
+
+    /// Create scalar of the same type from raw bytes, `None` returns if data is too short
+    /// or not a valid value of this type.
+    pub fn reinterpret(&self, data: &[u8]) -> Option<Self> {
+        let data = data.get(..self.size())?;
+        Some(match self {
+            SupportedScalar::I8(_) => SupportedScalar::I8(i8::from_ne_bytes(data.try_into().ok()?)),
+            SupportedScalar::I16(_) => {
+                SupportedScalar::I16(i16::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::I32(_) => {
+                SupportedScalar::I32(i32::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::I64(_) => {
+                SupportedScalar::I64(i64::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::I128(_) => {
+                SupportedScalar::I128(i128::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::Isize(_) => {
+                SupportedScalar::Isize(isize::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::U8(_) => SupportedScalar::U8(data[0]),
+            SupportedScalar::U16(_) => {
+                SupportedScalar::U16(u16::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::U32(_) => {
+                SupportedScalar::U32(u32::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::U64(_) => {
+                SupportedScalar::U64(u64::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::U128(_) => {
+                SupportedScalar::U128(u128::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::Usize(_) => {
+                SupportedScalar::Usize(usize::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::F32(_) => {
+                SupportedScalar::F32(f32::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::F64(_) => {
+                SupportedScalar::F64(f64::from_ne_bytes(data.try_into().ok()?))
+            }
+            SupportedScalar::Bool(_) => SupportedScalar::Bool(data[0] != 0),
+            SupportedScalar::Char(_) => {
+                SupportedScalar::Char(char::from_u32(u32::from_ne_bytes(data.try_into().ok()?))?)
+            }
+            SupportedScalar::Empty() => SupportedScalar::Empty(),
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct ScalarVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub value: Option<SupportedScalar>,
 }
@@ -124,6 +231,8 @@ impl ScalarVariable {
 #[derive(Clone)]
 pub struct StructVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub members: Vec<VariableIR>,
     pub type_params: HashMap<String, Option<TypeIdentity>>,
@@ -132,6 +241,8 @@ pub struct StructVariable {
 #[derive(Clone)]
 pub struct ArrayVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub items: Option<Vec<VariableIR>>,
 }
@@ -139,6 +250,8 @@ pub struct ArrayVariable {
 #[derive(Clone)]
 pub struct CEnumVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub value: Option<String>,
 }
@@ -146,6 +259,8 @@ pub struct CEnumVariable {
 #[derive(Clone)]
 pub struct RustEnumVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub value: Option<Box<VariableIR>>,
 }
@@ -153,6 +268,8 @@ pub struct RustEnumVariable {
 #[derive(Clone)]
 pub struct PointerVariable {
     pub identity: VariableIdentity,
+    /// Variable address in debugee memory.
+    pub raw_address: Option<usize>,
     pub type_name: Option<String>,
     pub value: Option<*const ()>,
     target_type: Option<TypeIdentity>,
@@ -175,7 +292,12 @@ impl PointerVariable {
             });
             let mut identity = self.identity.clone();
             identity.name = identity.name.map(|n| format!("*{n}"));
-            parser.parse_inner(eval_ctx, identity, val.map(Bytes::from), target_type)
+            parser.parse_inner(
+                eval_ctx,
+                identity,
+                val.map(|v| ObjectBinaryRepr::new(Bytes::from(v), Some(ptr as usize))),
+                target_type,
+            )
         })
     }
 
@@ -196,18 +318,19 @@ impl PointerVariable {
                 ptr as usize,
                 deref_size * len
             ))?;
-            let val = bytes::Bytes::from(val);
+            let val = ObjectBinaryRepr::new(bytes::Bytes::from(val), Some(ptr as usize));
             let mut identity = self.identity.clone();
             identity.name = identity.name.map(|n| format!("[*{n}]"));
 
             let items = val
+                .raw_data
                 .chunks(deref_size)
                 .enumerate()
                 .map(|(i, chunk)| {
                     parser.parse_inner(
                         eval_ctx,
                         VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
-                        Some(val.slice_ref(chunk)),
+                        Some(val.part(val.raw_data.slice_ref(chunk))),
                         target_type,
                     )
                 })
@@ -215,6 +338,7 @@ impl PointerVariable {
 
             Some(VariableIR::Array(ArrayVariable {
                 identity,
+                raw_address: Some(ptr as usize),
                 items: Some(items),
                 type_name: parser
                     .r#type
@@ -332,6 +456,65 @@ impl VariableIR {
         }
     }
 
+    /// Return address of variable in debugee memory, if variable placed in memory.
+    pub fn raw_address(&self) -> Option<usize> {
+        match self {
+            VariableIR::Scalar(s) => s.raw_address,
+            VariableIR::Struct(s) => s.raw_address,
+            VariableIR::Array(a) => a.raw_address,
+            VariableIR::CEnum(e) => e.raw_address,
+            VariableIR::RustEnum(e) => e.raw_address,
+            VariableIR::Pointer(p) => p.raw_address,
+            VariableIR::Specialized(s) => match s {
+                SpecializedVariableIR::Vector { original, .. }
+                | SpecializedVariableIR::VecDeque { original, .. }
+                | SpecializedVariableIR::String { original, .. }
+                | SpecializedVariableIR::Str { original, .. }
+                | SpecializedVariableIR::Tls { original, .. }
+                | SpecializedVariableIR::HashMap { original, .. }
+                | SpecializedVariableIR::HashSet { original, .. }
+                | SpecializedVariableIR::BTreeMap { original, .. }
+                | SpecializedVariableIR::BTreeSet { original, .. }
+                | SpecializedVariableIR::Cell { original, .. }
+                | SpecializedVariableIR::RefCell { original, .. } => original.raw_address,
+            },
+        }
+    }
+
+    /// Return size in bytes of a variable whose value fits into a machine word
+    /// (scalars and pointers), `None` for other variables.
+    pub fn word_size(&self) -> Option<usize> {
+        match self {
+            VariableIR::Scalar(ScalarVariable {
+                value: Some(scalar),
+                ..
+            }) if (1..=8).contains(&scalar.size()) => Some(scalar.size()),
+            VariableIR::Pointer(_) => Some(mem::size_of::<usize>()),
+            _ => None,
+        }
+    }
+
+    /// Return copy of scalar or pointer variable with a value taken from raw bytes.
+    pub fn with_raw_value(&self, data: &[u8]) -> Option<VariableIR> {
+        match self {
+            VariableIR::Scalar(scalar) => {
+                let value = scalar.value.as_ref()?.reinterpret(data)?;
+                Some(VariableIR::Scalar(ScalarVariable {
+                    value: Some(value),
+                    ..scalar.clone()
+                }))
+            }
+            VariableIR::Pointer(pointer) => {
+                let value = usize::from_ne_bytes(data.get(..8)?.try_into().ok()?);
+                Some(VariableIR::Pointer(PointerVariable {
+                    value: Some(value as *const ()),
+                    ..pointer.clone()
+                }))
+            }
+            _ => None,
+        }
+    }
+
     pub fn apply_select_plan(
         self,
         eval_ctx: &EvaluationContext,
@@ -471,9 +654,12 @@ impl<'a> VariableParser<'a> {
     fn parse_scalar(
         &self,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         r#type: &ScalarType,
     ) -> ScalarVariable {
+        let raw_address = value.as_ref().and_then(|v| v.address);
+        let value = value.map(|v| v.raw_data);
+
         fn render_scalar<S: Copy + Display>(data: Option<Bytes>) -> Option<S> {
             data.as_ref().map(|v| *scalar_from_bytes::<S>(v))
         }
@@ -529,6 +715,7 @@ impl<'a> VariableParser<'a> {
 
         ScalarVariable {
             identity,
+            raw_address,
             type_name: r#type.name.clone(),
             value: value_view,
         }
@@ -538,7 +725,7 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_name: Option<String>,
         type_params: HashMap<String, Option<TypeIdentity>>,
         members: &[StructureMember],
@@ -550,6 +737,7 @@ impl<'a> VariableParser<'a> {
 
         StructVariable {
             identity,
+            raw_address: value.and_then(|v| v.address),
             type_name,
             members: children,
             type_params,
@@ -560,15 +748,18 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         member: &StructureMember,
-        parent_value: Option<&Bytes>,
+        parent_value: Option<&ObjectBinaryRepr>,
     ) -> Option<VariableIR> {
         let name = member.name.clone();
         let type_ref = weak_error!(member.type_ref.ok_or(anyhow!(
             "unknown type for member {}",
             name.as_deref().unwrap_or_default()
         )))?;
-        let member_val =
-            parent_value.and_then(|val| member.value(eval_ctx, self.r#type, val.as_ptr() as usize));
+        let member_val = parent_value.and_then(|val| {
+            member
+                .value(eval_ctx, self.r#type, val.raw_data.as_ptr() as usize)
+                .map(|member_data| val.part(member_data))
+        });
 
         Some(self.parse_inner(
             eval_ctx,
@@ -582,14 +773,15 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_name: Option<String>,
         array_decl: &ArrayType,
     ) -> ArrayVariable {
         let items = array_decl.bounds(eval_ctx).and_then(|bounds| {
             let len = bounds.1 - bounds.0;
             let el_size = array_decl.size_in_bytes(eval_ctx, self.r#type)? / len as u64;
-            let bytes = value.as_ref()?;
+            let value = value.as_ref()?;
+            let bytes = &value.raw_data;
             let el_type_id = array_decl.element_type?;
             Some(
                 bytes
@@ -602,7 +794,7 @@ impl<'a> VariableParser<'a> {
                                 "{}",
                                 bounds.0 + i as i64
                             ))),
-                            Some(bytes.slice_ref(chunk)),
+                            Some(value.part(bytes.slice_ref(chunk))),
                             el_type_id,
                         )
                     })
@@ -612,6 +804,7 @@ impl<'a> VariableParser<'a> {
 
         ArrayVariable {
             identity,
+            raw_address: value.and_then(|v| v.address),
             items,
             type_name,
         }
@@ -621,11 +814,12 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_name: Option<String>,
         discr_type: Option<TypeIdentity>,
         enumerators: &HashMap<i64, String>,
     ) -> CEnumVariable {
+        let raw_address = value.as_ref().and_then(|v| v.address);
         let mb_discr = discr_type.map(|type_id| {
             self.parse_inner(
                 eval_ctx,
@@ -645,6 +839,7 @@ impl<'a> VariableParser<'a> {
 
         CEnumVariable {
             identity,
+            raw_address,
             type_name,
             value: value.and_then(|val| enumerators.get(&val).cloned()),
         }
@@ -654,7 +849,7 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_name: Option<String>,
         discr_member: Option<&StructureMember>,
         enumerators: &HashMap<Option<i64>, StructureMember>,
@@ -680,6 +875,7 @@ impl<'a> VariableParser<'a> {
 
         RustEnumVariable {
             identity,
+            raw_address: value.and_then(|v| v.address),
             type_name,
             value: enumerator,
         }
@@ -688,14 +884,18 @@ impl<'a> VariableParser<'a> {
     fn parse_pointer(
         &self,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_name: Option<String>,
         target_type: Option<TypeIdentity>,
     ) -> PointerVariable {
-        let mb_ptr = value.as_ref().map(scalar_from_bytes::<*const ()>).copied();
+        let mb_ptr = value
+            .as_ref()
+            .map(|v| scalar_from_bytes::<*const ()>(&v.raw_data))
+            .copied();
 
         PointerVariable {
             identity,
+            raw_address: value.and_then(|v| v.address),
             type_name: type_name.or_else(|| {
                 Some(format!(
                     "*{deref_type}",
@@ -711,7 +911,7 @@ impl<'a> VariableParser<'a> {
         &self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
         type_id: TypeIdentity,
     ) -> VariableIR {
         let type_name = self.r#type.type_name(type_id);
@@ -876,7 +1076,7 @@ impl<'a> VariableParser<'a> {
         self,
         eval_ctx: &EvaluationContext,
         identity: VariableIdentity,
-        value: Option<Bytes>,
+        value: Option<ObjectBinaryRepr>,
     ) -> VariableIR {
         self.parse_inner(eval_ctx, identity, value, self.r#type.root)
     }
@@ -998,16 +1198,19 @@ mod test {
             TestCase {
                 variable: VariableIR::Struct(StructVariable {
                     identity: VariableIdentity::no_namespace(Some("struct_1".to_owned())),
+                    raw_address: None,
                     type_name: None,
                     members: vec![
                         VariableIR::Array(ArrayVariable {
                             identity: VariableIdentity::no_namespace(Some("array_1".to_owned())),
+                            raw_address: None,
                             type_name: None,
                             items: Some(vec![
                                 VariableIR::Scalar(ScalarVariable {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_1".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1015,6 +1218,7 @@ mod test {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_2".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1022,12 +1226,14 @@ mod test {
                         }),
                         VariableIR::Array(ArrayVariable {
                             identity: VariableIdentity::no_namespace(Some("array_2".to_owned())),
+                            raw_address: None,
                             type_name: None,
                             items: Some(vec![
                                 VariableIR::Scalar(ScalarVariable {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_3".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1035,6 +1241,7 @@ mod test {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_4".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1051,16 +1258,19 @@ mod test {
             TestCase {
                 variable: VariableIR::Struct(StructVariable {
                     identity: VariableIdentity::no_namespace(Some("struct_1".to_owned())),
+                    raw_address: None,
                     type_name: None,
                     members: vec![
                         VariableIR::Struct(StructVariable {
                             identity: VariableIdentity::no_namespace(Some("struct_2".to_owned())),
+                            raw_address: None,
                             type_name: None,
                             members: vec![
                                 VariableIR::Scalar(ScalarVariable {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_1".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1068,11 +1278,13 @@ mod test {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "enum_1".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: Some(Box::new(VariableIR::Scalar(ScalarVariable {
                                         identity: VariableIdentity::no_namespace(Some(
                                             "scalar_2".to_owned(),
                                         )),
+                                        raw_address: None,
                                         type_name: None,
                                         value: None,
                                     }))),
@@ -1081,6 +1293,7 @@ mod test {
                                     identity: VariableIdentity::no_namespace(Some(
                                         "scalar_3".to_owned(),
                                     )),
+                                    raw_address: None,
                                     type_name: None,
                                     value: None,
                                 }),
@@ -1089,6 +1302,7 @@ mod test {
                         }),
                         VariableIR::Pointer(PointerVariable {
                             identity: VariableIdentity::no_namespace(Some("pointer_1".to_owned())),
+                            raw_address: None,
                             type_name: None,
                             value: None,
                             // deref: Some(Box::new(VariableIR::Scalar(ScalarVariable {
@@ -16,6 +16,66 @@ pub enum ValueLayout<'a> {
     Map(&'a [(VariableIR, VariableIR)]),
 }
 
+/// Output format of scalar values (like `x` in `print/x`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Natural,
+    Hex,
+    Octal,
+    Binary,
+    /// Integers as characters, bytes above ASCII as octal escapes (like `'\377'`).
+    Char,
+    Decimal,
+    Float,
+    /// Hexadecimal address with a name of function that contains it.
+    Address,
+    /// Arrays and vectors of bytes are rendered as strings.
+    String,
+}
+
+impl Format {
+    /// Return format by its letter, like `x` for [`Format::Hex`].
+    pub fn from_letter(letter: char) -> Option<Self> {
+        let format = match letter {
+            'x' => Format::Hex,
+            'o' => Format::Octal,
+            'b' => Format::Binary,
+            'c' => Format::Char,
+            'd' => Format::Decimal,
+            'f' => Format::Float,
+            'a' => Format::Address,
+            's' => Format::String,
+            _ => return None,
+        };
+        Some(format)
+    }
+}
+
+/// Options of variable rendering.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderOptions {
+    /// Format of scalar values, applied to all nested values.
+    pub format: Format,
+    /// Maximum depth of nested values, deeper values rendered as `{...}`.
+    pub max_depth: Option<usize>,
+    /// Maximum number of rendered elements of arrays and collections.
+    pub max_elements: Option<usize>,
+    /// Render each member of structures and collections on a separate line.
+    pub pretty: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            format: Format::default(),
+            max_depth: None,
+            max_elements: None,
+            pretty: true,
+        }
+    }
+}
+
 pub trait RenderRepr {
     fn name(&self) -> &str;
     fn r#type(&self) -> &str;
@@ -90,8 +90,13 @@ impl BucketReflection {
     }
 
     /// Read `T` as raw bytes from debugee process.
+    /// Return address of bucket data in debugee memory.
+    pub(super) fn location(&self) -> usize {
+        unsafe { self.ptr.sub(self.size) as usize }
+    }
+
     pub(super) fn read(&self, pid: Pid) -> nix::Result<Vec<u8>> {
-        unsafe { debugger::read_memory_by_pid(pid, self.ptr.sub(self.size) as usize, self.size) }
+        debugger::read_memory_by_pid(pid, self.location(), self.size)
     }
 }
 
@@ -6,8 +6,8 @@ use crate::debugger::variable::render::RenderRepr;
 use crate::debugger::variable::specialization::btree::BTreeReflection;
 use crate::debugger::variable::specialization::hashbrown::HashmapReflection;
 use crate::debugger::variable::{
-    ArrayVariable, AssumeError, ScalarVariable, StructVariable, SupportedScalar, VariableIR,
-    VariableIdentity, VariableParser,
+    ArrayVariable, AssumeError, ObjectBinaryRepr, ScalarVariable, StructVariable, SupportedScalar,
+    VariableIR, VariableIdentity, VariableParser,
 };
 use crate::{debugger, weak_error};
 use anyhow::Context;
@@ -211,16 +211,17 @@ impl<'a> VariableParserExtension<'a> {
             data_ptr as usize,
             len as usize * el_type_size as usize,
         )
-        .map(Bytes::from)?;
+        .map(|data| ObjectBinaryRepr::new(Bytes::from(data), Some(data_ptr as usize)))?;
 
         let items = data
+            .raw_data
             .chunks(el_type_size as usize)
             .enumerate()
             .map(|(i, chunk)| {
                 self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
-                    Some(data.slice_ref(chunk)),
+                    Some(data.part(data.raw_data.slice_ref(chunk))),
                     inner_type,
                 )
             })
@@ -229,10 +230,12 @@ impl<'a> VariableParserExtension<'a> {
         Ok(VecVariable {
             structure: StructVariable {
                 identity: ir.identity().clone(),
+                raw_address: ir.raw_address(),
                 type_name: Some(ir.r#type().to_owned()),
                 members: vec![
                     VariableIR::Array(ArrayVariable {
                         identity: VariableIdentity::no_namespace(Some("buf".to_owned())),
+                        raw_address: Some(data_ptr as usize),
                         type_name: self
                             .parser
                             .r#type
@@ -242,6 +245,7 @@ impl<'a> VariableParserExtension<'a> {
                     }),
                     VariableIR::Scalar(ScalarVariable {
                         identity: VariableIdentity::no_namespace(Some("cap".to_owned())),
+                        raw_address: None,
                         type_name: Some("usize".to_owned()),
                         value: Some(SupportedScalar::Usize(cap as usize)),
                     }),
@@ -361,7 +365,9 @@ impl<'a> VariableParserExtension<'a> {
                 let tuple = self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("kv".to_string())),
-                    weak_error!(data).map(Bytes::from),
+                    weak_error!(data).map(|data| {
+                        ObjectBinaryRepr::new(Bytes::from(data), Some(bucket.location()))
+                    }),
                     kv_type,
                 );
 
@@ -429,7 +435,9 @@ impl<'a> VariableParserExtension<'a> {
                 let tuple = self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("kv".to_string())),
-                    weak_error!(data).map(Bytes::from),
+                    weak_error!(data).map(|data| {
+                        ObjectBinaryRepr::new(Bytes::from(data), Some(bucket.location()))
+                    }),
                     kv_type,
                 );
 
@@ -506,14 +514,14 @@ impl<'a> VariableParserExtension<'a> {
                 let key = self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("k".to_string())),
-                    Some(Bytes::from(k)),
+                    Some(ObjectBinaryRepr::new(Bytes::from(k), None)),
                     k_type,
                 );
 
                 let value = self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some("v".to_string())),
-                    Some(Bytes::from(v)),
+                    Some(ObjectBinaryRepr::new(Bytes::from(v), None)),
                     v_type,
                 );
 
@@ -616,7 +624,7 @@ impl<'a> VariableParserExtension<'a> {
             data_ptr as usize,
             cap as usize * el_type_size,
         )
-        .map(Bytes::from)?;
+        .map(|data| ObjectBinaryRepr::new(Bytes::from(data), Some(data_ptr as usize)))?;
 
         let items = slice_ranges
             .0
@@ -625,11 +633,12 @@ impl<'a> VariableParserExtension<'a> {
             .enumerate()
             .map(|(i, real_idx)| {
                 let real_idx = real_idx as usize;
-                let el_data = &data[real_idx * el_type_size..(real_idx + 1) * el_type_size];
+                let el_data =
+                    &data.raw_data[real_idx * el_type_size..(real_idx + 1) * el_type_size];
                 self.parser.parse_inner(
                     eval_ctx,
                     VariableIdentity::no_namespace(Some(format!("{}", i as i64))),
-                    Some(data.slice_ref(el_data)),
+                    Some(data.part(data.raw_data.slice_ref(el_data))),
                     inner_type,
                 )
             })
@@ -638,10 +647,12 @@ impl<'a> VariableParserExtension<'a> {
         Ok(VecVariable {
             structure: StructVariable {
                 identity: ir.identity().clone(),
+                raw_address: ir.raw_address(),
                 type_name: Some(ir.r#type().to_owned()),
                 members: vec![
                     VariableIR::Array(ArrayVariable {
                         identity: VariableIdentity::no_namespace(Some("buf".to_owned())),
+                        raw_address: Some(data_ptr as usize),
                         type_name: self
                             .parser
                             .r#type
@@ -651,6 +662,7 @@ impl<'a> VariableParserExtension<'a> {
                     }),
                     VariableIR::Scalar(ScalarVariable {
                         identity: VariableIdentity::no_namespace(Some("cap".to_owned())),
+                        raw_address: None,
                         type_name: Some("usize".to_owned()),
                         value: Some(SupportedScalar::Usize(cap as usize)),
                     }),
@@ -717,6 +729,7 @@ impl<'a> VariableParserExtension<'a> {
 
         Ok(VariableIR::Struct(StructVariable {
             identity: ir.identity().clone(),
+            raw_address: ir.raw_address(),
             type_name: Some(ir.r#type().to_owned()),
             members: vec![borrow, value.clone()],
             type_params: Default::default(),
@@ -0,0 +1,69 @@
+use crate::debugger::address::RelocatedAddress;
+use crate::debugger::debugee::debug_registers::{BreakCondition, HardwareBreakpoint};
+use crate::debugger::variable::VariableIR;
+use nix::unistd::Pid;
+
+/// Kind of memory access on which debugee stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchpointKind {
+    /// Stop when watched value changes (`watch` command).
+    Write,
+    /// Stop when watched value is read (`rwatch` command).
+    Read,
+    /// Stop when watched value is read or written (`awatch` command).
+    Access,
+}
+
+impl WatchpointKind {
+    pub(super) fn break_condition(self) -> BreakCondition {
+        match self {
+            WatchpointKind::Write => BreakCondition::Write,
+            // x86-64 have no read-only breakpoints, reads filtered by value comparison
+            WatchpointKind::Read | WatchpointKind::Access => BreakCondition::ReadWrite,
+        }
+    }
+}
+
+/// Frame of watched local variable, watchpoint removed when this frame returns.
+pub(super) struct WatchpointScope {
+    /// Thread which stack contains watched variable.
+    pub(super) tid: Pid,
+    /// Frame CFA, the stack pointer value right after the frame returns.
+    pub(super) cfa: RelocatedAddress,
+    /// Address to which frame returns.
+    pub(super) return_addr: RelocatedAddress,
+}
+
+pub struct Watchpoint {
+    /// Watchpoint number, shared with breakpoint numbers.
+    pub number: u32,
+    /// Watched expression.
+    pub expr: String,
+    pub kind: WatchpointKind,
+    /// Watched memory address.
+    pub address: RelocatedAddress,
+    /// Watched memory size in bytes.
+    pub size: u8,
+    /// Index of debug register, `None` if watchpoint is disabled.
+    pub(super) slot: Option<usize>,
+    /// Last known value of watched variable.
+    pub(super) value: VariableIR,
+    /// Last known content of watched memory.
+    pub(super) raw_value: Vec<u8>,
+    /// Set for watchpoints on local variables.
+    pub(super) scope: Option<WatchpointScope>,
+}
+
+impl Watchpoint {
+    pub fn is_enabled(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    pub(super) fn hardware_breakpoint(&self) -> HardwareBreakpoint {
+        HardwareBreakpoint {
+            addr: self.address,
+            size: self.size,
+            condition: self.kind.break_condition(),
+        }
+    }
+}
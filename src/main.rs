@@ -1,5 +1,6 @@
 use bugstalker::console::AppBuilder;
 use bugstalker::cui;
+use bugstalker::debugger;
 use bugstalker::debugger::rust;
 use clap::{arg, Parser};
 use nix::sys;
@@ -18,18 +19,49 @@ struct Args {
     #[arg(long, default_value_t = String::from("console"))]
     ui: String,
 
-    debugee: String,
+    #[arg(required_unless_present = "pid")]
+    debugee: Option<String>,
+
+    /// Attach to a running process with this pid
+    #[arg(short, long)]
+    pid: Option<i32>,
 
     /// Path to rust stdlib
     #[clap(short, long)]
     std_lib_path: Option<String>,
+
+    /// Directory with separate debug information files, may be used multiple times
+    #[arg(long = "debug-dir")]
+    debug_dirs: Vec<String>,
 }
 
 fn main() {
     let args = Args::parse();
-    let debugee = &args.debugee;
-
     rust::Environment::init(args.std_lib_path.map(PathBuf::from));
+    debugger::init_debug_dirs(args.debug_dirs.into_iter().map(PathBuf::from).collect());
+
+    if let Some(pid) = args.pid {
+        let pid = Pid::from_raw(pid);
+        match args.ui.as_str() {
+            "cui" => {
+                let (stdout_reader, _) = os_pipe::pipe().unwrap();
+                let (stderr_reader, _) = os_pipe::pipe().unwrap();
+                let app = cui::AppBuilder::new(stdout_reader, stderr_reader)
+                    .attach(pid)
+                    .expect("attach to process fail");
+                app.run().expect("run application fail");
+            }
+            _ => {
+                let app = AppBuilder::new()
+                    .attach(pid)
+                    .expect("attach to process fail");
+                app.run().expect("run application fail");
+            }
+        }
+        return;
+    }
+
+    let debugee = &args.debugee.expect("debugee program required");
 
     let (stdout_reader, stdout_writer) = os_pipe::pipe().unwrap();
     let (stderr_reader, stderr_writer) = os_pipe::pipe().unwrap();
@@ -54,6 +86,8 @@ fn main() {
                 pid,
                 Options::PTRACE_O_TRACECLONE
                     .union(Options::PTRACE_O_TRACEEXEC)
+                    .union(Options::PTRACE_O_TRACEFORK)
+                    .union(Options::PTRACE_O_TRACEVFORK)
                     .union(Options::PTRACE_O_TRACEEXIT),
             )
             .unwrap();
@@ -1,8 +1,11 @@
 use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::debugger_env;
-use crate::{assert_no_proc, HW_APP};
-use bugstalker::debugger::address::{PCValue, RelocatedAddress};
+use crate::{assert_no_proc, HW_APP, MT_APP, PLUGIN_HOST_APP, VARS_APP};
+use bugstalker::debugger::address::{GlobalAddress, PCValue, RelocatedAddress};
+use bugstalker::debugger::command::expression::{ConditionParser, SelectPlan};
+use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
+use bugstalker::debugger::BreakpointSpec;
 use serial_test::serial;
 
 #[test]
@@ -115,3 +118,250 @@ fn test_brkpt_on_line() {
         assert_no_proc!(child);
     });
 }
+
+#[test]
+#[serial]
+fn test_conditional_brkpt() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+        let condition = ConditionParser::new("i == 100 && (sum > 0 || !true)")
+            .parse()
+            .unwrap();
+        debugger.set_breakpoint_condition(brkpt, condition).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+
+        let vars = debugger
+            .read_variable(SelectPlan::select_variable("i"))
+            .unwrap();
+        let VariableIR::Scalar(i) = &vars[0] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(i.value, Some(SupportedScalar::I32(100)));
+
+        debugger.remove_breakpoint(brkpt).unwrap();
+        debugger.continue_debugee().unwrap();
+
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_brkpt_registry() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let loop_brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+        let print_brkpt = debugger.set_breakpoint_at_line("mt.rs", 25).unwrap();
+        debugger.set_breakpoint_ignore_count(loop_brkpt, 5).unwrap();
+
+        let breakpoints = debugger.breakpoints();
+        assert_eq!(breakpoints.len(), 2);
+        assert_eq!(breakpoints[0].number, loop_brkpt);
+        assert_eq!(
+            breakpoints[0].spec,
+            BreakpointSpec::Line("mt.rs".to_string(), 23)
+        );
+        assert_eq!(breakpoints[1].number, print_brkpt);
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        let vars = debugger
+            .read_variable(SelectPlan::select_variable("i"))
+            .unwrap();
+        let VariableIR::Scalar(i) = &vars[0] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(i.value, Some(SupportedScalar::I32(5)));
+        assert_eq!(debugger.breakpoints()[0].hit_count, 6);
+        assert_eq!(debugger.breakpoints()[0].ignore_count, 0);
+
+        debugger.disable_breakpoint(loop_brkpt).unwrap();
+        assert!(!debugger.breakpoints()[0].enabled);
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(25));
+        assert_eq!(debugger.breakpoints()[0].hit_count, 6);
+        assert_eq!(debugger.breakpoints()[1].hit_count, 1);
+
+        debugger.remove_breakpoint(print_brkpt).unwrap();
+        debugger.remove_breakpoint(loop_brkpt).unwrap();
+        assert!(debugger.breakpoints().is_empty());
+        assert!(debugger.remove_breakpoint(loop_brkpt).is_err());
+        debugger.continue_debugee().unwrap();
+
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_brkpt_on_generic_fn() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        let fn_brkpt = debugger.set_breakpoint_at_fn("vars::generic").unwrap();
+        let line_brkpt = debugger.set_breakpoint_at_line("vars.rs", 413).unwrap();
+
+        let breakpoints = debugger.breakpoints();
+        assert_eq!(breakpoints[0].number, fn_brkpt);
+        assert_eq!(breakpoints[0].locations.len(), 3);
+        assert_eq!(breakpoints[1].number, line_brkpt);
+        assert_eq!(breakpoints[1].locations.len(), 3);
+        debugger.disable_breakpoint(line_brkpt).unwrap();
+
+        let expected = [
+            SupportedScalar::U8(1),
+            SupportedScalar::I64(2),
+            SupportedScalar::Char('c'),
+        ];
+        debugger.run_debugee().unwrap();
+        for (i, expected) in expected.into_iter().enumerate() {
+            if i > 0 {
+                debugger.continue_debugee().unwrap();
+            }
+            assert_eq!(info.line.take(), Some(413));
+            let args = debugger.read_arguments().unwrap();
+            let VariableIR::Scalar(val) = &args[0] else {
+                panic!("not a scalar");
+            };
+            assert_eq!(val.value, Some(expected));
+        }
+        assert_eq!(debugger.breakpoints()[0].hit_count, 3);
+
+        debugger.remove_breakpoint(fn_brkpt).unwrap();
+        debugger.enable_breakpoint(line_brkpt).unwrap();
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_brkpt_on_fn_after_prologue() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_fn("arguments").unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(231));
+
+        let args = debugger.read_arguments().unwrap();
+        let VariableIR::Scalar(by_val) = &args[0] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(by_val.value, Some(SupportedScalar::I32(1)));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_deferred_brkpt_in_shared_lib() {
+    debugger_env!(PLUGIN_HOST_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger =
+            Debugger::new(PLUGIN_HOST_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger
+            .set_breakpoint_at_fn("libplugin.so::print_sum")
+            .unwrap();
+        assert!(debugger.set_breakpoint_at_fn("print_sum").is_err());
+        assert!(debugger.breakpoints()[0].locations.is_empty());
+        debugger
+            .set_breakpoint_at_line("plugin_host.rs", 23)
+            .unwrap();
+
+        debugger.run_debugee().unwrap();
+        let pc = debugger.current_thread_stop_at().unwrap().pc;
+        let breakpoints = debugger.breakpoints();
+        assert_eq!(breakpoints[0].number, brkpt);
+        assert_eq!(breakpoints[0].hit_count, 1);
+        assert_eq!(breakpoints[0].locations, vec![PCValue::Relocated(pc)]);
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        // plugin unloaded, breakpoint is pending again
+        assert!(debugger.breakpoints()[0].locations.is_empty());
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_remove_brkpt_at_entry_point() {
+    debugger_env!(PLUGIN_HOST_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger =
+            Debugger::new(PLUGIN_HOST_APP, child, TestHooks::new(info.clone())).unwrap();
+        // `e_entry` field of ELF header
+        let elf = std::fs::read(PLUGIN_HOST_APP).unwrap();
+        let entry_point = u64::from_le_bytes(elf[24..32].try_into().unwrap());
+
+        // shared libraries tracking starts at entry point,
+        // removing user breakpoint there must keep it
+        let brkpt = debugger
+            .set_breakpoint(PCValue::Global(GlobalAddress::from(entry_point as usize)))
+            .unwrap();
+        debugger.remove_breakpoint(brkpt).unwrap();
+        debugger
+            .set_breakpoint_at_fn("libplugin.so::print_sum")
+            .unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(3));
+        assert!(info.file.take().unwrap().ends_with("plugin/src/lib.rs"));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_debug_info_in_shared_lib() {
+    debugger_env!(PLUGIN_HOST_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger =
+            Debugger::new(PLUGIN_HOST_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger
+            .set_breakpoint_at_fn("libplugin.so::print_sum")
+            .unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(3));
+        assert!(info.file.take().unwrap().ends_with("plugin/src/lib.rs"));
+
+        let args = debugger.read_arguments().unwrap();
+        assert_eq!(args.len(), 2);
+        let VariableIR::Scalar(a) = &args[0] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(a.value, Some(SupportedScalar::I64(1)));
+        let VariableIR::Scalar(b) = &args[1] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(b.value, Some(SupportedScalar::I64(2)));
+
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(4));
+        let vars = debugger.read_local_variables().unwrap();
+        let VariableIR::Scalar(sum) = &vars[0] else {
+            panic!("not a scalar");
+        };
+        assert_eq!(sum.value, Some(SupportedScalar::I64(3)));
+
+        let frame = debugger.frame_info(child).unwrap();
+        assert!(frame.return_addr.is_some());
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
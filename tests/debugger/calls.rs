@@ -0,0 +1,195 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, CALLS_APP};
+use bugstalker::debugger::command::expression::CallParser;
+use bugstalker::debugger::command::Variables;
+use bugstalker::debugger::variable::render::RenderRepr;
+use bugstalker::debugger::variable::{SpecializedVariableIR, SupportedScalar, VariableIR};
+use bugstalker::debugger::Debugger;
+use serial_test::serial;
+
+fn assert_scalar(var: &VariableIR, exp_type: &str, exp_val: SupportedScalar) {
+    let VariableIR::Scalar(scalar) = var else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.type_name.as_deref(), Some(exp_type));
+    assert_eq!(scalar.value, Some(exp_val));
+}
+
+fn assert_point(var: &VariableIR, exp_x: f64, exp_y: f64) {
+    let VariableIR::Struct(point) = var else {
+        panic!("not a struct");
+    };
+    assert_eq!(point.type_name.as_deref(), Some("Point"));
+    assert_scalar(&point.members[0], "f64", SupportedScalar::F64(exp_x));
+    assert_scalar(&point.members[1], "f64", SupportedScalar::F64(exp_y));
+}
+
+#[test]
+#[serial]
+fn test_return_value_after_step_out() {
+    debugger_env!(CALLS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(CALLS_APP, child, TestHooks::new(info.clone())).unwrap();
+        for func in [
+            "sum",
+            "half",
+            "is_even",
+            "find",
+            "make_point",
+            "make_rect",
+            "greeting",
+            "nothing",
+        ] {
+            debugger.set_breakpoint_at_fn(func).unwrap();
+        }
+
+        // scalars returned in rax and xmm0
+        debugger.run_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        assert_scalar(&value, "i64", SupportedScalar::I64(3));
+
+        debugger.continue_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        assert_scalar(&value, "f64", SupportedScalar::F64(2.5));
+
+        debugger.continue_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        assert_scalar(&value, "bool", SupportedScalar::Bool(true));
+
+        // pair of scalars returned in rax and rdx
+        debugger.continue_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        let VariableIR::RustEnum(option) = value else {
+            panic!("not an enum");
+        };
+        assert_eq!(option.type_name.as_deref(), Some("Option<u32>"));
+        let VariableIR::Struct(some) = option.value.as_deref().unwrap() else {
+            panic!("not a struct");
+        };
+        assert_scalar(&some.members[0], "u32", SupportedScalar::U32(5));
+
+        // pair of floats returned in xmm0 and xmm1
+        debugger.continue_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        assert_point(&value, 1.5, 2.5);
+
+        // execution stops at breakpoint before function returns
+        debugger.continue_debugee().unwrap();
+        assert!(debugger.step_out().unwrap().is_none());
+        debugger.step_out().unwrap();
+        assert!(debugger.step_out().unwrap().is_none());
+        debugger.step_out().unwrap();
+
+        // big structure returned in memory
+        let value = debugger.step_out().unwrap().unwrap();
+        let VariableIR::Struct(rect) = value else {
+            panic!("not a struct");
+        };
+        assert_eq!(rect.type_name.as_deref(), Some("Rect"));
+        assert_point(&rect.members[0], 0.0, 4.0);
+        assert_point(&rect.members[1], 3.0, 0.0);
+
+        debugger.continue_debugee().unwrap();
+        let value = debugger.step_out().unwrap().unwrap();
+        let VariableIR::Specialized(SpecializedVariableIR::String {
+            string: Some(string),
+            ..
+        }) = value
+        else {
+            panic!("not a string");
+        };
+        assert_eq!(string.value, "hello, bs");
+
+        // function returns nothing
+        debugger.continue_debugee().unwrap();
+        assert!(debugger.step_out().unwrap().is_none());
+        assert_eq!(info.line.take(), Some(58));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+fn call(debugger: &Debugger, expr: &str) -> anyhow::Result<Option<VariableIR>> {
+    let call = CallParser::new(expr).parse().unwrap();
+    debugger.call_function(call)
+}
+
+#[test]
+#[serial]
+fn test_call_function() {
+    debugger_env!(CALLS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(CALLS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("calls.rs", 67).unwrap();
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(67));
+
+        // scalars passed in registers
+        let value = call(&debugger, "sum(2, -40)").unwrap().unwrap();
+        assert_scalar(&value, "i64", SupportedScalar::I64(-38));
+        assert_eq!(value.name(), "sum(2, -40)");
+        let value = call(&debugger, "calls::half(h)").unwrap().unwrap();
+        assert_scalar(&value, "f64", SupportedScalar::F64(1.25));
+        let value = call(&debugger, "is_even(7)").unwrap().unwrap();
+        assert_scalar(&value, "bool", SupportedScalar::Bool(false));
+        let value = call(&debugger, "read_ptr(&n)").unwrap().unwrap();
+        assert_scalar(&value, "i32", SupportedScalar::I32(3));
+
+        // structures passed and returned in registers and memory
+        let value = call(&debugger, "make_point(0.5, q.x)").unwrap().unwrap();
+        assert_point(&value, 0.5, 3.0);
+        let value = call(&debugger, "make_rect(1.0, 2.0)").unwrap().unwrap();
+        let VariableIR::Struct(rect) = value else {
+            panic!("not a struct");
+        };
+        assert_point(&rect.members[0], 0.0, 2.0);
+        assert_point(&rect.members[1], 1.0, 0.0);
+        let value = call(&debugger, "greeting(\"debugger\")").unwrap().unwrap();
+        let VariableIR::Specialized(SpecializedVariableIR::String {
+            string: Some(string),
+            ..
+        }) = value
+        else {
+            panic!("not a string");
+        };
+        assert_eq!(string.value, "hello, debugger");
+        assert!(call(&debugger, "nothing()").unwrap().is_none());
+
+        // methods
+        let value = call(&debugger, "p.dist(&r.top_left)").unwrap().unwrap();
+        assert_scalar(&value, "f64", SupportedScalar::F64(4.5_f64.sqrt()));
+        let value = call(&debugger, "q.scaled(0.5)").unwrap().unwrap();
+        assert_point(&value, 1.5, 2.5);
+        let vars = Variables::new(&debugger, vec!["vars", "v.len()"])
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_scalar(&vars[0], "usize", SupportedScalar::U64(3));
+
+        // invalid calls
+        assert!(call(&debugger, "sum(1)").is_err());
+        assert!(call(&debugger, "half(true)").is_err());
+        assert!(call(&debugger, "half(&h)").is_err());
+        assert!(call(&debugger, "p.unknown()").is_err());
+
+        // crashed call aborted, thread state restored
+        let err = call(&debugger, "read_ptr(8)").unwrap_err();
+        assert!(err.to_string().contains("SIGSEGV"));
+        // null pointer dereference leads to panic without unwinding
+        let err = call(&debugger, "read_ptr(0)").unwrap_err();
+        assert!(err.to_string().contains("SIGABRT"));
+        let value = call(&debugger, "sum(1, 2)").unwrap().unwrap();
+        assert_scalar(&value, "i64", SupportedScalar::I64(3));
+
+        // call interrupted by breakpoint
+        debugger.set_breakpoint_at_fn("sum").unwrap();
+        let err = call(&debugger, "sum(1, 2)").unwrap_err();
+        assert!(err.to_string().contains("interrupted"));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
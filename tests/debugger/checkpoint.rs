@@ -0,0 +1,78 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::HW_APP;
+use bugstalker::debugger::command::expression::SelectPlanParser;
+use bugstalker::debugger::variable::{SpecializedVariableIR, VariableIR};
+use bugstalker::debugger::Debugger;
+use serial_test::serial;
+
+fn assert_arg_s(debugger: &Debugger, exp_value: &str) {
+    let plan = SelectPlanParser::new("s").parse().unwrap();
+    let var = debugger.read_argument(plan).unwrap().pop().unwrap();
+    let VariableIR::Specialized(SpecializedVariableIR::Str {
+        string: Some(str), ..
+    }) = var
+    else {
+        panic!("not a &str");
+    };
+    assert_eq!(str.value, exp_value);
+}
+
+#[test]
+#[serial]
+fn test_checkpoint_restart() {
+    debugger_env!(HW_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(HW_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger
+            .set_breakpoint_at_line("hello_world.rs", 15)
+            .unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        assert_arg_s(&debugger, "Hello, world!");
+
+        assert_eq!(debugger.checkpoint().unwrap(), 1);
+        let checkpoints = debugger.checkpoints().unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].number, 1);
+        assert_eq!(checkpoints[0].line, Some(15));
+        assert_ne!(checkpoints[0].pid, child);
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        assert_arg_s(&debugger, "bye!");
+
+        // breakpoints set after checkpoint creation works in restarted debugee
+        debugger
+            .set_breakpoint_at_line("hello_world.rs", 9)
+            .unwrap();
+        debugger.restart_checkpoint(1).unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        assert_arg_s(&debugger, "Hello, world!");
+        // debugee process replaced by restarted one
+        assert!(sys::signal::kill(child, None).is_err());
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        assert_arg_s(&debugger, "bye!");
+
+        // checkpoint may be restarted again
+        debugger.restart_checkpoint(1).unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        assert_arg_s(&debugger, "Hello, world!");
+
+        debugger.delete_checkpoint(1).unwrap();
+        assert!(debugger.checkpoints().unwrap().is_empty());
+        assert!(debugger.restart_checkpoint(1).is_err());
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(15));
+        debugger.continue_debugee().unwrap();
+    });
+}
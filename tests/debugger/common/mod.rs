@@ -1,5 +1,8 @@
 use bugstalker::debugger::address::RelocatedAddress;
-use bugstalker::debugger::{EventHook, Place};
+use bugstalker::debugger::uw::Backtrace;
+use bugstalker::debugger::variable::VariableIR;
+use bugstalker::debugger::{EventHook, PanicInfo, Place, Watchpoint};
+use nix::unistd::Pid;
 use std::cell::Cell;
 use std::ffi::c_int;
 use std::sync::Arc;
@@ -8,6 +11,9 @@ use std::sync::Arc;
 pub struct DebugeeRunInfo {
     pub line: Arc<Cell<Option<u64>>>,
     pub file: Arc<Cell<Option<String>>>,
+    pub panic: Arc<Cell<Option<PanicInfo>>>,
+    pub signal: Arc<Cell<Option<c_int>>>,
+    pub watchpoint: Arc<Cell<Option<u32>>>,
 }
 
 #[derive(Default)]
@@ -29,8 +35,33 @@ impl EventHook for TestHooks {
         self.info.line.set(place.map(|p| p.line_number));
         Ok(())
     }
-    fn on_signal(&self, _signo: c_int, _code: c_int) {}
+    fn on_signal(&self, signo: c_int, _code: c_int) {
+        self.info.signal.set(Some(signo));
+    }
     fn on_exit(&self, _code: i32) {}
+    fn on_watchpoint(
+        &self,
+        pc: RelocatedAddress,
+        place: Option<Place>,
+        watchpoint: &Watchpoint,
+        _old: Option<&VariableIR>,
+        _new: Option<&VariableIR>,
+    ) -> anyhow::Result<()> {
+        self.info.watchpoint.set(Some(watchpoint.number));
+        self.on_trap(pc, place)
+    }
+    fn on_panic(
+        &self,
+        _tid: Pid,
+        panic: &PanicInfo,
+        _backtrace: Option<&Backtrace>,
+    ) -> anyhow::Result<()> {
+        let location = panic.location.as_ref();
+        self.info.file.set(location.map(|l| l.file.clone()));
+        self.info.line.set(location.map(|l| l.line as u64));
+        self.info.panic.set(Some(panic.clone()));
+        Ok(())
+    }
 }
 
 #[macro_export]
@@ -71,6 +102,8 @@ macro_rules! debugger_env {
                     $child,
                     Options::PTRACE_O_TRACECLONE
                         .union(Options::PTRACE_O_TRACEEXEC)
+                        .union(Options::PTRACE_O_TRACEFORK)
+                        .union(Options::PTRACE_O_TRACEVFORK)
                         .union(Options::PTRACE_O_TRACEEXIT),
                 )
                 .unwrap();
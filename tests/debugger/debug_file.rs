@@ -0,0 +1,73 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::{assert_no_proc, debugger_env};
+use crate::{CALC_PACKED_APP, CALC_STRIPPED_APP, CALC_UNPACKED_APP};
+use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
+use bugstalker::debugger::Debugger;
+use serial_test::serial;
+
+fn assert_sum_arguments(debugger: &Debugger, info: &DebugeeRunInfo) {
+    assert_eq!(info.line.take(), Some(7));
+    assert!(info.file.take().unwrap().ends_with("calc.rs"));
+
+    let args = debugger.read_arguments().unwrap();
+    let values = args
+        .iter()
+        .map(|arg| match arg {
+            VariableIR::Scalar(scalar) => scalar.value.clone(),
+            _ => panic!("not a scalar"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![Some(SupportedScalar::I64(1)), Some(SupportedScalar::I64(2))]
+    );
+}
+
+fn run_to_sum(mut debugger: Debugger, info: DebugeeRunInfo) {
+    debugger.set_breakpoint_at_fn("sum").unwrap();
+    debugger.set_breakpoint_at_line("calc.rs", 3).unwrap();
+
+    debugger.run_debugee().unwrap();
+    assert_sum_arguments(&debugger, &info);
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(3));
+
+    debugger.continue_debugee().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_debug_info_from_debuglink() {
+    debugger_env!(CALC_STRIPPED_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let debugger =
+            Debugger::new(CALC_STRIPPED_APP, child, TestHooks::new(info.clone())).unwrap();
+        run_to_sum(debugger, info);
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_debug_info_from_dwp() {
+    debugger_env!(CALC_PACKED_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let debugger = Debugger::new(CALC_PACKED_APP, child, TestHooks::new(info.clone())).unwrap();
+        run_to_sum(debugger, info);
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_debug_info_from_dwo() {
+    debugger_env!(CALC_UNPACKED_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let debugger =
+            Debugger::new(CALC_UNPACKED_APP, child, TestHooks::new(info.clone())).unwrap();
+        run_to_sum(debugger, info);
+        assert_no_proc!(child);
+    });
+}
@@ -0,0 +1,100 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, FORK_APP};
+use bugstalker::debugger::FollowForkMode;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_follow_fork_parent() {
+    debugger_env!(FORK_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(FORK_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 9).unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 20).unwrap();
+
+        // child process detached without traps, so it exits successfully
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(20));
+        assert_eq!(debugger.inferiors(), vec![child]);
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_follow_fork_child_and_exec() {
+    debugger_env!(FORK_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(FORK_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_follow_fork_mode(FollowForkMode::Child);
+        // rebound to main function of calc program after exec
+        debugger.set_breakpoint_at_fn("main").unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 9).unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 20).unwrap();
+        debugger.set_breakpoint_at_fn("worker").unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(7));
+        assert_eq!(debugger.inferiors(), vec![child]);
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+        assert!(info.file.take().unwrap().ends_with("fork.rs"));
+        let forked = debugger.inferiors()[0];
+        assert_ne!(forked, child);
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(26));
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(2));
+        assert!(info.file.take().unwrap().ends_with("calc.rs"));
+        assert_eq!(debugger.inferiors(), vec![forked]);
+
+        debugger.continue_debugee().unwrap();
+
+        // parent detached after fork, reap it
+        waitpid(child, None).unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_detach_on_fork_off() {
+    debugger_env!(FORK_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(FORK_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_detach_on_fork(false);
+        debugger.set_breakpoint_at_line("fork.rs", 9).unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 18).unwrap();
+        debugger.set_breakpoint_at_line("fork.rs", 20).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(18));
+        let inferiors = debugger.inferiors();
+        assert_eq!(inferiors.len(), 2);
+        assert_eq!(inferiors[0], child);
+        let forked = inferiors[1];
+
+        // parent stays stopped while child runs
+        debugger.switch_inferior(forked).unwrap();
+        assert_eq!(debugger.inferiors(), vec![forked, child]);
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+        debugger.continue_debugee().unwrap();
+
+        // exited child is forgotten
+        debugger.switch_inferior(child).unwrap();
+        assert_eq!(debugger.inferiors(), vec![child]);
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(20));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
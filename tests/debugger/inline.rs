@@ -0,0 +1,95 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, INLINE_APP};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_inlined_calls_backtrace() {
+    debugger_env!(INLINE_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(INLINE_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("inline.rs", 5).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(5));
+
+        let bt = debugger.backtrace(child).unwrap();
+        let frames = bt
+            .iter()
+            .map(|part| {
+                let place = part.place.as_ref().unwrap();
+                (place.func_name.as_str(), place.line, place.inlined)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frames,
+            vec![
+                ("inline::square", Some(5), true),
+                ("inline::sum_of_squares", Some(11), true),
+                ("inline::calculate", Some(18), false),
+                ("inline::main", Some(23), false),
+            ]
+        );
+        // virtual frames share instruction pointer with function frame
+        assert_eq!(bt[0].ip, bt[2].ip);
+
+        debugger.remove_breakpoint(brkpt).unwrap();
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_step_over_inlined_call() {
+    debugger_env!(INLINE_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(INLINE_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("inline.rs", 11).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(11));
+
+        // `square(b)` is inlined, but stepped over as a regular call
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(12));
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(13));
+        // leave inlined `sum_of_squares` to its caller
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(18));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_step_into_and_out_of_inlined_call() {
+    debugger_env!(INLINE_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(INLINE_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("inline.rs", 11).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(11));
+
+        debugger.step_into().unwrap();
+        assert_eq!(info.line.take(), Some(5));
+
+        // finish inlined `square`, execution stops in inlined `sum_of_squares`
+        debugger.step_out().unwrap();
+        assert_eq!(info.line.take(), Some(12));
+        let bt = debugger.backtrace(child).unwrap();
+        assert_eq!(
+            bt[0].place.as_ref().unwrap().func_name,
+            "inline::sum_of_squares"
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
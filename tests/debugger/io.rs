@@ -3,7 +3,9 @@ use crate::common::TestHooks;
 use crate::{assert_no_proc, CALC_APP};
 use crate::{debugger_env, HW_APP};
 use bugstalker::debugger::address::{PCValue, RelocatedAddress};
+use bugstalker::debugger::command::expression::SelectPlan;
 use bugstalker::debugger::variable::render::{RenderRepr, ValueLayout};
+use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
 use serial_test::serial;
 use std::borrow::Cow;
 
@@ -87,3 +89,64 @@ fn test_read_value_u64() {
         assert_no_proc!(child);
     });
 }
+
+#[test]
+#[serial]
+fn test_frame_selection() {
+    debugger_env!(CALC_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(CALC_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("calc.rs", 13).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(13));
+
+        let vars = debugger.read_local_variables().unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars[0].name(), "output");
+        let args = debugger.read_arguments().unwrap();
+        assert_eq!(args.len(), 1);
+        assert_scalar_i64(&args[0], "v", 3);
+        let inner = debugger.frame_in_focus_info().unwrap();
+        assert_eq!(inner.num, 0);
+
+        // select caller frame, locals of `main` are visible
+        let frame = debugger.set_frame_into_focus(1).unwrap();
+        assert_eq!(info.line.take(), Some(3));
+        assert_eq!(frame.num, 1);
+        assert!(usize::from(frame.cfa) > usize::from(inner.cfa));
+        assert_eq!(debugger.frame_num_in_focus(), 1);
+
+        let vars = debugger.read_local_variables().unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_scalar_i64(&vars[0], "s", 3);
+        assert!(debugger.read_arguments().unwrap().is_empty());
+        let vars = debugger
+            .read_variable(SelectPlan::select_variable("s"))
+            .unwrap();
+        let s = vars.iter().find(|var| var.r#type() == "i64").unwrap();
+        assert_scalar_i64(s, "s", 3);
+
+        // return to innermost frame
+        debugger.set_frame_into_focus(0).unwrap();
+        assert_eq!(info.line.take(), Some(13));
+        let vars = debugger.read_local_variables().unwrap();
+        assert_eq!(vars[0].name(), "output");
+
+        // frame selection is reset when debugee continues
+        debugger.set_frame_into_focus(1).unwrap();
+        debugger.step_over().unwrap();
+        assert_eq!(debugger.frame_num_in_focus(), 0);
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+fn assert_scalar_i64(var: &VariableIR, exp_name: &str, exp_val: i64) {
+    let VariableIR::Scalar(scalar) = var else {
+        panic!("not a scalar");
+    };
+    assert_eq!(scalar.identity.name.as_deref(), Some(exp_name));
+    assert_eq!(scalar.value, Some(SupportedScalar::I64(exp_val)));
+}
@@ -1,22 +1,46 @@
 mod common;
 
 mod breakpoints;
+mod calls;
+mod checkpoint;
+mod debug_file;
+mod fork;
+mod inline;
 mod io;
+mod memory;
 mod multithreaded;
+mod panic;
+mod print;
+mod signal;
 mod steps;
 mod symbol;
 mod variables;
+mod watchpoints;
 
 use crate::common::{DebugeeRunInfo, TestHooks};
 use bugstalker::debugger::register;
 use bugstalker::debugger::register::Register;
+use bugstalker::debugger::{rust, Debugger};
+use nix::unistd::Pid;
 use serial_test::serial;
+use std::io::{BufRead, BufReader};
 use std::mem;
+use std::process::{Command, Stdio};
 
 const HW_APP: &str = "./tests/hello_world";
 const CALC_APP: &str = "./tests/calc";
+const CALC_STRIPPED_APP: &str = "./target/debug/calc_stripped";
+const CALC_PACKED_APP: &str = "./target/debug/calc_packed";
+const CALC_UNPACKED_APP: &str = "./target/debug/calc_unpacked";
 const MT_APP: &str = "./target/debug/mt";
 const VARS_APP: &str = "./target/debug/vars";
+const PLUGIN_HOST_APP: &str = "./target/debug/plugin_host";
+const PANIC_APP: &str = "./target/debug/panic";
+const PANIC_DYNAMIC_APP: &str = "./target/debug/panic_dynamic";
+const SIGNALS_APP: &str = "./target/debug/signals";
+const FORK_APP: &str = "./target/debug/fork";
+const INLINE_APP: &str = "./target/debug/inline";
+const CALLS_APP: &str = "./target/debug/calls";
 
 #[test]
 #[serial]
@@ -46,6 +70,41 @@ fn test_debugger_graceful_shutdown_multiprocess() {
     });
 }
 
+#[test]
+#[serial]
+fn test_attach_to_running_process() {
+    rust::Environment::init(None);
+
+    let mut child = Command::new(HW_APP).stdout(Stdio::piped()).spawn().unwrap();
+    // the first print means the process is done with first `myprint` call and goes to sleep
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "Hello, world!\n");
+
+    let info = DebugeeRunInfo::default();
+    let mut debugger = Debugger::attach(
+        Pid::from_raw(child.id() as i32),
+        TestHooks::new(info.clone()),
+    )
+    .unwrap();
+    debugger
+        .set_breakpoint_at_line("hello_world.rs", 15)
+        .unwrap();
+
+    debugger.continue_debugee().unwrap();
+    assert_eq!(info.line.take(), Some(15));
+
+    mem::drop(debugger);
+
+    // process must continue execution after detach
+    line.clear();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "bye!\n");
+    let status = child.wait().unwrap();
+    assert!(status.success());
+}
+
 #[test]
 #[serial]
 fn test_frame_cfa() {
@@ -89,7 +148,7 @@ fn test_registers() {
         assert_eq!(info.line.take(), Some(5));
 
         // there is only info about return address (dwarf reg 16) in .debug_info section
-        // so assert it with libunwind provided address
+        // so assert it with unwinder provided address
         let pc = debugger.current_thread_stop_at().unwrap().pc;
         let frame = debugger.frame_info(child).unwrap();
         let registers = debugger.current_thread_registers_at_pc(pc).unwrap();
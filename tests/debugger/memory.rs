@@ -0,0 +1,87 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, VARS_APP};
+use bugstalker::debugger::command::Examine;
+use serial_test::serial;
+use std::fs;
+
+#[test]
+#[serial]
+fn test_examine_memory() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 26).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(26));
+
+        let examine = |cmd: &str| {
+            let args = cmd.split(' ').collect::<Vec<_>>();
+            Examine::new(&debugger, args).and_then(|examine| examine.run())
+        };
+        let items = |cmd: &str| -> Vec<Vec<String>> {
+            examine(cmd)
+                .unwrap()
+                .into_iter()
+                .map(|line| line.items)
+                .collect()
+        };
+
+        assert_eq!(items("x/dw &int32"), [["2"]]);
+        assert_eq!(items("x/dh &int16"), [["-1"]]);
+        assert_eq!(items("x/xg &uint64"), [["0x0000000000000004"]]);
+        assert_eq!(items("x/4xb &int32"), [["0x02", "0x00", "0x00", "0x00"]]);
+        assert_eq!(items("x/c &char_ascii"), [["97 'a'"]]);
+        assert_eq!(items("x/f &f64"), [["1.2"]]);
+        assert_eq!(items("x/fw &f32"), [["1.1"]]);
+        assert_eq!(items("x/s &int32"), [["\"\\u{2}\""]]);
+
+        let dump = items("x/4y &int32");
+        assert_eq!(dump.len(), 1);
+        assert_eq!(dump[0][..5], ["02", "00", "00", "00", "  "]);
+        assert_eq!(dump[0].last().unwrap(), "|....|");
+
+        let main = debugger.symbol_address("vars::main").unwrap();
+        let lines = examine("x/2i vars::main").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].addr, main);
+        assert_eq!(lines[0].symbol.as_deref(), Some("vars::main+0"));
+        assert!(usize::from(lines[1].addr) > usize::from(main));
+
+        // breakpoint trap is hidden from examined memory
+        let pc = usize::from(debugger.current_thread_stop_at().unwrap().pc);
+        assert_eq!(debugger.read_memory(pc, 1).unwrap(), [0xCC]);
+        assert_ne!(debugger.read_memory_unpatched(pc, 1).unwrap(), [0xCC]);
+
+        assert!(examine("x/z &int32").is_err());
+        assert!(examine("x/fb &int32").is_err());
+        assert!(examine("x unknown_symbol").is_err());
+        assert!(examine("x/99999999999g &int32").is_err());
+        assert!(examine("x/99999999999999999999g &int32").is_err());
+
+        // disassembling stops at the end of mapped memory
+        let maps = fs::read_to_string(format!("/proc/{child}/maps")).unwrap();
+        let mappings = maps
+            .lines()
+            .map(|line| {
+                let mut fields = line.split([' ', '-']);
+                let mut addr = || usize::from_str_radix(fields.next().unwrap(), 16).unwrap();
+                let (start, end) = (addr(), addr());
+                (start, end, fields.next().unwrap().starts_with('r'))
+            })
+            .collect::<Vec<_>>();
+        // readable mapping followed by unmapped memory
+        let mapping_end = mappings
+            .windows(2)
+            .find(|pair| pair[0].2 && pair[0].1 != pair[1].0)
+            .map(|pair| pair[0].1)
+            .unwrap();
+        let lines = examine(&format!("x/10i {:#x}", mapping_end - 2)).unwrap();
+        assert!(lines.len() <= 2);
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
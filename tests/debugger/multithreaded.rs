@@ -3,7 +3,10 @@ use crate::common::TestHooks;
 use crate::debugger_env;
 use crate::{assert_no_proc, MT_APP};
 use bugstalker::debugger::uw::Backtrace;
+use bugstalker::debugger::{SchedulerLocking, TraceeStatus};
 use serial_test::serial;
+use std::thread;
+use std::time::Duration;
 
 #[test]
 #[serial]
@@ -106,3 +109,130 @@ fn test_multithreaded_trace() {
         assert_no_proc!(child);
     });
 }
+
+#[test]
+#[serial]
+fn test_thread_switch() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+
+        debugger.set_breakpoint_at_line("mt.rs", 31).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 14).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(31));
+
+        let mut threads = debugger.thread_state().unwrap();
+        threads.sort_by_key(|dump| dump.thread.number);
+        let numbers = threads
+            .iter()
+            .map(|dump| dump.thread.number)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert_eq!(threads[0].thread.pid, child);
+        assert!(threads[2].in_focus);
+        assert!(threads.iter().all(|dump| dump.name.is_some()));
+
+        // registers read from thread in focus
+        let tid = debugger.set_thread_into_focus(2).unwrap();
+        assert_eq!(tid, threads[1].thread.pid);
+        assert_eq!(debugger.current_thread_stop_at().unwrap().pid, tid);
+        let rip = debugger.get_register_value("rip").unwrap();
+        assert_eq!(Some(rip as usize), threads[1].pc.map(usize::from));
+
+        // place of thread in focus reported on switch
+        debugger.set_thread_into_focus(3).unwrap();
+        assert_eq!(info.line.take(), Some(31));
+        debugger.set_thread_into_focus(1).unwrap();
+        assert_eq!(debugger.current_thread_stop_at().unwrap().pid, child);
+
+        assert!(debugger.set_thread_into_focus(4).is_err());
+
+        // thread stopped at breakpoint is not in focus, but it must not hit breakpoint again
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(14));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_scheduler_locking_step() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_scheduler_locking(SchedulerLocking::Step);
+        debugger.set_breakpoint_at_line("mt.rs", 31).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 21).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(31));
+
+        // sleep of the first thread is over, but it can't run while other thread steps
+        thread::sleep(Duration::from_secs(2));
+        let pc_before = debugger.thread_state().unwrap();
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(32));
+        let pc_after = debugger.thread_state().unwrap();
+        for dump in pc_after.iter().filter(|dump| !dump.in_focus) {
+            let before = pc_before
+                .iter()
+                .find(|d| d.thread.pid == dump.thread.pid)
+                .unwrap();
+            assert_eq!(before.pc, dump.pc);
+        }
+
+        // continue is not locked
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(21));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_non_stop() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_non_stop(true).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 31).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 21).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 14).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(31));
+
+        // only thread that hit the breakpoint is stopped
+        let threads = debugger.thread_state().unwrap();
+        assert_eq!(threads.len(), 3);
+        for dump in &threads {
+            let expected = if dump.in_focus {
+                TraceeStatus::Stopped
+            } else {
+                TraceeStatus::Running
+            };
+            assert_eq!(dump.thread.status, expected);
+        }
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(21));
+
+        debugger.interrupt().unwrap();
+        let threads = debugger.thread_state().unwrap();
+        assert!(threads
+            .iter()
+            .all(|dump| dump.thread.status == TraceeStatus::Stopped));
+
+        debugger.continue_all().unwrap();
+        assert_eq!(info.line.take(), Some(14));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
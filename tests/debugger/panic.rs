@@ -0,0 +1,64 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, PANIC_APP, PANIC_DYNAMIC_APP};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_catch_panic() {
+    debugger_env!(PANIC_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(PANIC_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.catch_panic().unwrap();
+
+        debugger.run_debugee().unwrap();
+        let panic = info.panic.take().unwrap();
+        assert_eq!(panic.message.as_deref(), Some("panic in thread"));
+        let location = panic.location.unwrap();
+        assert!(location.file.ends_with("panic.rs"));
+        assert_eq!((location.line, location.column), (5, 9));
+
+        // debugee stopped in panicking thread
+        let stop_at = debugger.current_thread_stop_at().unwrap();
+        assert_ne!(stop_at.pid, child);
+        assert!(!debugger.backtrace(stop_at.pid).unwrap().is_empty());
+
+        debugger.continue_debugee().unwrap();
+        let panic = info.panic.take().unwrap();
+        assert_eq!(panic.message.as_deref(), Some("panic in main, code: 42"));
+        let location = panic.location.unwrap();
+        assert_eq!((location.line, location.column), (10, 5));
+        assert_eq!(debugger.current_thread_stop_at().unwrap().pid, child);
+
+        debugger.continue_debugee().unwrap();
+        assert!(info.panic.take().is_none());
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_catch_panic_in_dynamic_std() {
+    debugger_env!(PANIC_DYNAMIC_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger =
+            Debugger::new(PANIC_DYNAMIC_APP, child, TestHooks::new(info.clone())).unwrap();
+        // std is not loaded yet, traps are set when debugee loads it
+        debugger.catch_panic().unwrap();
+
+        debugger.run_debugee().unwrap();
+        let panic = info.panic.take().unwrap();
+        assert_eq!(panic.message.as_deref(), Some("panic in thread"));
+        assert_eq!(panic.location.unwrap().line, 5);
+
+        debugger.continue_debugee().unwrap();
+        let panic = info.panic.take().unwrap();
+        assert_eq!(panic.message.as_deref(), Some("panic in main, code: 42"));
+        assert_eq!(panic.location.unwrap().line, 10);
+
+        debugger.continue_debugee().unwrap();
+        assert!(info.panic.take().is_none());
+        assert_no_proc!(child);
+    });
+}
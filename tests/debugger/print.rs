@@ -0,0 +1,54 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, VARS_APP};
+use bugstalker::console::variable::render_variable_ir_with_options;
+use bugstalker::debugger::command::Print;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn test_print_with_format_and_options() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 26).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 59).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(26));
+
+        let print = |debugger: &Debugger, cmd: &str| -> Vec<String> {
+            let args = cmd.split(' ').collect::<Vec<_>>();
+            let print = Print::new(debugger, args).unwrap();
+            let options = print.options;
+            print
+                .run()
+                .unwrap()
+                .iter()
+                .map(|var| render_variable_ir_with_options(var, 0, &options, &|_| None))
+                .collect()
+        };
+
+        assert_eq!(print(&debugger, "print/x int16"), ["i16(0xffff)"]);
+        assert_eq!(print(&debugger, "print/d int64"), ["i64(-2)"]);
+        assert_eq!(print(&debugger, "print/c char_ascii"), ["char('a')"]);
+        // bytes above ASCII rendered as octal escapes, signed bytes are not zero-extended
+        assert_eq!(print(&debugger, "print/c int8 - 2"), ["i8('\\377')"]);
+        assert_eq!(print(&debugger, "print/c uint8 + 200"), ["u8('\\311')"]);
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(59));
+        assert_eq!(
+            print(&debugger, "print -elements 2 -pretty off arr_1"),
+            ["[i32] { 0: i32(1), 1: i32(-1), ... }"]
+        );
+        assert_eq!(
+            print(&debugger, "print/x -elements 1 -pretty off arr_2"),
+            ["[[i32]] { 0: [i32] { 0: i32(0x1), ... }, ... }"]
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
@@ -0,0 +1,191 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, MT_APP, SIGNALS_APP};
+use bugstalker::debugger::SignalAction;
+use nix::libc;
+use nix::sys::signal::Signal;
+use serial_test::serial;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+#[serial]
+fn test_signal_stop_and_pass() {
+    debugger_env!(SIGNALS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 15).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 17).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.signal.take(), Some(libc::SIGUSR1));
+        assert_eq!(info.line.take(), None);
+
+        // signal delivered on continue, so debugee handler is called
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(15));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_signal_nostop() {
+    debugger_env!(SIGNALS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::new(info.clone())).unwrap();
+        let action = SignalAction {
+            stop: false,
+            print: false,
+            pass: true,
+        };
+        debugger.set_signal_action(Signal::SIGUSR1, action).unwrap();
+        assert_eq!(debugger.signal_action(Signal::SIGUSR1), action);
+        debugger.set_breakpoint_at_line("signals.rs", 15).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 17).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.signal.take(), None);
+        assert_eq!(info.line.take(), Some(15));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_signal_nopass() {
+    debugger_env!(SIGNALS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::new(info.clone())).unwrap();
+        let action = SignalAction {
+            stop: false,
+            print: true,
+            pass: false,
+        };
+        debugger.set_signal_action(Signal::SIGUSR1, action).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 15).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 17).unwrap();
+
+        // signal reported without stop, but not delivered
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.signal.take(), Some(libc::SIGUSR1));
+        assert_eq!(info.line.take(), Some(17));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_continue_without_signal() {
+    debugger_env!(SIGNALS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 15).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 17).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.signal.take(), Some(libc::SIGUSR1));
+
+        // `signal 0` discards pending signal
+        debugger.continue_with_signal(None).unwrap();
+        assert_eq!(info.line.take(), Some(17));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_signal_trap_action_is_fixed() {
+    debugger_env!(SIGNALS_APP, child, {
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::default()).unwrap();
+        assert!(debugger
+            .set_signal_action(Signal::SIGTRAP, SignalAction::default())
+            .is_err());
+    });
+}
+
+#[test]
+#[serial]
+fn test_backtrace_through_signal_frame() {
+    debugger_env!(SIGNALS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(SIGNALS_APP, child, TestHooks::new(info.clone())).unwrap();
+        let action = SignalAction {
+            stop: false,
+            print: false,
+            pass: true,
+        };
+        debugger.set_signal_action(Signal::SIGUSR1, action).unwrap();
+        debugger.set_breakpoint_at_line("signals.rs", 7).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(7));
+
+        let bt = debugger.backtrace(child).unwrap();
+        let handler = bt[0].place.as_ref().unwrap();
+        assert_eq!(handler.func_name, "signals::handle_usr1");
+        assert!(handler.file.as_ref().unwrap().ends_with("signals.rs"));
+        assert_eq!(handler.line, Some(7));
+
+        let signal_frame_pos = bt
+            .iter()
+            .position(|part| part.place.as_ref().is_some_and(|place| place.signal_frame))
+            .unwrap();
+        assert_eq!(signal_frame_pos, 1);
+        // unwinding continues through interrupted code up to `main`
+        let main = bt.last().unwrap().place.as_ref().unwrap();
+        assert_eq!(main.func_name, "signals::main");
+        assert_eq!(main.line, Some(12));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_signal_nopass_while_interrupted() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let action = SignalAction {
+            stop: false,
+            print: true,
+            pass: false,
+        };
+        debugger.set_signal_action(Signal::SIGUSR1, action).unwrap();
+        debugger.set_non_stop(true).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 9).unwrap();
+        debugger.set_breakpoint_at_line("mt.rs", 25).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+
+        // running thread receives a signal, the signal is caught when thread is interrupted
+        let threads = debugger.thread_state().unwrap();
+        let sum1 = threads.iter().find(|dump| dump.thread.number == 2).unwrap();
+        let tid = sum1.thread.pid.as_raw();
+        let res = unsafe { libc::syscall(libc::SYS_tgkill, child.as_raw(), tid, libc::SIGUSR1) };
+        assert_eq!(res, 0);
+        thread::sleep(Duration::from_millis(100));
+        debugger.interrupt().unwrap();
+
+        // signal reported without stop, but not delivered
+        debugger.set_non_stop(false).unwrap();
+        debugger.continue_all().unwrap();
+        assert_eq!(info.signal.take(), Some(libc::SIGUSR1));
+        assert_eq!(info.line.take(), Some(25));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
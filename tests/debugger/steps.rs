@@ -1,7 +1,9 @@
 use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::debugger_env;
-use crate::{assert_no_proc, HW_APP};
+use crate::{assert_no_proc, HW_APP, MT_APP};
+use bugstalker::debugger::command::expression::ConditionParser;
+use bugstalker::debugger::BreakpointSpec;
 use serial_test::serial;
 
 #[test]
@@ -92,3 +94,111 @@ fn test_step_over_on_fn_decl() {
         assert_no_proc!(child);
     });
 }
+
+#[test]
+#[serial]
+fn test_until_leaves_loop() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        debugger.remove_breakpoint(brkpt).unwrap();
+
+        // `next` stays in the loop body
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        debugger.until().unwrap();
+        assert_eq!(info.line.take(), Some(25));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_advance() {
+    debugger_env!(HW_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(HW_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_fn("main").unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(5));
+
+        debugger
+            .advance(&BreakpointSpec::Line("hello_world.rs".to_string(), 15))
+            .unwrap();
+        assert_eq!(info.line.take(), Some(15));
+
+        // current frame returns before location is reached
+        debugger
+            .advance(&BreakpointSpec::Line("hello_world.rs".to_string(), 9))
+            .unwrap();
+        assert_eq!(info.line.take(), Some(7));
+
+        debugger
+            .advance(&BreakpointSpec::Function("myprint".to_string()))
+            .unwrap();
+        assert_eq!(info.line.take(), Some(15));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_step_over_to_conditional_brkpt() {
+    debugger_env!(HW_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(HW_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_fn("main").unwrap();
+        let brkpt = debugger
+            .set_breakpoint_at_line("hello_world.rs", 7)
+            .unwrap();
+        let condition = ConditionParser::new("1 == 2").parse().unwrap();
+        debugger.set_breakpoint_condition(brkpt, condition).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(5));
+
+        // `next` stops at line even if breakpoint condition is false
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(7));
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_step_over_to_ignored_brkpt() {
+    debugger_env!(HW_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(HW_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_fn("main").unwrap();
+        let brkpt = debugger
+            .set_breakpoint_at_line("hello_world.rs", 7)
+            .unwrap();
+        debugger.set_breakpoint_ignore_count(brkpt, 1).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(5));
+
+        // `next` stops at line even if breakpoint hit is ignored
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(7));
+        debugger.step_over().unwrap();
+        assert_eq!(info.line.take(), Some(9));
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
@@ -3,7 +3,7 @@ use crate::common::DebugeeRunInfo;
 use crate::common::TestHooks;
 use crate::{debugger_env, VARS_APP};
 use bugstalker::debugger;
-use bugstalker::debugger::command::expression::{SelectPlan, SelectPlanParser};
+use bugstalker::debugger::command::expression::{ExprParser, SelectPlan, SelectPlanParser};
 use bugstalker::debugger::variable::render::RenderRepr;
 use bugstalker::debugger::variable::VariableIR;
 use bugstalker::debugger::{variable, Debugger};
@@ -1774,3 +1774,261 @@ fn test_cell() {
         assert_no_proc!(child);
     });
 }
+
+#[test]
+#[serial]
+fn test_set_variable() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 26).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 92).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 119).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(26));
+
+        let set = |expr: &str, value: &str| debugger.set_variable(make_select_plan(expr), value);
+        set("int8", "-5").unwrap();
+        set("int128", "-170141183460469231731687303715884105728").unwrap();
+        set("uint16", "0x100").unwrap();
+        set("f32", "2.5").unwrap();
+        set("f64", "3").unwrap();
+        set("boolean_true", "false").unwrap();
+        set("char_ascii", "'z'").unwrap();
+        set("char_non_ascii", "'é'").unwrap();
+        assert!(set("uint8", "256").is_err());
+        assert!(set("uint8", "-1").is_err());
+        assert!(set("boolean_false", "1").is_err());
+        assert!(set("int32", "true").is_err());
+        assert!(set("unknown", "1").is_err());
+
+        assert_scalar(
+            &read_single_var(&debugger, "int8"),
+            "int8",
+            "i8",
+            Some(SupportedScalar::I8(-5)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "int128"),
+            "int128",
+            "i128",
+            Some(SupportedScalar::I128(i128::MIN)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "uint8"),
+            "uint8",
+            "u8",
+            Some(SupportedScalar::U8(1)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "uint16"),
+            "uint16",
+            "u16",
+            Some(SupportedScalar::U16(256)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "f32"),
+            "f32",
+            "f32",
+            Some(SupportedScalar::F32(2.5)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "f64"),
+            "f64",
+            "f64",
+            Some(SupportedScalar::F64(3.0)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "boolean_true"),
+            "boolean_true",
+            "bool",
+            Some(SupportedScalar::Bool(false)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "char_ascii"),
+            "char_ascii",
+            "char",
+            Some(SupportedScalar::Char('z')),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "char_non_ascii"),
+            "char_non_ascii",
+            "char",
+            Some(SupportedScalar::Char('é')),
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(92));
+
+        let set = |expr: &str, value: &str| debugger.set_variable(make_select_plan(expr), value);
+        set("enum_1", "EnumA::A").unwrap();
+        set("enum_2", "E").unwrap();
+        set("enum_3.__1", "5.5").unwrap();
+        assert!(set("enum_4", "C").is_err());
+        assert!(set("enum_1", "EnumA::Z").is_err());
+
+        assert_c_enum(
+            &read_single_var(&debugger, "enum_1"),
+            "enum_1",
+            "EnumA",
+            Some("A".to_string()),
+        );
+        assert_rust_enum(
+            &read_single_var(&debugger, "enum_2"),
+            "enum_2",
+            "EnumC",
+            |enum_val| {
+                assert_struct(enum_val, "E", "E", |_, _| {
+                    panic!("expected empty struct");
+                });
+            },
+        );
+        assert_rust_enum(
+            &read_single_var(&debugger, "enum_3"),
+            "enum_3",
+            "EnumC",
+            |enum_val| {
+                assert_struct(enum_val, "D", "D", |i, member| {
+                    match i {
+                        0 => assert_scalar(member, "__0", "f64", Some(SupportedScalar::F64(1.1))),
+                        1 => assert_scalar(member, "__1", "f32", Some(SupportedScalar::F32(5.5))),
+                        _ => panic!("2 members expected"),
+                    };
+                });
+            },
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(119));
+
+        let set = |expr: &str, value: &str| debugger.set_variable(make_select_plan(expr), value);
+        set("*mut_ref_b", "3").unwrap();
+        set("f.baz[1]", "4").unwrap();
+        set("(*ref_f).bar", "5").unwrap();
+        assert!(set("f.baz[2]", "1").is_err());
+        assert!(set("f", "1").is_err());
+
+        assert_scalar(
+            &read_single_var(&debugger, "*mut_ref_b"),
+            "*mut_ref_b",
+            "i32",
+            Some(SupportedScalar::I32(3)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "f.baz[1]"),
+            "1",
+            "i32",
+            Some(SupportedScalar::I32(4)),
+        );
+        assert_scalar(
+            &read_single_var(&debugger, "f.bar"),
+            "bar",
+            "i32",
+            Some(SupportedScalar::I32(5)),
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_evaluate_expression() {
+    debugger_env!(VARS_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(VARS_APP, child, TestHooks::new(info.clone())).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 26).unwrap();
+        debugger.set_breakpoint_at_line("vars.rs", 185).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(26));
+
+        let eval = |debugger: &Debugger, expr: &str| {
+            let expr = ExprParser::new(expr).parse().unwrap();
+            debugger.evaluate_expression(&expr, "result")
+        };
+        let assert_result = |debugger: &Debugger, expr: &str, exp_type: &str, exp_val| {
+            assert_scalar(
+                &eval(debugger, expr).unwrap(),
+                "result",
+                exp_type,
+                Some(exp_val),
+            )
+        };
+
+        assert_result(&debugger, "int32 * 2 + 1", "i32", SupportedScalar::I32(5));
+        assert_result(
+            &debugger,
+            "uint64 << 2 | 1",
+            "u64",
+            SupportedScalar::U64(17),
+        );
+        assert_result(&debugger, "-int8 as u8 - 1", "u8", SupportedScalar::U8(254));
+        assert_result(
+            &debugger,
+            "(int128 - 1) / 2 % 3",
+            "i128",
+            SupportedScalar::I128(1),
+        );
+        assert_result(&debugger, "f64 * 2.0", "f64", SupportedScalar::F64(2.4));
+        assert_result(
+            &debugger,
+            "char_ascii as u32 - 'a' as u32",
+            "u32",
+            SupportedScalar::U32(0),
+        );
+        assert_result(&debugger, "300 as u8", "u8", SupportedScalar::U8(44));
+        assert_result(
+            &debugger,
+            "int32 >= 2 && !boolean_false",
+            "bool",
+            SupportedScalar::Bool(true),
+        );
+        assert!(eval(&debugger, "int32 + uint8").is_err());
+        assert!(eval(&debugger, "uint8 + 256").is_err());
+        assert!(eval(&debugger, "uint8 - 2").is_err());
+        assert!(eval(&debugger, "int32 / 0").is_err());
+        assert!(eval(&debugger, "unknown + 1").is_err());
+
+        let VariableIR::Pointer(pointer) = eval(&debugger, "&int32").unwrap() else {
+            panic!("not a pointer");
+        };
+        assert_eq!(pointer.type_name.as_deref(), Some("&i32"));
+        assert_eq!(
+            pointer.value.map(|ptr| ptr as usize),
+            read_single_var(&debugger, "int32").raw_address()
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(185));
+
+        let vars = debugger
+            .read_variable(make_select_plan("ns_1::GLOB_3"))
+            .unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_str(&vars[0], "GLOB_3", "glob_3");
+        let vars = debugger
+            .read_variable(make_select_plan("crate::GLOB_3"))
+            .unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_scalar(&vars[0], "GLOB_3", "i32", Some(SupportedScalar::I32(3)));
+
+        assert_result(
+            &debugger,
+            "crate::GLOB_3 * 2",
+            "i32",
+            SupportedScalar::I32(6),
+        );
+        assert_result(
+            &debugger,
+            "vars::ns_1::GLOB_3 == \"glob_3\"",
+            "bool",
+            SupportedScalar::Bool(true),
+        );
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
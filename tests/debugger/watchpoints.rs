@@ -0,0 +1,163 @@
+use crate::common::DebugeeRunInfo;
+use crate::common::TestHooks;
+use crate::debugger_env;
+use crate::{assert_no_proc, MT_APP};
+use bugstalker::debugger::command::expression::SelectPlan;
+use bugstalker::debugger::command::Breakpoints;
+use bugstalker::debugger::variable::{SupportedScalar, VariableIR};
+use bugstalker::debugger::{Debugger, WatchpointKind};
+use serial_test::serial;
+
+fn read_i32(debugger: &Debugger, name: &str) -> Option<SupportedScalar> {
+    let vars = debugger
+        .read_variable(SelectPlan::select_variable(name))
+        .unwrap();
+    let VariableIR::Scalar(scalar) = &vars[0] else {
+        panic!("not a scalar");
+    };
+    scalar.value.clone()
+}
+
+#[test]
+#[serial]
+fn test_watchpoint_on_write() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        debugger.remove_breakpoint(brkpt).unwrap();
+
+        let number = debugger
+            .set_watchpoint(SelectPlan::select_variable("sum"), WatchpointKind::Write)
+            .unwrap();
+
+        // first iteration adds zero, so value not changed
+        debugger.continue_debugee().unwrap();
+        assert_eq!(read_i32(&debugger, "sum"), Some(SupportedScalar::I32(1)));
+        debugger.continue_debugee().unwrap();
+        assert_eq!(read_i32(&debugger, "sum"), Some(SupportedScalar::I32(3)));
+
+        debugger.remove_watchpoint(number).unwrap();
+        assert!(debugger.watchpoints().is_empty());
+        debugger.continue_debugee().unwrap();
+
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_watchpoint_on_write_while_stepping() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+        // first iteration adds zero, so stop at the second one
+        debugger.set_breakpoint_ignore_count(brkpt, 1).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        debugger.remove_breakpoint(brkpt).unwrap();
+
+        let number = debugger
+            .set_watchpoint(SelectPlan::select_variable("sum"), WatchpointKind::Write)
+            .unwrap();
+
+        // store of a new value may belong to the next line
+        let mut steps = 0;
+        while info.watchpoint.get().is_none() && steps < 3 {
+            debugger.step_into().unwrap();
+            steps += 1;
+        }
+        assert_eq!(info.watchpoint.take(), Some(number));
+        assert_eq!(read_i32(&debugger, "sum"), Some(SupportedScalar::I32(1)));
+
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.watchpoint.take(), Some(number));
+        assert_eq!(read_i32(&debugger, "sum"), Some(SupportedScalar::I32(3)));
+
+        debugger.remove_watchpoint(number).unwrap();
+        debugger.continue_debugee().unwrap();
+
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_watchpoint_removed_at_end_of_scope() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 25).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(25));
+        debugger.remove_breakpoint(brkpt).unwrap();
+
+        debugger
+            .set_watchpoint(SelectPlan::select_variable("sum"), WatchpointKind::Write)
+            .unwrap();
+        assert_eq!(debugger.watchpoints().len(), 1);
+
+        // sum is not changed anymore, debugee stops when `sum1` function returns
+        debugger.continue_debugee().unwrap();
+        assert!(debugger.watchpoints().is_empty());
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
+
+#[test]
+#[serial]
+fn test_disable_and_enable_watchpoint() {
+    debugger_env!(MT_APP, child, {
+        let info = DebugeeRunInfo::default();
+        let mut debugger = Debugger::new(MT_APP, child, TestHooks::new(info.clone())).unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 23).unwrap();
+
+        debugger.run_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(23));
+        debugger.remove_breakpoint(brkpt).unwrap();
+
+        let number = debugger
+            .set_watchpoint(SelectPlan::select_variable("sum"), WatchpointKind::Write)
+            .unwrap();
+        let brkpt = debugger.set_breakpoint_at_line("mt.rs", 25).unwrap();
+        assert_eq!(brkpt, number + 1);
+
+        // watchpoints and breakpoints share numbering
+        let disable = format!("{number}");
+        Breakpoints::new(&mut debugger, vec!["disable", &disable])
+            .unwrap()
+            .run()
+            .unwrap();
+        assert!(!debugger.watchpoints()[0].is_enabled());
+        assert!(debugger.breakpoints()[0].enabled);
+
+        // disabled watchpoint is not triggered
+        debugger.continue_debugee().unwrap();
+        assert_eq!(info.line.take(), Some(25));
+        assert_eq!(
+            read_i32(&debugger, "sum"),
+            Some(SupportedScalar::I32(49995000))
+        );
+
+        Breakpoints::new(&mut debugger, vec!["enable", &disable])
+            .unwrap()
+            .run()
+            .unwrap();
+        assert!(debugger.watchpoints()[0].is_enabled());
+
+        // sum is not changed anymore, debugee stops when `sum1` function returns
+        debugger.continue_debugee().unwrap();
+        assert!(debugger.watchpoints().is_empty());
+
+        debugger.continue_debugee().unwrap();
+        assert_no_proc!(child);
+    });
+}
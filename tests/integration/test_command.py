@@ -44,6 +44,23 @@ class CommandTestCase(unittest.TestCase):
         self.debugger.sendline('continue')
         self.debugger.expect('bye!')
 
+    def test_breakpoint_info_disable_delete(self):
+        """List, disable and delete breakpoints by number"""
+        self.debugger.sendline('break hello_world.rs:5')
+        self.debugger.expect('Breakpoint 1 at hello_world.rs:5')
+        self.debugger.sendline('break 0x55555555BD63')
+        self.debugger.expect('Breakpoint 2 at 0x0055555555BD63')
+
+        self.debugger.sendline('disable 1')
+        self.debugger.sendline('info break')
+        self.debugger.expect(r'1\s+breakpoint\s+n\s+\S+\s+hello_world.rs:5')
+        self.debugger.expect(r'2\s+breakpoint\s+y\s+\S+\s+0x0055555555BD63')
+
+        self.debugger.sendline('delete 2')
+        self.debugger.sendline('run')
+        self.debugger.expect('Hello, world!')
+        self.debugger.expect('bye!')
+
     def test_write_register(self):
         """Register writes (by moving pc counter into program start)"""
         self.debugger.sendline('break 0x55555555BD6C')
@@ -49,6 +49,34 @@ class MultithreadTestCase(unittest.TestCase):
         self.debugger.expect('total 249985000')
         self.debugger.expect('Program exit with code: 0')
 
+    def test_multithreaded_conditional_breakpoint(self):
+        """Multithread debugee conditional breakpoint"""
+        self.debugger.sendline('break mt.rs:23 if i == 100')
+        self.debugger.expect('break mt.rs:23 if i == 100')
+
+        self.debugger.sendline('run')
+        self.debugger.expect('Hit breakpoint at address')
+        self.debugger.expect_exact('>        sum += i;')
+        self.debugger.sendline('vars i')
+        self.debugger.expect_exact('i = i32(100)')
+
+    def test_multithreaded_watchpoint(self):
+        """Multithread debugee watchpoint on local variable"""
+        self.debugger.sendline('break mt.rs:23')
+        self.debugger.expect('break mt.rs:23')
+
+        self.debugger.sendline('run')
+        self.debugger.expect('Hit breakpoint at address')
+        self.debugger.sendline('watch sum')
+        self.debugger.expect_exact('Watchpoint 1: sum')
+
+        self.debugger.sendline('continue')
+        self.debugger.expect('Hit breakpoint at address')
+        self.debugger.sendline('continue')
+        self.debugger.expect('Hit watchpoint 1 \\(sum\\)')
+        self.debugger.expect_exact('old value: i32(0)')
+        self.debugger.expect_exact('new value: i32(1)')
+
     def test_multithreaded_backtrace(self):
         """Backtrace command for multithread debugee"""
         self.debugger.sendline('break mt.rs:21')
@@ -0,0 +1,5 @@
+#[no_mangle]
+pub extern "C" fn print_sum(a: i64, b: i64) {
+    let sum = a + b;
+    println!("sum: {sum}");
+}